@@ -1,3 +1,5 @@
+mod rng;
+
 use rand::Rng;
 use std::{
     fs::File,
@@ -7,8 +9,12 @@ use std::{
 const COLLATZ_ITER_NUM: u8 = 100;
 const NUM_ARR_SIZE: usize = 10;
 
-fn powers(x: u64) -> [u64; NUM_ARR_SIZE] {
-    let mut arr = [0u64; NUM_ARR_SIZE];
+fn powers_n<const N: usize>(x: u64) -> [u64; N] {
+    let mut arr = [0u64; N];
+
+    if N == 0 {
+        return arr;
+    }
 
     arr[0] = x;
 
@@ -19,6 +25,10 @@ fn powers(x: u64) -> [u64; NUM_ARR_SIZE] {
     arr
 }
 
+fn powers(x: u64) -> [u64; NUM_ARR_SIZE] {
+    powers_n(x)
+}
+
 fn collatz_single(mut n: u64) -> bool {
     for _ in 0..COLLATZ_ITER_NUM {
         if n == 1 {
@@ -34,18 +44,32 @@ fn collatz_single(mut n: u64) -> bool {
     false
 }
 
-fn check_collatz(arr: &[u64; NUM_ARR_SIZE]) -> [bool; 10] {
-    let mut result = [false; NUM_ARR_SIZE];
-    for i in 0..10 {
-        result[i] = collatz_single(arr[i]);
+fn collatz_converges(mut n: u64, max_iters: u32) -> Option<bool> {
+    for _ in 0..max_iters {
+        if n == 1 {
+            return Some(true);
+        }
+        if n.is_multiple_of(2) {
+            n /= 2;
+        } else {
+            n = n.checked_mul(3)?.checked_add(1)?;
+        }
     }
-    result
+
+    Some(n == 1)
+}
+
+fn check_collatz_n<const N: usize>(arr: &[u64; N]) -> [bool; N] {
+    arr.map(collatz_single)
 }
 
-fn double_loop_tuple_returner(prob: f64) -> (usize, [u8; 10]) {
+fn check_collatz(arr: &[u64; NUM_ARR_SIZE]) -> [bool; NUM_ARR_SIZE] {
+    check_collatz_n(arr)
+}
+
+fn double_loop_tuple_returner(prob: f64, rng: &mut impl Rng) -> (usize, [u8; 10]) {
     let mut idx: usize = 0;
     let mut arr: [u8; NUM_ARR_SIZE] = [0u8; NUM_ARR_SIZE];
-    let mut rng = rand::rng();
     let mut break_count = 0;
 
     'outer: loop {
@@ -97,6 +121,12 @@ fn main() {
 
         println!("Hipoteza Collatza (true/false): {:?}", collatz_results);
 
+        match collatz_converges(new_x, COLLATZ_ITER_NUM.into()) {
+            Some(true) => println!("Ciąg Collatza dla x dochodzi do 1"),
+            Some(false) => println!("Ciąg Collatza dla x nie doszedł do 1 w limicie kroków"),
+            None => println!("Przepełnienie podczas liczenia ciągu Collatza dla x"),
+        }
+
         let mut file = match File::create("xyz.txt") {
             Err(_) => break true,
             Ok(file) => file,
@@ -116,7 +146,85 @@ fn main() {
 
     println!("Wyjście z woli użytkownika");
 
-    let (idx, arr) = double_loop_tuple_returner(0.2);
+    let (idx, arr) = match std::env::args().nth(1).and_then(|seed| seed.parse().ok()) {
+        Some(seed) => double_loop_tuple_returner(0.2, &mut rng::seeded_rng(seed)),
+        None => double_loop_tuple_returner(0.2, &mut rand::rng()),
+    };
 
     println!("Liczba break'ów: {}, stan tablicy {:?}", idx, arr);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collatz_converges_test() {
+        assert_eq!(collatz_converges(1, 0), Some(true));
+        assert_eq!(collatz_converges(6, 100), Some(true));
+        // 27 potrzebuje 111 kroków, żeby dojść do 1
+        assert_eq!(collatz_converges(27, 111), Some(true));
+    }
+
+    #[test]
+    fn collatz_iteration_cap_test() {
+        assert_eq!(collatz_converges(27, 110), Some(false));
+        assert_eq!(collatz_converges(6, 0), Some(false));
+    }
+
+    #[test]
+    fn powers_n_test() {
+        assert_eq!(powers_n::<5>(3), [3, 9, 27, 81, 243]);
+        let p16 = powers_n::<16>(2);
+        assert_eq!(p16[0], 2);
+        assert_eq!(p16[15], 1 << 16);
+        assert_eq!(powers_n::<0>(7), []);
+        assert_eq!(powers(2), powers_n::<NUM_ARR_SIZE>(2));
+    }
+
+    #[test]
+    fn check_collatz_n_test() {
+        let p5 = powers_n::<5>(3);
+        let c5 = check_collatz_n(&p5);
+        for (x, converges) in p5.iter().zip(c5) {
+            assert_eq!(converges, collatz_single(*x));
+        }
+
+        // potęgi dwójki zawsze dochodzą do 1
+        assert_eq!(check_collatz_n(&powers_n::<16>(2)), [true; 16]);
+        assert_eq!(check_collatz(&powers(2)), [true; NUM_ARR_SIZE]);
+    }
+
+    #[test]
+    fn double_loop_same_seed_test() {
+        for seed in [0, 7, 12345] {
+            let first = double_loop_tuple_returner(0.5, &mut rng::seeded_rng(seed));
+            let second = double_loop_tuple_returner(0.5, &mut rng::seeded_rng(seed));
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn double_loop_different_seeds_test() {
+        let results: Vec<_> = (0..10)
+            .map(|seed| double_loop_tuple_returner(0.5, &mut rng::seeded_rng(seed)))
+            .collect();
+        // przy p = 0.5 dziesięć różnych ziaren praktycznie nie może dać tego samego wyniku
+        assert!(results.iter().any(|result| *result != results[0]));
+    }
+
+    #[test]
+    fn double_loop_extreme_probabilities_test() {
+        let mut rng = rng::seeded_rng(1);
+        assert_eq!(double_loop_tuple_returner(1.0, &mut rng), (10, [1; 10]));
+        let mut expected = [0; 10];
+        expected[0] = 1;
+        assert_eq!(double_loop_tuple_returner(0.0, &mut rng), (0, expected));
+    }
+
+    #[test]
+    fn collatz_overflow_test() {
+        assert_eq!(collatz_converges(u64::MAX, 10), None);
+        assert_eq!(collatz_converges(u64::MAX / 3, 10), None);
+    }
+}
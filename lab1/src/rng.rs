@@ -0,0 +1,6 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+// Generator o stałym ziarnie, żeby testy i pomiary dało się powtórzyć.
+pub fn seeded_rng(seed: u64) -> impl Rng {
+    StdRng::seed_from_u64(seed)
+}
@@ -2,8 +2,12 @@
 #![allow(dead_code)]
 
 use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
 use std::thread::{ScopedJoinHandle, scope, spawn};
 
+use lab7::chunk_ranges;
+
 // 1
 
 pub trait Shape {
@@ -36,6 +40,18 @@ pub fn total_area_generic<T: Shape>(items: &[T]) -> f64 {
     items.iter().map(Shape::area).sum()
 }
 
+// Jak `total_area_generic`, ale slice jest dzielony między `threads` wątków.
+pub fn total_area_parallel<T: Shape + Sync>(items: &[T], threads: usize) -> f64 {
+    scope(|s| {
+        let handles: Vec<ScopedJoinHandle<'_, f64>> = chunk_ranges(items.len(), threads)
+            .into_iter()
+            .map(|range| s.spawn(|| total_area_generic(&items[range])))
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
 /// Funkcja sumuje pola figór przekazanych przez slice boxów z dynamicznymi implementorami `Shape`.
 /// Uzupełnij brakujący argument do funkcji.
 #[must_use]
@@ -90,6 +106,23 @@ pub fn apply_all_dyn(seq: &mut [f64], t: &dyn Transform) {
     }
 }
 
+// Funkcja aplikuje `t` do każdego elementu każdego wiersza `grid`
+pub fn apply_all_2d(grid: &mut [Vec<f64>], t: &dyn Transform) {
+    for row in grid {
+        apply_all_dyn(row, t);
+    }
+}
+
+// Jak `apply_all_2d`, ale każdy wiersz przetwarzany jest w osobnym wątku (`std::thread::scope`).
+// Referencja do `t` jest współdzielona między wątkami, więc transformacja musi być `Sync`.
+pub fn apply_all_2d_parallel(grid: &mut [Vec<f64>], t: &(dyn Transform + Sync)) {
+    scope(|s| {
+        for row in grid {
+            s.spawn(move || apply_all_dyn(row, t));
+        }
+    });
+}
+
 // 4
 
 // Funkcja sumuje wszystkie wartości typu i32 w `boxes`
@@ -216,6 +249,117 @@ pub fn pipeline(n: i32, threads: usize) -> i32 {
     })
 }
 
+// Ograniczona kolejka blokująca dla wzorca producent/konsument.
+// `push` czeka, gdy kolejka jest pełna, `pop_blocking` czeka, gdy jest pusta.
+// Po `close` kolejka nie przyjmuje nowych elementów, a `pop_blocking` zwraca `None`,
+// gdy zostanie opróżniona.
+pub struct WorkQueue<T> {
+    state: Mutex<WorkQueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+}
+
+struct WorkQueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+impl<T> WorkQueue<T> {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "WorkQueue capacity must be positive");
+
+        Self {
+            state: Mutex::new(WorkQueueState {
+                items: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+        }
+    }
+
+    // Zwraca element z powrotem, jeśli kolejka została zamknięta.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let mut state = self
+            .not_full
+            .wait_while(self.state.lock().unwrap(), |state| {
+                !state.closed && state.items.len() >= self.capacity
+            })
+            .unwrap();
+
+        if state.closed {
+            return Err(item);
+        }
+
+        state.items.push_back(item);
+        self.not_empty.notify_one();
+
+        Ok(())
+    }
+
+    pub fn pop_blocking(&self) -> Option<T> {
+        let mut state = self
+            .not_empty
+            .wait_while(self.state.lock().unwrap(), |state| {
+                !state.closed && state.items.is_empty()
+            })
+            .unwrap();
+
+        let item = state.items.pop_front();
+        self.not_full.notify_one();
+
+        item
+    }
+
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+// Wariant `pipeline` korzystający z `WorkQueue` zamiast `mpsc`.
+// Kolejka jest zamykana dopiero po zakończeniu wszystkich producentów.
+#[must_use]
+pub fn pipeline_queue(n: i32, threads: usize) -> i32 {
+    let queue = WorkQueue::new(16);
+
+    scope(|s| {
+        let consumer = s.spawn(|| {
+            let mut sum = 0;
+
+            while let Some(num) = queue.pop_blocking() {
+                sum += num;
+            }
+
+            sum
+        });
+
+        let producers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    for num in 1..=n {
+                        if queue.push(num).is_err() {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        queue.close();
+
+        consumer.join().unwrap()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -239,6 +383,26 @@ mod tests {
         assert!(approx_eq(total, expected, 1e-12));
     }
 
+    #[test]
+    fn test_total_area_parallel() {
+        let rects: Vec<Rect> = (1..=50)
+            .map(|i| Rect {
+                w: i as f64,
+                h: 2.0,
+            })
+            .collect();
+        let expected = total_area_generic(&rects);
+
+        for threads in [0, 1, 4, 7, 64] {
+            assert!(approx_eq(
+                total_area_parallel(&rects, threads),
+                expected,
+                1e-9
+            ));
+        }
+        assert_eq!(total_area_parallel::<Circle>(&[], 4), 0.0);
+    }
+
     #[test]
     fn test_total_area_dyn_mixed() {
         let items: Vec<Box<dyn Shape>> = vec![
@@ -273,6 +437,32 @@ mod tests {
         assert_eq!(mul.name(), "mul");
     }
 
+    #[test]
+    fn test_apply_all_2d_add() {
+        let mut grid = vec![vec![1.0, 2.0, 3.0], vec![-1.0, 0.0, 0.5]];
+        apply_all_2d(&mut grid, &Add { k: 1.0 });
+        assert_eq!(grid, vec![vec![2.0, 3.0, 4.0], vec![0.0, 1.0, 1.5]]);
+    }
+
+    #[test]
+    fn test_apply_all_2d_parallel_matches_sequential() {
+        let mut sequential = vec![vec![1.0, 2.0, 3.0], vec![], vec![-1.0, 0.0, 0.5]];
+        let mut parallel = sequential.clone();
+        let mul = Mul { k: 3.0 };
+        apply_all_2d(&mut sequential, &mul);
+        apply_all_2d_parallel(&mut parallel, &mul);
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel[0], vec![3.0, 6.0, 9.0]);
+    }
+
+    #[test]
+    fn test_apply_all_2d_empty_grid() {
+        let mut grid: Vec<Vec<f64>> = Vec::new();
+        apply_all_2d(&mut grid, &Add { k: 1.0 });
+        apply_all_2d_parallel(&mut grid, &Add { k: 1.0 });
+        assert!(grid.is_empty());
+    }
+
     #[test]
     fn test_sum_all_i32_mixed_any() {
         let boxes: Vec<Box<dyn Any>> = vec![
@@ -322,4 +512,74 @@ mod tests {
         let s = pipeline(10, 3);
         assert_eq!(s, 165); // 3 * 55
     }
+
+    #[test]
+    fn test_pipeline_queue_matches_pipeline() {
+        assert_eq!(pipeline_queue(3, 2), 12);
+        assert_eq!(pipeline_queue(10, 3), pipeline(10, 3));
+        assert_eq!(pipeline_queue(10, 0), 0);
+    }
+
+    #[test]
+    fn test_work_queue_receives_every_item_once() {
+        let queue = WorkQueue::new(2);
+        let producers = 4;
+        let per_producer = 50;
+
+        let mut received = std::thread::scope(|s| {
+            let consumer = s.spawn(|| {
+                let mut received = Vec::new();
+                while let Some(item) = queue.pop_blocking() {
+                    received.push(item);
+                }
+                received
+            });
+
+            let handles: Vec<_> = (0..producers)
+                .map(|p| {
+                    let queue = &queue;
+                    s.spawn(move || {
+                        for i in 0..per_producer {
+                            queue.push(p * per_producer + i).unwrap();
+                        }
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+            queue.close();
+
+            consumer.join().unwrap()
+        });
+
+        received.sort_unstable();
+        assert_eq!(received, (0..producers * per_producer).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_work_queue_close_unblocks_consumer() {
+        let queue: WorkQueue<i32> = WorkQueue::new(1);
+
+        std::thread::scope(|s| {
+            let consumer = s.spawn(|| queue.pop_blocking());
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            queue.close();
+            assert_eq!(consumer.join().unwrap(), None);
+        });
+
+        assert_eq!(queue.push(1), Err(1));
+    }
+
+    #[test]
+    fn test_work_queue_drains_after_close() {
+        let queue = WorkQueue::new(3);
+        queue.push(1).unwrap();
+        queue.push(2).unwrap();
+        queue.close();
+        assert_eq!(queue.pop_blocking(), Some(1));
+        assert_eq!(queue.pop_blocking(), Some(2));
+        assert_eq!(queue.pop_blocking(), None);
+    }
 }
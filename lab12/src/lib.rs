@@ -64,6 +64,70 @@ where
     }
 }
 
+pub struct TableMachine<S> {
+    map: std::collections::HashMap<S, S>,
+}
+
+impl<S> TableMachine<S>
+where
+    S: Clone + Eq + std::hash::Hash,
+{
+    pub fn new(transitions: Vec<(S, S)>) -> Self {
+        Self {
+            map: transitions.into_iter().collect(),
+        }
+    }
+
+    pub fn builder() -> TableMachineBuilder<S> {
+        TableMachineBuilder {
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn run(&self, start: S, max_steps: usize) -> Vec<S> {
+        let mut visited = vec![start.clone()];
+        let mut state = start;
+
+        for _ in 0..max_steps {
+            let Some(next) = self.step(state) else {
+                break;
+            };
+
+            visited.push(next.clone());
+            state = next;
+        }
+
+        visited
+    }
+}
+
+impl<S> StateMachine<S> for TableMachine<S>
+where
+    S: Clone + Eq + std::hash::Hash,
+{
+    fn step(&self, state: S) -> Option<S> {
+        self.map.get(&state).cloned()
+    }
+}
+
+pub struct TableMachineBuilder<S> {
+    transitions: Vec<(S, S)>,
+}
+
+impl<S> TableMachineBuilder<S>
+where
+    S: Clone + Eq + std::hash::Hash,
+{
+    pub fn transition(mut self, from: S, to: S) -> Self {
+        self.transitions.push((from, to));
+        self
+    }
+
+    pub fn build(self) -> TableMachine<S> {
+        TableMachine::new(self.transitions)
+    }
+}
+
 pub fn join_machines<'a, S, M1, M2>(x: M1, y: M2) -> Vec<Box<dyn StateMachine<S> + 'a>>
 where
     M1: StateMachine<S> + 'a,
@@ -179,4 +243,65 @@ mod tests {
         // Sprawdzamy drugą maszynę
         assert_eq!(combined[1].step("Middle"), Some("End"));
     }
+
+    // --- 5. Testy TableMachine ---
+
+    #[test]
+    fn test_table_machine_from_vec() {
+        let machine = TableMachine::new(vec![
+            (string!("idle"), string!("running")),
+            (string!("running"), string!("paused")),
+            (string!("paused"), string!("running")),
+        ]);
+
+        assert_eq!(machine.step(string!("idle")), Some(string!("running")));
+        assert_eq!(machine.step(string!("running")), Some(string!("paused")));
+        assert_eq!(machine.step(string!("paused")), Some(string!("running")));
+        assert_eq!(machine.step(string!("stopped")), None);
+    }
+
+    #[test]
+    fn test_table_machine_builder_and_run() {
+        let machine = TableMachine::<String>::builder()
+            .transition(string!("red"), string!("green"))
+            .transition(string!("green"), string!("yellow"))
+            .transition(string!("yellow"), string!("off"))
+            .build();
+
+        assert_eq!(
+            machine.run(string!("red"), 10),
+            vec![
+                string!("red"),
+                string!("green"),
+                string!("yellow"),
+                string!("off")
+            ]
+        );
+
+        // Ostatnie przejście wygrywa, tak jak przy wstawianiu do HashMap
+        let overridden = TableMachine::<String>::builder()
+            .transition(string!("a"), string!("b"))
+            .transition(string!("a"), string!("c"))
+            .build();
+        assert_eq!(overridden.step(string!("a")), Some(string!("c")));
+    }
+
+    #[test]
+    fn test_table_machine_run_stops_after_max_steps_on_cycle() {
+        let machine = TableMachine::new(vec![
+            (string!("ping"), string!("pong")),
+            (string!("pong"), string!("ping")),
+        ]);
+
+        assert_eq!(
+            machine.run(string!("ping"), 3),
+            vec![
+                string!("ping"),
+                string!("pong"),
+                string!("ping"),
+                string!("pong")
+            ]
+        );
+        assert_eq!(machine.run(string!("ping"), 0), vec![string!("ping")]);
+    }
 }
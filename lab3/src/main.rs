@@ -160,6 +160,37 @@ impl E {
         ret
     }
 
+    fn is_numeric(&self, value: i64) -> bool {
+        matches!(self, E::Const(Const::Numeric(n)) if *n == value)
+    }
+
+    fn normalize(self) -> Box<Self> {
+        match self {
+            E::Add(e, e1) => E::add(e.normalize(), e1.normalize()),
+            E::Mul(e, e1) => E::mul(e.normalize(), e1.normalize()),
+            E::Neg(e) => {
+                let e = E::neg(e.normalize()).unneg();
+                match *e {
+                    E::Neg(inner) if inner.is_numeric(0) => inner,
+                    _ => e,
+                }
+            }
+            E::Inv(e) => {
+                let e = E::inv(e.normalize()).uninv();
+                match *e {
+                    E::Inv(inner) if inner.is_numeric(1) => inner,
+                    _ => e,
+                }
+            }
+            E::Func { name, arg } => E::func(name, arg.normalize()),
+            e => Box::new(e),
+        }
+    }
+
+    fn diff_normalized(self, by: Var) -> Box<Self> {
+        self.diff(by).normalize()
+    }
+
     fn substitute(self, name: &str, value: Box<Self>) -> Box<Self> {
         match self {
             E::Add(e, e1) => E::add(
@@ -231,6 +262,12 @@ fn main() {
         }
     );
     println!("All invs removed: {}", many_invs.uninv().to_string());
+
+    let nested = E::neg(E::neg(E::neg(E::inv(E::inv(E::var(Var::X))))));
+    println!(
+        "Normalized diff: {}",
+        nested.diff_normalized(Var::X).to_string()
+    );
 }
 #[cfg(test)]
 mod tests {
@@ -430,6 +467,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalize_collapses_nested_neg_and_inv() {
+        let expr = E::neg(E::neg(E::inv(E::inv(E::neg(E::var(Var::X))))));
+        assert_eq!(expr.normalize().to_string(), "-(X)");
+
+        let expr = E::add(
+            E::neg(E::constant(Const::Numeric(0))),
+            E::inv(E::constant(Const::Numeric(1))),
+        );
+        assert_eq!(expr.normalize().to_string(), "(0 + 1)");
+    }
+
+    #[test]
+    fn test_diff_normalized_nested_neg() {
+        let expr = E::neg(E::neg(E::neg(E::var(Var::X))));
+        assert_eq!(expr.clone().diff(Var::X).to_string(), "-(-(-(1)))");
+        assert_eq!(expr.diff_normalized(Var::X).to_string(), "-(1)");
+
+        let expr = E::neg(E::neg(E::mul(E::var(Var::X), E::neg(E::var(Var::Y)))));
+        assert_eq!(
+            expr.diff_normalized(Var::X).to_string(),
+            "((1 * -(Y)) + (X * 0))"
+        );
+    }
+
+    #[test]
+    fn test_diff_normalized_drops_neg_zero() {
+        let expr = E::add(E::var(Var::X), E::neg(E::var(Var::Y)));
+        assert_eq!(expr.clone().diff(Var::X).to_string(), "(1 + -(0))");
+        assert_eq!(expr.diff_normalized(Var::X).to_string(), "(1 + 0)");
+    }
+
     #[test]
     fn test_arg_count_zeroary() {
         assert_eq!(E::constant(Const::Numeric(1)).arg_count(), 0);
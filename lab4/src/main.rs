@@ -5,8 +5,14 @@ use std::num::NonZero;
 use std::os::unix::ffi::OsStrExt;
 use std::path::PathBuf;
 use std::str::{FromStr, from_utf8};
-use std::time::Instant;
-use std::{fs, hint, io};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use std::{fs, hint, io, thread};
+
+const MAX_PATH_LEN: usize = 1024;
+const DEFAULT_ADDR: &str = "localhost:8080";
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
 
 fn divisors(n: NonZero<u32>) -> BTreeSet<NonZero<u32>> {
     let mut result = BTreeSet::<NonZero<u32>>::new();
@@ -27,15 +33,24 @@ fn divisors(n: NonZero<u32>) -> BTreeSet<NonZero<u32>> {
     result
 }
 
-fn assert_sorted(buf: &[i32]) {
-    let vector = Vec::<i32>::from(buf);
+// `divisors` skips 1 and n, so 1 is added back for every n > 1.
+fn aliquot_sum(n: NonZero<u32>) -> u64 {
+    let sum: u64 = divisors(n).iter().map(|d| u64::from(d.get())).sum();
 
-    for window in vector.windows(2) {
-        if window[0] <= window[1] {
-            continue;
-        }
+    if n.get() > 1 { sum + 1 } else { sum }
+}
+
+fn is_perfect(n: NonZero<u32>) -> bool {
+    aliquot_sum(n) == u64::from(n.get())
+}
+
+fn is_sorted<T: PartialOrd>(buf: &[T]) -> bool {
+    buf.windows(2).all(|window| window[0] <= window[1])
+}
 
-        panic!();
+fn assert_sorted<T: PartialOrd>(buf: &[T]) {
+    if !is_sorted(buf) {
+        panic!("Buffer is not sorted");
     }
 }
 
@@ -99,14 +114,61 @@ fn bulk_write(stream: &mut TcpStream, buf: &[u8]) -> io::Result<()> {
     Ok(())
 }
 
-fn handle_client(mut stream: TcpStream) -> io::Result<()> {
+fn parse_path_len(header: &[u8]) -> Option<usize> {
+    if header.len() != 4 || !header.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let path_len: usize = from_utf8(header).ok()?.parse().ok()?;
+
+    (path_len <= MAX_PATH_LEN).then_some(path_len)
+}
+
+#[derive(Clone)]
+struct ServerStats {
+    requests_served: Arc<AtomicUsize>,
+    logger: Arc<dyn Fn(&str) + Send + Sync>,
+}
+
+impl ServerStats {
+    fn new(logger: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self {
+            requests_served: Arc::new(AtomicUsize::new(0)),
+            logger: Arc::new(logger),
+        }
+    }
+
+    fn log(&self, event: &str) {
+        (self.logger)(event);
+    }
+
+    fn requests_served(&self) -> usize {
+        self.requests_served.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for ServerStats {
+    fn default() -> Self {
+        Self::new(|event| println!("{}", event))
+    }
+}
+
+fn handle_client(mut stream: TcpStream, stats: &ServerStats) -> io::Result<()> {
     loop {
         let mut path: Vec<u8> = Vec::new();
 
         loop {
             let path_len = bulk_read(&mut stream, 4)?;
-            let path_len_str = from_utf8(&path_len[..]).unwrap_or("0000");
-            let path_len_usize: usize = path_len_str.trim_start_matches('0').parse().unwrap_or(0);
+
+            if path_len.is_empty() {
+                return Ok(());
+            }
+
+            let Some(path_len_usize) = parse_path_len(&path_len) else {
+                bulk_write(&mut stream, "Bad length\n".as_bytes())?;
+                return Ok(());
+            };
+
             let mut read_bytes = bulk_read(&mut stream, path_len_usize)?;
 
             if read_bytes.len() < path_len_usize {
@@ -134,13 +196,13 @@ fn handle_client(mut stream: TcpStream) -> io::Result<()> {
             }
         };
 
-        println!("Valid path: {:?}", path_str);
+        stats.log(&format!("Valid path: {:?}", path_str));
         let mut response = Vec::<u8>::new();
         let read_dir = match fs::read_dir(path_buf) {
             Ok(read_dir) => read_dir,
             Err(e) => {
                 bulk_write(&mut stream, "Bad dir\n".as_bytes())?;
-                println!("Error occured while reading dir: {}\n", e);
+                stats.log(&format!("Error occured while reading dir: {}", e));
                 return Ok(());
             }
         };
@@ -149,7 +211,7 @@ fn handle_client(mut stream: TcpStream) -> io::Result<()> {
             let direntry = match direntry {
                 Err(e) => {
                     bulk_write(&mut stream, "Bad dir\n".as_bytes())?;
-                    println!("Error occured while reading direntry: {}\n", e);
+                    stats.log(&format!("Error occured while reading direntry: {}", e));
                     return Ok(());
                 }
 
@@ -161,8 +223,72 @@ fn handle_client(mut stream: TcpStream) -> io::Result<()> {
 
         bulk_write(&mut stream, &response)?;
 
-        println!("Response written successfully");
+        stats.requests_served.fetch_add(1, Ordering::Relaxed);
+        stats.log("Response written successfully");
+    }
+}
+
+fn bind_server(addr: &str) -> io::Result<TcpListener> {
+    let listener = TcpListener::bind(addr)?;
+
+    println!("Listening on {}", listener.local_addr()?);
+
+    Ok(listener)
+}
+
+fn serve(listener: TcpListener, stats: ServerStats) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+
+        stats.log("New client");
+
+        if let Err(e) = handle_client(stream, &stats) {
+            stats.log(&format!("Error occured in handle_client: {}", e))
+        };
+
+        stats.log(&format!("Requests served: {}", stats.requests_served()));
     }
+
+    Ok(())
+}
+
+fn run_server(addr: &str) -> io::Result<()> {
+    serve(bind_server(addr)?, ServerStats::default())
+}
+
+fn connect_with_retries(addr: &str, retries: u32) -> io::Result<TcpStream> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+
+    loop {
+        match TcpStream::connect(addr) {
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused && attempt < retries => {
+                thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn request_listing(addr: &str, path: &str, retries: u32) -> io::Result<Vec<String>> {
+    if path.len() > MAX_PATH_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path longer than {} bytes", MAX_PATH_LEN),
+        ));
+    }
+
+    let mut stream = connect_with_retries(addr, retries)?;
+
+    bulk_write(&mut stream, format!("{:04}{}", path.len(), path).as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    Ok(response.lines().map(str::to_string).collect())
 }
 
 fn main() {
@@ -174,16 +300,234 @@ fn main() {
     let val = NonZero::new(12).unwrap();
     let divs = divisors(val);
     println!("Divisors of {}: {:?}", val, divs);
+    println!("{} is perfect: {}", val, is_perfect(val));
 
-    let listener = TcpListener::bind("localhost:8080").unwrap();
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
 
-    for stream in listener.incoming() {
-        let stream = stream.unwrap();
+    if let Some(path) = std::env::args().nth(2) {
+        match request_listing(&addr, &path, 3) {
+            Ok(entries) => entries.iter().for_each(|entry| println!("{}", entry)),
+            Err(e) => println!("Client error: {}", e),
+        }
+        return;
+    }
+
+    if let Err(e) = run_server(&addr) {
+        println!("Server error: {}", e);
+    }
+}
 
-        println!("New client");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if let Err(e) = handle_client(stream) {
-            println!("Error occured in handle_client: {}", e)
-        };
+    #[test]
+    fn is_sorted_i32_test() {
+        assert!(is_sorted(&[1, 2, 2, 3]));
+        assert!(!is_sorted(&[3, 1, 2]));
+    }
+
+    #[test]
+    fn is_sorted_str_test() {
+        assert!(is_sorted(&["apple", "banana", "cherry"]));
+        assert!(!is_sorted(&["banana", "apple"]));
+    }
+
+    #[test]
+    fn is_sorted_f64_test() {
+        assert!(is_sorted(&[-1.5, 0.0, 2.25]));
+        assert!(!is_sorted(&[2.0, 1.0]));
+        // NaN is not comparable to anything, so any pair containing it is out of order
+        assert!(!is_sorted(&[1.0, f64::NAN, 2.0]));
+        assert!(!is_sorted(&[f64::NAN, f64::NAN]));
+    }
+
+    #[test]
+    fn is_sorted_trivial_test() {
+        let empty: [i32; 0] = [];
+
+        assert!(is_sorted(&empty));
+        assert!(is_sorted(&[42]));
+        assert!(is_sorted(&[f64::NAN]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn assert_sorted_panics_test() {
+        assert_sorted(&[2, 1]);
+    }
+
+    fn send_to_server(request: &[u8]) -> Vec<u8> {
+        let listener = TcpListener::bind("localhost:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_client(stream, &ServerStats::new(|_| {}))
+        });
+
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(request).unwrap();
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).unwrap();
+
+        server.join().unwrap().unwrap();
+        response
+    }
+
+    #[test]
+    fn server_binds_to_ephemeral_port_test() {
+        let listener = bind_server("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        assert_ne!(address.port(), 0);
+
+        std::thread::spawn(move || serve(listener, ServerStats::new(|_| {})));
+
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(b"9999").unwrap();
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).unwrap();
+
+        assert_eq!(response, b"Bad length\n");
+    }
+
+    #[test]
+    fn run_server_reports_bad_address_test() {
+        assert!(run_server("not an address").is_err());
+    }
+
+    #[test]
+    fn stats_count_served_requests_test() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let events_sink = events.clone();
+        let stats =
+            ServerStats::new(move |event| events_sink.lock().unwrap().push(event.to_string()));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server_stats = stats.clone();
+
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_client(stream, &server_stats)
+        });
+
+        let dir = std::env::temp_dir();
+        let dir = dir.to_str().unwrap();
+        let request = format!("{:04}{}", dir.len(), dir);
+
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).unwrap();
+
+        server.join().unwrap().unwrap();
+
+        assert_eq!(stats.requests_served(), 2);
+        // every served request logs the valid path and the written response
+        assert_eq!(events.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn parse_path_len_test() {
+        assert_eq!(parse_path_len(b"0000"), Some(0));
+        assert_eq!(parse_path_len(b"0012"), Some(12));
+        assert_eq!(parse_path_len(b"1024"), Some(MAX_PATH_LEN));
+        assert_eq!(parse_path_len(b"1025"), None);
+        assert_eq!(parse_path_len(b"9999"), None);
+        assert_eq!(parse_path_len(b"12ab"), None);
+        assert_eq!(parse_path_len(b"-001"), None);
+        assert_eq!(parse_path_len(b"12"), None);
+    }
+
+    #[test]
+    fn oversized_length_prefix_is_rejected_test() {
+        let response = send_to_server(b"9999");
+
+        assert_eq!(response, b"Bad length\n");
+    }
+
+    #[test]
+    fn malformed_length_prefix_is_rejected_test() {
+        let response = send_to_server(b"+1e9");
+
+        assert_eq!(response, b"Bad length\n");
+    }
+
+    #[test]
+    fn request_listing_test() {
+        let dir = std::env::temp_dir().join(format!("lab4_listing_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::write(dir.join("b.txt"), "b").unwrap();
+
+        let listener = bind_server("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || serve(listener, ServerStats::new(|_| {})));
+
+        let mut entries = request_listing(&address, dir.to_str().unwrap(), 0).unwrap();
+        entries.sort();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(entries, vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn request_listing_rejects_long_path_test() {
+        let path = "a".repeat(MAX_PATH_LEN + 1);
+        let err = request_listing("127.0.0.1:1", &path, 0).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn request_listing_retries_until_server_is_up_test() {
+        // free the port so the server can bind it only after the first attempt fails
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .to_string();
+
+        let err = request_listing(&address, "/", 0).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+
+        let server_address = address.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            let listener = bind_server(&server_address).unwrap();
+            serve(listener, ServerStats::new(|_| {}))
+        });
+
+        let start = Instant::now();
+        let dir = std::env::temp_dir();
+        let entries = request_listing(&address, dir.to_str().unwrap(), 8);
+
+        assert!(entries.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn perfect_numbers_test() {
+        assert!(is_perfect(NonZero::new(6).unwrap()));
+        assert!(is_perfect(NonZero::new(28).unwrap()));
+        assert!(!is_perfect(NonZero::new(12).unwrap()));
+    }
+
+    #[test]
+    fn aliquot_sum_test() {
+        assert_eq!(aliquot_sum(NonZero::new(1).unwrap()), 0);
+        assert_eq!(aliquot_sum(NonZero::new(12).unwrap()), 16);
+        assert_eq!(aliquot_sum(NonZero::new(7).unwrap()), 1);
+        assert_eq!(aliquot_sum(NonZero::new(28).unwrap()), 28);
     }
 }
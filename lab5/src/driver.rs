@@ -0,0 +1,249 @@
+// Prosty tekstowy format programów, jedna instrukcja w linii, wyrażenia w
+// notacji prefiksowej:
+//   set x 3
+//   repeat 2 set x add x 1
+//   print mul x x
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::SplitWhitespace;
+
+use super::{Context, Expr, Stmt, add, assign, block, constant, mul, nothing, sub};
+
+pub type Sink = Rc<RefCell<Vec<u64>>>;
+
+impl Expr for Box<dyn Expr> {
+    fn exec_expr(&mut self, context: &Context) -> u64 {
+        (**self).exec_expr(context)
+    }
+}
+
+impl Stmt for Box<dyn Stmt> {
+    fn exec_stmt(&mut self, context: &Context) {
+        (**self).exec_stmt(context);
+    }
+
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        (**self).eval_stmt(context, output);
+    }
+}
+
+// `print` z programu tekstowego trafia do `sink` zamiast na stdout.
+struct Emit {
+    inner: Box<dyn Expr>,
+    sink: Sink,
+}
+
+impl Stmt for Emit {
+    fn exec_stmt(&mut self, context: &Context) {
+        let value = self.inner.exec_expr(context);
+        self.sink.borrow_mut().push(value);
+    }
+}
+
+// `Repeat` bierze liczbę powtórzeń jako parametr const, tu znamy ją dopiero
+// po wczytaniu programu.
+struct RepeatTimes {
+    times: u32,
+    inner: Box<dyn Stmt>,
+}
+
+impl Stmt for RepeatTimes {
+    fn exec_stmt(&mut self, context: &Context) {
+        for _ in 0..self.times {
+            self.inner.exec_stmt(context);
+        }
+    }
+
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        for _ in 0..self.times {
+            self.inner.eval_stmt(context, output);
+        }
+    }
+}
+
+// Nazwy znane w danym miejscu programu: z kontekstu albo z wcześniejszego `set`.
+// Program nie ma rozgałęzień, więc sprawdzenie przy parsowaniu wystarcza, żeby
+// `Constant` nigdy nie trafił na brakującą nazwę.
+struct Names<'c> {
+    context: &'c Context,
+    assigned: Vec<&'static str>,
+}
+
+impl Names<'_> {
+    fn resolve(&self, name: &str) -> Option<&'static str> {
+        match self.context.get_key_value(name) {
+            Some((known, _)) => Some(*known),
+            None => self.assigned.iter().copied().find(|known| *known == name),
+        }
+    }
+
+    // `Context` trzyma `&'static str`, więc wyciekamy tylko nowe nazwy z `set`,
+    // każdą raz.
+    fn define(&mut self, name: &str) -> &'static str {
+        self.resolve(name).unwrap_or_else(|| {
+            let name: &'static str = String::from(name).leak();
+            self.assigned.push(name);
+            name
+        })
+    }
+}
+
+pub fn parse_program(
+    source: &str,
+    sink: &Sink,
+    context: &Context,
+) -> Result<Box<dyn Stmt>, String> {
+    let mut names = Names {
+        context,
+        assigned: Vec::new(),
+    };
+    let mut stmts = Vec::new();
+
+    for line in source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let mut tokens = line.split_whitespace();
+        stmts.push(parse_stmt(&mut tokens, sink, &mut names)?);
+        if let Some(extra) = tokens.next() {
+            return Err(format!("Unexpected token: {extra}"));
+        }
+    }
+
+    Ok(Box::new(block(stmts)))
+}
+
+fn parse_stmt(
+    tokens: &mut SplitWhitespace,
+    sink: &Sink,
+    names: &mut Names,
+) -> Result<Box<dyn Stmt>, String> {
+    match tokens.next() {
+        Some("print") => Ok(Box::new(Emit {
+            inner: parse_expr(tokens, names)?,
+            sink: Rc::clone(sink),
+        })),
+        Some("set") => {
+            let name = tokens.next().ok_or("Missing variable name")?;
+            let inner = parse_expr(tokens, names)?;
+            Ok(Box::new(assign(names.define(name), inner)))
+        }
+        Some("repeat") => {
+            let times = tokens.next().ok_or("Missing repeat count")?;
+            let times = times
+                .parse()
+                .map_err(|_| format!("Invalid repeat count: {times}"))?;
+            let assigned_before = names.assigned.len();
+            let inner = parse_stmt(tokens, sink, names)?;
+            // `repeat 0` niczego nie przypisuje
+            if times == 0 {
+                names.assigned.truncate(assigned_before);
+            }
+            Ok(Box::new(RepeatTimes { times, inner }))
+        }
+        Some("nothing") => Ok(Box::new(nothing())),
+        Some(other) => Err(format!("Unknown statement: {other}")),
+        None => Err("Missing statement".to_string()),
+    }
+}
+
+fn parse_expr(tokens: &mut SplitWhitespace, names: &Names) -> Result<Box<dyn Expr>, String> {
+    match tokens.next() {
+        Some("add") => Ok(Box::new(add(
+            parse_expr(tokens, names)?,
+            parse_expr(tokens, names)?,
+        ))),
+        Some("sub") => Ok(Box::new(sub(
+            parse_expr(tokens, names)?,
+            parse_expr(tokens, names)?,
+        ))),
+        Some("mul") => Ok(Box::new(mul(
+            parse_expr(tokens, names)?,
+            parse_expr(tokens, names)?,
+        ))),
+        Some(token) => match token.parse::<u64>() {
+            Ok(value) => Ok(Box::new(value)),
+            Err(_) => match names.resolve(token) {
+                Some(name) => Ok(Box::new(constant(name))),
+                None => Err(format!("Unknown constant: {token}")),
+            },
+        },
+        None => Err("Missing expression".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context_from_pairs, eval_program};
+
+    fn run(source: &str, context: &Context) -> Vec<u64> {
+        let sink = Sink::default();
+        let mut program = parse_program(source, &sink, context).unwrap();
+        eval_program(&mut program, context);
+        sink.take()
+    }
+
+    #[test]
+    fn parse_program_prints_expressions() {
+        let context = context_from_pairs(&[("limit", 5)]);
+        let output = run(
+            "print 7\n\nprint add limit 1\n# komentarz\nprint mul 2 sub 10 3",
+            &context,
+        );
+        assert_eq!(output, vec![7, 6, 14]);
+    }
+
+    #[test]
+    fn parse_program_repeat_and_set() {
+        let source = "
+            set x 1
+            repeat 3 set x mul x 2
+            print x
+            repeat 2 print add x 1
+        ";
+        assert_eq!(run(source, &Context::new()), vec![8, 9, 9]);
+    }
+
+    #[test]
+    fn parse_program_reports_errors() {
+        let sink = Sink::default();
+        let context = Context::new();
+        assert_eq!(
+            parse_program("jump 3", &sink, &context).err(),
+            Some("Unknown statement: jump".to_string())
+        );
+        assert_eq!(
+            parse_program("repeat x print 1", &sink, &context).err(),
+            Some("Invalid repeat count: x".to_string())
+        );
+        assert_eq!(
+            parse_program("print add 1", &sink, &context).err(),
+            Some("Missing expression".to_string())
+        );
+        assert_eq!(
+            parse_program("print 1 2", &sink, &context).err(),
+            Some("Unexpected token: 2".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_program_rejects_unknown_names() {
+        let sink = Sink::default();
+        let context = context_from_pairs(&[("limit", 5)]);
+        let unknown = |source| parse_program(source, &sink, &context).err();
+
+        assert_eq!(unknown("print y"), Some("Unknown constant: y".to_string()));
+        // prawa strona `set` liczy się przed przypisaniem
+        assert_eq!(
+            unknown("set x add x 1"),
+            Some("Unknown constant: x".to_string())
+        );
+        assert_eq!(
+            unknown("repeat 0 set x 1\nprint x"),
+            Some("Unknown constant: x".to_string())
+        );
+        assert_eq!(unknown("set x limit\nprint add x limit"), None);
+    }
+}
@@ -1,13 +1,59 @@
 use std::collections::HashMap;
 
+mod driver;
+
 type Context = HashMap<&'static str, u64>;
 
 trait Expr {
     fn exec_expr(&mut self, context: &Context) -> u64;
 }
 
+trait TryExpr {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String>;
+}
+
 trait Stmt {
     fn exec_stmt(&mut self, context: &Context);
+
+    // Named writes go to `output` instead of `&mut u64` destinations and are
+    // visible to later statements as constants.
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        self.exec_stmt(&with_output(context, output));
+    }
+}
+
+fn with_output(context: &Context, output: &Context) -> Context {
+    let mut context = context.clone();
+    context.extend(output.iter().map(|(name, value)| (*name, *value)));
+    context
+}
+
+fn context_from_pairs(pairs: &[(&'static str, u64)]) -> Context {
+    pairs.iter().copied().collect()
+}
+
+fn context_from_env(prefix: &str) -> Context {
+    context_from_vars(prefix, std::env::vars())
+}
+
+// Zmienne `PREFIX_NAZWA=liczba` trafiają do kontekstu jako `NAZWA`; nazwy są
+// wyciekane, bo `Context` trzyma `&'static str`.
+fn context_from_vars(prefix: &str, vars: impl IntoIterator<Item = (String, String)>) -> Context {
+    let prefix = format!("{prefix}_");
+
+    vars.into_iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(&prefix)?;
+            let value = value.parse::<u64>().ok()?;
+            Some((&*String::from(name).leak(), value))
+        })
+        .collect()
+}
+
+fn eval_program(stmt: &mut dyn Stmt, context: &Context) -> Context {
+    let mut output = Context::new();
+    stmt.eval_stmt(context, &mut output);
+    output
 }
 
 struct Print<T: Expr> {
@@ -44,12 +90,39 @@ impl<T: Stmt, U: Stmt> Stmt for Seq<T, U> {
         self.first.exec_stmt(context);
         self.second.exec_stmt(context);
     }
+
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        self.first.eval_stmt(context, output);
+        self.second.eval_stmt(context, output);
+    }
 }
 
 fn seq<T: Stmt, U: Stmt>(first: T, second: U) -> Seq<T, U> {
     Seq { first, second }
 }
 
+struct Block {
+    stmts: Vec<Box<dyn Stmt>>,
+}
+
+impl Stmt for Block {
+    fn exec_stmt(&mut self, context: &Context) {
+        for stmt in self.stmts.iter_mut() {
+            stmt.exec_stmt(context);
+        }
+    }
+
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        for stmt in self.stmts.iter_mut() {
+            stmt.eval_stmt(context, output);
+        }
+    }
+}
+
+fn block(stmts: Vec<Box<dyn Stmt>>) -> impl Stmt {
+    Block { stmts }
+}
+
 impl<T: Stmt> Seq<T, Nothing> {
     fn shorten_1(self) -> T {
         self.first
@@ -74,6 +147,12 @@ impl Expr for u64 {
     }
 }
 
+impl TryExpr for u64 {
+    fn try_exec_expr(&mut self, _context: &Context) -> Result<u64, String> {
+        Ok(*self)
+    }
+}
+
 struct When<T: Expr, U: Expr, V: Expr> {
     condition: T,
     if_true: U,
@@ -90,6 +169,21 @@ impl<T: Expr, U: Expr, V: Expr> Expr for When<T, U, V> {
     }
 }
 
+impl<T, U, V> TryExpr for When<T, U, V>
+where
+    T: Expr + TryExpr,
+    U: Expr + TryExpr,
+    V: Expr + TryExpr,
+{
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        if self.condition.try_exec_expr(context)? == 0 {
+            return self.if_false.try_exec_expr(context);
+        }
+
+        self.if_true.try_exec_expr(context)
+    }
+}
+
 fn when<T: Expr, U: Expr, V: Expr>(condition: T, if_true: U, if_false: V) -> When<T, U, V> {
     When {
         condition,
@@ -108,6 +202,12 @@ impl<const N: u32, T: Stmt> Stmt for Repeat<N, T> {
             self.inner.exec_stmt(context);
         }
     }
+
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        for _ in 0..N {
+            self.inner.eval_stmt(context, output);
+        }
+    }
 }
 
 fn repeat<const N: u32, T: Stmt>(inner: T) -> Repeat<N, T> {
@@ -124,6 +224,15 @@ impl Expr for Constant {
     }
 }
 
+impl TryExpr for Constant {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        context
+            .get(self.name)
+            .copied()
+            .ok_or_else(|| format!("Unknown constant: {}", self.name))
+    }
+}
+
 fn constant(name: &'static str) -> Constant {
     Constant { name }
 }
@@ -138,6 +247,12 @@ impl<'a> Expr for ReadFrom<'a> {
     }
 }
 
+impl<'a> TryExpr for ReadFrom<'a> {
+    fn try_exec_expr(&mut self, _context: &Context) -> Result<u64, String> {
+        Ok(*self.from)
+    }
+}
+
 fn read_from<'a>(from: &'a u64) -> ReadFrom<'a> {
     ReadFrom { from }
 }
@@ -154,6 +269,13 @@ impl<'a, T: Expr> Expr for SaveIn<'a, T> {
     }
 }
 
+impl<'a, T: Expr + TryExpr> TryExpr for SaveIn<'a, T> {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        *self.destination = self.inner.try_exec_expr(context)?;
+        Ok(*self.destination)
+    }
+}
+
 fn save_in<'a, T: Expr>(destination: &'a mut u64, inner: T) -> SaveIn<'a, T> {
     SaveIn { destination, inner }
 }
@@ -175,6 +297,17 @@ impl<'a, T: Expr> Expr for Volatile<'a, T> {
     }
 }
 
+impl<'a, T: Expr + TryExpr> TryExpr for Volatile<'a, T> {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        let mut context = context.clone();
+
+        context.insert(self.name, *self.destination);
+
+        *self.destination = self.inner.try_exec_expr(&context)?;
+        Ok(*self.destination)
+    }
+}
+
 fn volatile<'a, T: Expr>(
     destination: &'a mut u64,
     name: &'static str,
@@ -187,6 +320,26 @@ fn volatile<'a, T: Expr>(
     }
 }
 
+struct Assign<T: Expr> {
+    name: &'static str,
+    inner: T,
+}
+
+impl<T: Expr> Stmt for Assign<T> {
+    fn exec_stmt(&mut self, context: &Context) {
+        self.inner.exec_expr(context);
+    }
+
+    fn eval_stmt(&mut self, context: &Context, output: &mut Context) {
+        let value = self.inner.exec_expr(&with_output(context, output));
+        output.insert(self.name, value);
+    }
+}
+
+fn assign<T: Expr>(name: &'static str, inner: T) -> Assign<T> {
+    Assign { name, inner }
+}
+
 struct Add<T: Expr, U: Expr> {
     arg1: T,
     arg2: U,
@@ -198,6 +351,16 @@ impl<T: Expr, U: Expr> Expr for Add<T, U> {
     }
 }
 
+impl<T: Expr + TryExpr, U: Expr + TryExpr> TryExpr for Add<T, U> {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        let arg1 = self.arg1.try_exec_expr(context)?;
+        let arg2 = self.arg2.try_exec_expr(context)?;
+
+        arg1.checked_add(arg2)
+            .ok_or_else(|| format!("Overflow in {} + {}", arg1, arg2))
+    }
+}
+
 fn add<T: Expr, U: Expr>(arg1: T, arg2: U) -> Add<T, U> {
     Add { arg1, arg2 }
 }
@@ -207,9 +370,22 @@ struct Sub<T: Expr, U: Expr> {
     arg2: U,
 }
 
+// Sub saturates at 0 instead of panicking; use try_exec_expr to detect underflow.
 impl<T: Expr, U: Expr> Expr for Sub<T, U> {
     fn exec_expr(&mut self, context: &Context) -> u64 {
-        self.arg1.exec_expr(context) - self.arg2.exec_expr(context)
+        self.arg1
+            .exec_expr(context)
+            .saturating_sub(self.arg2.exec_expr(context))
+    }
+}
+
+impl<T: Expr + TryExpr, U: Expr + TryExpr> TryExpr for Sub<T, U> {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        let arg1 = self.arg1.try_exec_expr(context)?;
+        let arg2 = self.arg2.try_exec_expr(context)?;
+
+        arg1.checked_sub(arg2)
+            .ok_or_else(|| format!("Underflow in {} - {}", arg1, arg2))
     }
 }
 
@@ -228,6 +404,16 @@ impl<T: Expr, U: Expr> Expr for Mul<T, U> {
     }
 }
 
+impl<T: Expr + TryExpr, U: Expr + TryExpr> TryExpr for Mul<T, U> {
+    fn try_exec_expr(&mut self, context: &Context) -> Result<u64, String> {
+        let arg1 = self.arg1.try_exec_expr(context)?;
+        let arg2 = self.arg2.try_exec_expr(context)?;
+
+        arg1.checked_mul(arg2)
+            .ok_or_else(|| format!("Overflow in {} * {}", arg1, arg2))
+    }
+}
+
 fn mul<T: Expr, U: Expr>(arg1: T, arg2: U) -> Mul<T, U> {
     Mul { arg1, arg2 }
 }
@@ -241,9 +427,9 @@ fn main() {
     let mut b1: u64 = 0;
     let c1: u64 = 69;
 
-    // context with one constant
-    let mut context = Context::new();
-    context.insert("limit", 5); // constant used later
+    // context with one constant, overridable with LAB5_limit=<n>
+    let mut context = context_from_pairs(&[("limit", 5)]); // constant used later
+    context.extend(context_from_env("LAB5"));
 
     // program:
     // repeat 5 times:
@@ -306,6 +492,16 @@ fn main() {
 
     factorial.exec_stmt(&context);
 
+    let mut countdown: Vec<Box<dyn Stmt>> = Vec::new();
+
+    for i in (1..=3u64).rev() {
+        countdown.push(Box::new(print(i)));
+    }
+
+    println!("Countdown: ");
+
+    block(countdown).exec_stmt(&context);
+
     let nothing1 = seq(nothing(), print(5));
     let nothing2 = seq(print(6), nothing());
     let nothing3 = seq(nothing(), nothing());
@@ -313,6 +509,48 @@ fn main() {
     nothing1.shorten_2();
     nothing2.shorten_1();
     nothing3.collapse();
+
+    let mut owned_fibonacci = seq(
+        block(vec![
+            Box::new(assign("prev1", 0u64)),
+            Box::new(assign("prev2", 1u64)),
+        ]),
+        repeat::<3, _>(seq(
+            assign("next", add(constant("prev1"), constant("prev2"))),
+            seq(
+                assign("prev1", constant("prev2")),
+                assign("prev2", constant("next")),
+            ),
+        )),
+    );
+
+    let output = eval_program(&mut owned_fibonacci, &context);
+    println!("Owned Fibonacci: {}", output["prev1"]);
+
+    // program tekstowy z pliku podanego jako argument, bez rekompilacji
+    let source = match std::env::args().nth(1) {
+        Some(path) => match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Cannot read {}: {}", path, err);
+                std::process::exit(1);
+            }
+        },
+        None => "set x limit\nrepeat 2 set x mul x 2\nprint x".to_string(),
+    };
+    let sink = driver::Sink::default();
+    match driver::parse_program(&source, &sink, &context) {
+        Ok(mut program) => {
+            eval_program(&mut program, &context);
+            println!("Text program: {:?}", sink.borrow());
+        }
+        Err(err) => println!("Text program error: {}", err),
+    }
+
+    match add(constant("limit"), constant("missing")).try_exec_expr(&context) {
+        Ok(value) => println!("Checked result: {}", value),
+        Err(err) => println!("Checked evaluation failed: {}", err),
+    }
 }
 
 #[cfg(test)]
@@ -359,6 +597,29 @@ mod tests {
         assert_eq!(*calls.borrow(), 1);
     }
 
+    #[test]
+    fn context_from_pairs_builds_context() {
+        let ctx = context_from_pairs(&[("x", 1), ("y", 2), ("x", 3)]);
+        assert_eq!(ctx, HashMap::from([("x", 3), ("y", 2)]));
+        assert!(context_from_pairs(&[]).is_empty());
+    }
+
+    #[test]
+    fn context_from_vars_reads_prefixed_numbers() {
+        // kontrolowana mapa zamiast prawdziwego środowiska
+        let vars = HashMap::from([
+            ("LAB5_limit".to_string(), "5".to_string()),
+            ("LAB5_x".to_string(), "42".to_string()),
+            ("LAB5_name".to_string(), "abc".to_string()),
+            ("LAB5_neg".to_string(), "-1".to_string()),
+            ("OTHER_y".to_string(), "7".to_string()),
+            ("LAB5".to_string(), "9".to_string()),
+        ]);
+
+        let ctx = context_from_vars("LAB5", vars);
+        assert_eq!(ctx, HashMap::from([("limit", 5), ("x", 42)]));
+    }
+
     #[test]
     fn nothing_struct_does_nothing() {
         let ctx = HashMap::from([("x", 0), ("y", 0)]);
@@ -383,6 +644,31 @@ mod tests {
         assert_eq!(&*log.borrow(), &["first", "second"]);
     }
 
+    #[test]
+    fn block_executes_statements_in_order() {
+        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut stmts: Vec<Box<dyn Stmt>> = Vec::new();
+
+        for label in ["first", "second", "third"] {
+            stmts.push(Box::new(Recorder {
+                label,
+                log: log.clone(),
+            }));
+        }
+
+        let mut b = block(stmts);
+        b.exec_stmt(&ctx);
+        assert_eq!(&*log.borrow(), &["first", "second", "third"]);
+    }
+
+    #[test]
+    fn empty_block_does_nothing() {
+        let ctx = HashMap::from([("x", 0), ("y", 0)]);
+        let mut b = block(Vec::new());
+        b.exec_stmt(&ctx);
+    }
+
     #[test]
     fn seq_shorten_1_discards_trailing_nothing_and_returns_first() {
         let ctx = HashMap::from([("x", 0), ("y", 0)]);
@@ -448,6 +734,50 @@ mod tests {
         assert_eq!(program.exec_expr(&ctx), 123);
     }
 
+    #[test]
+    fn try_exec_reports_missing_constant() {
+        let ctx = HashMap::from([("x", 1)]);
+        let mut program = add(
+            constant("x"),
+            when(constant("x"), constant("missing"), 0u64),
+        );
+        assert_eq!(
+            program.try_exec_expr(&ctx),
+            Err("Unknown constant: missing".to_string())
+        );
+    }
+
+    #[test]
+    fn try_exec_evaluates_program() {
+        let ctx = HashMap::from([("x", 3), ("y", 0)]);
+        let mut dst: u64 = 0;
+        let mut program = save_in(
+            &mut dst,
+            mul(add(constant("x"), 2u64), when(constant("y"), 0u64, 4u64)),
+        );
+        assert_eq!(program.try_exec_expr(&ctx), Ok(20));
+        assert_eq!(dst, 20);
+    }
+
+    #[test]
+    fn try_exec_reports_underflow() {
+        let ctx = HashMap::new();
+        let mut program = sub(1u64, 2u64);
+        assert!(program.try_exec_expr(&ctx).is_err());
+        let mut program = sub(2u64, 5u64);
+        assert_eq!(
+            program.try_exec_expr(&ctx),
+            Err("Underflow in 2 - 5".to_string())
+        );
+    }
+
+    #[test]
+    fn sub_struct_saturates_at_zero() {
+        let ctx = HashMap::new();
+        assert_eq!(sub(2u64, 5u64).exec_expr(&ctx), 0);
+        assert_eq!(sub(5u64, 2u64).exec_expr(&ctx), 3);
+    }
+
     #[test]
     fn readfrom_struct_returns_value() {
         let ctx = HashMap::new();
@@ -482,6 +812,45 @@ mod tests {
         assert_eq!(a, 7);
     }
 
+    #[test]
+    fn eval_program_captures_named_writes() {
+        let ctx = HashMap::from([("x", 3)]);
+        let mut program = seq(
+            assign("a", add(constant("x"), 1u64)),
+            repeat::<2, _>(assign("a", mul(constant("a"), 2u64))),
+        );
+        let output = eval_program(&mut program, &ctx);
+        assert_eq!(output, HashMap::from([("a", 16)]));
+        // kontekst wejściowy pozostaje nietknięty
+        assert_eq!(ctx, HashMap::from([("x", 3)]));
+    }
+
+    #[test]
+    fn eval_program_makes_writes_visible_to_later_statements() {
+        let ctx = HashMap::new();
+        let calls = Rc::new(RefCell::new(0u32));
+        let stmts: Vec<Box<dyn Stmt>> = vec![
+            Box::new(assign("a", 5u64)),
+            Box::new(assign("b", when(constant("a"), 1u64, 2u64))),
+            Box::new(print(CounterExpr {
+                calls: calls.clone(),
+                value: 0,
+            })),
+        ];
+        let mut program = block(stmts);
+        let output = eval_program(&mut program, &ctx);
+        assert_eq!(output, HashMap::from([("a", 5), ("b", 1)]));
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn assign_without_eval_program_writes_nothing() {
+        let ctx = HashMap::from([("x", 1)]);
+        let mut program = assign("a", constant("x"));
+        program.exec_stmt(&ctx);
+        assert_eq!(eval_program(&mut nothing(), &ctx), HashMap::new());
+    }
+
     // Nesting tests
     #[test]
     fn nesting_when_inside_when_structs() {
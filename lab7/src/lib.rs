@@ -1,5 +1,7 @@
 use itertools::Itertools;
 use std::collections::HashSet;
+use std::ops::{Add, Mul, Range, Rem};
+use std::thread::scope;
 
 // Nie zmieniaj ciała tej funkcji — jedynie typy.
 pub fn wrap_call(f1: impl Fn(u32) -> u32, f2: impl FnOnce(u32, u32) -> u32) -> u32 {
@@ -20,11 +22,23 @@ pub fn sum_squares_odd_loop(list: &[u32]) -> u32 {
 }
 
 pub fn sum_squares_odd(list: &[u32]) -> u32 {
+    sum_squares_odd_generic(list)
+}
+
+// Działa dla dowolnego typu całkowitego (również ze znakiem — liczby ujemne
+// nieparzyste też się liczą). Przepełnienie zachowuje się jak zwykła
+// arytmetyka typu T: panika w trybie debug, zawinięcie w release.
+pub fn sum_squares_odd_generic<T>(list: &[T]) -> T
+where
+    T: Copy + PartialEq + From<u8> + Rem<Output = T> + Mul<Output = T> + Add<Output = T>,
+{
+    let zero = T::from(0);
+    let two = T::from(2);
+
     list.iter()
         .copied()
-        .filter(|el| el % 2 == 1)
-        .map(|el| el * el)
-        .sum()
+        .filter(|&el| el % two != zero)
+        .fold(zero, |sum, el| sum + el * el)
 }
 
 pub fn vertices_loop(edges: &[(u32, u32)]) -> Vec<u32> {
@@ -52,6 +66,11 @@ pub fn vertices(edges: &[(u32, u32)]) -> Vec<u32> {
         .collect::<Vec<u32>>()
 }
 
+// Suma dwóch list krawędzi skierowanych, bez duplikatów, posortowana po (src, dst).
+pub fn merge_edges(a: &[(u32, u32)], b: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    a.iter().chain(b).copied().sorted().dedup().collect()
+}
+
 // Zwraca posortowany rosnąco wektor wierzchołków uczestniczących w jakimkolwiek
 // cyklu długości 2 (u->v oraz v->u, u!=v), bez duplikatów.
 pub fn cycles_2_loop(edges: &[(u32, u32)]) -> Vec<u32> {
@@ -91,6 +110,45 @@ pub fn cycles_2(edges: &[(u32, u32)]) -> Vec<u32> {
         .collect::<Vec<u32>>()
 }
 
+// Pary (min, max) wierzchołków tworzących cykl długości 2, bez duplikatów, posortowane.
+pub fn mutual_pairs(edges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let set: HashSet<(u32, u32)> = edges.iter().copied().collect();
+
+    edges
+        .iter()
+        .filter(|(u, v)| u < v && set.contains(&(*v, *u)))
+        .copied()
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+// Macierz sąsiedztwa n×n; krawędzie z wierzchołkami spoza 0..n są pomijane.
+pub fn to_adjacency_matrix(edges: &[(u32, u32)], n: u32) -> Vec<Vec<bool>> {
+    let mut matrix = vec![vec![false; n as usize]; n as usize];
+
+    for &(u, v) in edges.iter().filter(|(u, v)| *u < n && *v < n) {
+        matrix[u as usize][v as usize] = true;
+    }
+
+    matrix
+}
+
+// Ułamek możliwych krawędzi skierowanych (n*n, z pętlami) obecnych w grafie.
+pub fn edge_density(edges: &[(u32, u32)], n: u32) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+
+    let present = to_adjacency_matrix(edges, n)
+        .iter()
+        .flatten()
+        .filter(|&&edge| edge)
+        .count();
+
+    present as f64 / (n as f64 * n as f64)
+}
+
 fn is_prime(candidate: u32) -> bool {
     let limit = (candidate as f64).sqrt() as u32;
 
@@ -103,6 +161,21 @@ fn is_prime(candidate: u32) -> bool {
     true
 }
 
+// Pierwiastek liczony całkowitoliczbowo, bez przejścia przez f64.
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n.is_multiple_of(2) {
+        return n == 2;
+    }
+
+    let limit = n.isqrt();
+    (3..=limit)
+        .step_by(2)
+        .all(|divisor| !n.is_multiple_of(divisor))
+}
+
 pub fn primes_loop(n: u32) -> Vec<u32> {
     let mut result = Vec::<u32>::new();
 
@@ -125,6 +198,50 @@ pub fn primes(n: u32) -> Vec<u32> {
         .collect()
 }
 
+// Dzieli `0..len` na co najwyżej `parts` spójnych, niepustych zakresów, których długości
+// różnią się o co najwyżej 1. `parts == 0` traktujemy jak jeden zakres.
+pub fn chunk_ranges(len: usize, parts: usize) -> Vec<Range<usize>> {
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let parts = parts.clamp(1, len);
+    let (base, extra) = (len / parts, len % parts);
+    let mut start = 0;
+
+    (0..parts)
+        .map(|i| {
+            let end = start + base + usize::from(i < extra);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+// Jak `primes`, ale przedział `2..n` jest dzielony między `threads` wątków.
+pub fn primes_parallel(n: u32, threads: usize) -> Vec<u32> {
+    let len = n.saturating_sub(2) as usize;
+
+    scope(|s| {
+        let handles: Vec<_> = chunk_ranges(len, threads)
+            .into_iter()
+            .map(|range| {
+                s.spawn(move || {
+                    (range.start as u32 + 2..range.end as u32 + 2)
+                        .filter(|&candidate| is_prime(candidate))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
 pub fn run_length_encode_loop(list: &[u32]) -> Vec<(u32, usize)> {
     let mut result = Vec::<(u32, usize)>::new();
 
@@ -228,6 +345,23 @@ mod tests {
         assert_eq!(sum_squares_odd(&nums), 35);
     }
 
+    #[test]
+    fn sum_squares_odd_generic_unsigned() {
+        let empty: &[u64] = &[];
+        assert_eq!(sum_squares_odd_generic(empty), 0);
+        assert_eq!(sum_squares_odd_generic(&[2u64, 4, 6]), 0);
+        assert_eq!(sum_squares_odd_generic(&[1u64, 2, 3, 4, 5]), 35);
+        // wynik nie mieściłby się w u32
+        assert_eq!(sum_squares_odd_generic(&[100_001u64]), 10_000_200_001);
+    }
+
+    #[test]
+    fn sum_squares_odd_generic_signed() {
+        assert_eq!(sum_squares_odd_generic(&[1i32, 2, 3, 4, 5]), 35);
+        assert_eq!(sum_squares_odd_generic(&[-1i32, -2, -3, -4, -5]), 35);
+        assert_eq!(sum_squares_odd_generic(&[-3i32, 3, -4]), 18);
+    }
+
     #[test]
     fn vertices_and_cycles() {
         let edges = [(1, 2), (2, 1), (3, 4), (4, 3), (5, 5), (2, 3)];
@@ -241,6 +375,30 @@ mod tests {
         assert_eq!(c_loop, vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    fn merge_edges_overlapping_and_disjoint() {
+        let a = [(3, 1), (1, 2), (2, 1)];
+        let b = [(1, 2), (2, 3), (3, 1)];
+        assert_eq!(merge_edges(&a, &b), vec![(1, 2), (2, 1), (2, 3), (3, 1)]);
+
+        let c = [(5, 6), (4, 4)];
+        assert_eq!(
+            merge_edges(&a, &c),
+            vec![(1, 2), (2, 1), (3, 1), (4, 4), (5, 6)]
+        );
+        // krawędzie skierowane: (1, 2) i (2, 1) to różne krawędzie
+        assert_eq!(merge_edges(&[(1, 2)], &[(2, 1)]), vec![(1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn merge_edges_empty_inputs() {
+        let empty: [(u32, u32); 0] = [];
+        assert_eq!(merge_edges(&empty, &empty), Vec::<(u32, u32)>::new());
+        assert_eq!(merge_edges(&[(2, 1), (2, 1)], &empty), vec![(2, 1)]);
+        assert_eq!(merge_edges(&empty, &[(1, 1)]), vec![(1, 1)]);
+        assert_eq!(vertices(&merge_edges(&[(1, 2)], &[(3, 1)])), vec![1, 2, 3]);
+    }
+
     #[test]
     fn cycles_2_duplicates() {
         let edges = [(1, 2), (2, 1), (1, 2), (2, 1), (2, 2)];
@@ -248,6 +406,68 @@ mod tests {
         assert_eq!(cycles_2(&edges), vec![1, 2]);
     }
 
+    #[test]
+    fn mutual_pairs_with_self_loops() {
+        let edges = [
+            (1, 2),
+            (3, 3),
+            (2, 1),
+            (4, 3),
+            (3, 4),
+            (1, 2),
+            (5, 6),
+            (6, 1),
+            (1, 6),
+            (7, 7),
+        ];
+        assert_eq!(mutual_pairs(&edges), vec![(1, 2), (1, 6), (3, 4)]);
+
+        let mut reversed = edges;
+        reversed.reverse();
+        assert_eq!(mutual_pairs(&reversed), vec![(1, 2), (1, 6), (3, 4)]);
+
+        // wierzchołki par pokrywają się z cycles_2
+        let flattened: Vec<u32> = mutual_pairs(&edges)
+            .into_iter()
+            .flat_map(|(u, v)| [u, v])
+            .sorted()
+            .dedup()
+            .collect();
+        assert_eq!(flattened, cycles_2(&edges));
+    }
+
+    #[test]
+    fn mutual_pairs_only_self_loops() {
+        assert_eq!(mutual_pairs(&[(1, 1), (2, 2)]), Vec::<(u32, u32)>::new());
+        assert_eq!(mutual_pairs(&[]), Vec::<(u32, u32)>::new());
+        assert_eq!(mutual_pairs(&[(9, 8), (8, 9), (9, 8)]), vec![(8, 9)]);
+    }
+
+    #[test]
+    fn adjacency_matrix_small_graph() {
+        let edges = [(0, 1), (1, 2), (2, 0), (1, 2), (2, 2), (3, 0), (0, 7)];
+        let matrix = to_adjacency_matrix(&edges, 3);
+        assert_eq!(
+            matrix,
+            vec![
+                vec![false, true, false],
+                vec![false, false, true],
+                vec![true, false, true],
+            ]
+        );
+        assert_eq!(to_adjacency_matrix(&edges, 0), Vec::<Vec<bool>>::new());
+    }
+
+    #[test]
+    fn edge_density_examples() {
+        let edges = [(0, 1), (1, 2), (2, 0), (1, 2), (2, 2), (3, 0)];
+        // 4 z 9 możliwych krawędzi, (3, 0) spoza zakresu
+        assert!((edge_density(&edges, 3) - 4.0 / 9.0).abs() < 1e-12);
+        assert_eq!(edge_density(&[(0, 0)], 1), 1.0);
+        assert_eq!(edge_density(&[], 4), 0.0);
+        assert_eq!(edge_density(&edges, 0), 0.0);
+    }
+
     #[test]
     fn empty_graph() {
         let edges: [(u32, u32); 0] = [];
@@ -279,6 +499,66 @@ mod tests {
         assert_eq!(p100, primes_loop(100));
     }
 
+    fn assert_chunking(len: usize, parts: usize) {
+        let ranges = chunk_ranges(len, parts);
+
+        assert_eq!(ranges.first().map_or(0, |r| r.start), 0);
+        assert_eq!(ranges.last().map_or(0, |r| r.end), len);
+        assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(ranges.iter().all(|r| !r.is_empty()));
+        assert!(ranges.len() <= parts.max(1));
+
+        let lengths: Vec<usize> = ranges.iter().map(ExactSizeIterator::len).collect();
+        let (min, max) = (lengths.iter().min(), lengths.iter().max());
+        assert!(max.zip(min).is_none_or(|(max, min)| max - min <= 1));
+    }
+
+    #[test]
+    fn chunk_ranges_cover_and_balance() {
+        for len in [0, 1, 2, 7, 10, 64, 101] {
+            for parts in [0, 1, 2, 3, 4, 8, 100, 200] {
+                assert_chunking(len, parts);
+            }
+        }
+
+        assert_eq!(chunk_ranges(10, 3), vec![0..4, 4..7, 7..10]);
+        assert_eq!(chunk_ranges(2, 5), vec![0..1, 1..2]);
+        assert_eq!(chunk_ranges(5, 0), vec![0..5]);
+        assert_eq!(chunk_ranges(0, 4), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn primes_parallel_matches_primes() {
+        for n in [0, 2, 3, 30, 1000] {
+            for threads in [0, 1, 3, 8, 2000] {
+                assert_eq!(
+                    primes_parallel(n, threads),
+                    primes(n),
+                    "n = {n}, threads = {threads}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_prime_u64_small_values() {
+        assert!(!is_prime_u64(0));
+        assert!(!is_prime_u64(1));
+        assert!(is_prime_u64(2));
+        for n in 2..1000u32 {
+            assert_eq!(is_prime_u64(n as u64), is_prime(n), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn is_prime_u64_large_values() {
+        assert!(is_prime_u64(4_294_967_291)); // największa liczba pierwsza < 2^32
+        assert!(!is_prime_u64(4_294_967_295)); // 3 * 5 * 17 * 257 * 65537
+        assert!(!is_prime_u64(65_521 * 65_521));
+        assert!(is_prime_u64((1 << 40) + 15));
+        assert!(!is_prime_u64(1_048_573 * 1_048_583));
+    }
+
     #[test]
     fn wrap_call_fn_ptr() {
         fn times2(x: u32) -> u32 {
@@ -34,43 +34,79 @@ impl<T> DerefMut for HeapOrStack<T> {
     }
 }
 
-struct AustroHungarianGreeter {
+struct MessageCycler {
+    messages: Vec<String>,
     idx: Cell<usize>,
 }
 
-impl AustroHungarianGreeter {
-    fn new() -> Self {
-        Self { idx: Cell::new(0) }
+impl MessageCycler {
+    fn new(messages: Vec<String>) -> Self {
+        Self {
+            messages,
+            idx: Cell::new(0),
+        }
     }
 
-    fn greet(&self) -> &'static str {
-        const MESSAGES: [&str; 3] = [
-            "Es lebe der Kaiser!",
-            "Möge uns der Kaiser schützen!",
-            "Éljen Ferenc József császár!",
-        ];
+    fn next(&self) -> &str {
+        if self.messages.is_empty() {
+            return "";
+        }
 
         let curr_idx = self.idx.get();
-        let return_msg = MESSAGES[curr_idx % MESSAGES.len()];
 
         self.idx.set(curr_idx + 1);
 
-        return_msg
+        &self.messages[curr_idx % self.messages.len()]
+    }
+
+    fn count(&self) -> usize {
+        self.idx.get()
+    }
+}
+
+struct AustroHungarianGreeter {
+    cycler: MessageCycler,
+}
+
+impl AustroHungarianGreeter {
+    fn new() -> Self {
+        Self {
+            cycler: MessageCycler::new(vec![
+                "Es lebe der Kaiser!".to_string(),
+                "Möge uns der Kaiser schützen!".to_string(),
+                "Éljen Ferenc József császár!".to_string(),
+            ]),
+        }
+    }
+
+    fn greet(&self) -> &str {
+        self.cycler.next()
     }
 }
 
 impl Drop for AustroHungarianGreeter {
     fn drop(&mut self) {
-        println!("Ich habe {} mal gegrüßt", self.idx.get());
+        println!("Ich habe {} mal gegrüßt", self.cycler.count());
     }
 }
 
 fn canon_head<'a>(xs: &'a VecDeque<i32>) -> Option<Cow<'a, VecDeque<i32>>> {
+    canon_head_by(xs, |&x| x % 2 != 0)
+}
+
+pub fn first_matching_index<T, F: Fn(&T) -> bool>(xs: &VecDeque<T>, pred: F) -> Option<usize> {
+    xs.iter().position(pred)
+}
+
+fn canon_head_by<T: Clone, F: Fn(&T) -> bool>(
+    xs: &VecDeque<T>,
+    pred: F,
+) -> Option<Cow<'_, VecDeque<T>>> {
     if xs.is_empty() {
         return Some(Cow::Borrowed(xs));
     }
 
-    match xs.iter().position(|&x| x % 2 != 0) {
+    match first_matching_index(xs, pred) {
         Some(0) => Some(Cow::Borrowed(xs)),
         Some(index) => {
             let mut owned_deque = xs.clone();
@@ -162,6 +198,13 @@ impl Vertex {
             .chain(self.out_edges.clone())
             .collect()
     }
+
+    fn live_neighbours(&self) -> Vec<Rc<RefCell<Vertex>>> {
+        self.all_neighbours()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
 }
 
 fn cycle(n: usize) -> Rc<RefCell<Vertex>> {
@@ -192,6 +235,31 @@ fn cycle(n: usize) -> Rc<RefCell<Vertex>> {
     first_vertex
 }
 
+fn bfs_order(start: &Rc<RefCell<Vertex>>, max_depth: usize) -> Vec<i32> {
+    let mut visited = vec![start.clone()];
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+    let mut order = Vec::new();
+
+    while let Some((vertex, depth)) = queue.pop_front() {
+        order.push(vertex.borrow().data);
+
+        if depth == max_depth {
+            continue;
+        }
+
+        for neighbour in vertex.borrow().live_neighbours() {
+            if visited.iter().any(|v| Rc::ptr_eq(v, &neighbour)) {
+                continue;
+            }
+
+            visited.push(neighbour.clone());
+            queue.push_back((neighbour, depth + 1));
+        }
+    }
+
+    order
+}
+
 fn main() {
     let greeter = AustroHungarianGreeter::new();
 
@@ -239,16 +307,154 @@ fn main() {
 
     let mut current_node = cycle(5);
 
+    println!("BFS: {:?}", bfs_order(&current_node, 3));
+
     for _ in 1..10 {
         println!("{}", current_node.borrow().data);
 
         current_node = current_node
             .clone()
             .borrow()
-            .all_neighbours()
+            .live_neighbours()
             .first()
             .unwrap()
-            .upgrade()
-            .unwrap()
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_cycler_wraps_around() {
+        let cycler = MessageCycler::new(vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ]);
+
+        let sequence: Vec<String> = (0..6).map(|_| cycler.next().to_string()).collect();
+
+        assert_eq!(sequence, vec!["a", "b", "c", "d", "a", "b"]);
+        assert_eq!(cycler.count(), 6);
+    }
+
+    #[test]
+    fn message_cycler_empty_messages() {
+        let cycler = MessageCycler::new(Vec::new());
+
+        assert_eq!(cycler.next(), "");
+        assert_eq!(cycler.count(), 0);
+    }
+
+    #[test]
+    fn live_neighbours_skips_dropped_vertices() {
+        let mut vertex = Vertex::new();
+        let owned = vertex.create_neighbor();
+        let linked = Rc::new(RefCell::new(Vertex::new()));
+        linked.borrow_mut().data = 7;
+
+        vertex.link_to(&linked);
+        drop(owned);
+
+        assert_eq!(vertex.all_neighbours().len(), 2);
+        assert_eq!(vertex.live_neighbours().len(), 2);
+
+        drop(linked);
+
+        assert_eq!(vertex.all_neighbours().len(), 2);
+        let live = vertex.live_neighbours();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].borrow().data, 0);
+    }
+
+    #[test]
+    fn canon_head_by_integers() {
+        let leading = VecDeque::from([3, 2, 4]);
+        let rotated = VecDeque::from([2, 4, 6, 3, 2]);
+        let no_match = VecDeque::from([2, 4, 6]);
+
+        assert!(matches!(
+            canon_head_by(&leading, |&x| x % 2 != 0),
+            Some(Cow::Borrowed(_))
+        ));
+        match canon_head_by(&rotated, |&x| x % 2 != 0) {
+            Some(Cow::Owned(deque)) => assert_eq!(deque, VecDeque::from([3, 2, 2, 4, 6])),
+            _ => panic!("Expected owned rotation"),
+        }
+        assert!(canon_head_by(&no_match, |&x| x % 2 != 0).is_none());
+        assert_eq!(
+            canon_head(&rotated),
+            canon_head_by(&rotated, |&x| x % 2 != 0)
+        );
+    }
+
+    #[test]
+    fn first_matching_index_examples() {
+        let numbers = VecDeque::from([2, 4, 7, 9]);
+        assert_eq!(first_matching_index(&numbers, |&x| x % 2 != 0), Some(2));
+        assert_eq!(first_matching_index(&numbers, |&x| x > 100), None);
+
+        let words = VecDeque::from([
+            String::from("a"),
+            String::new(),
+            String::from("b"),
+            String::new(),
+        ]);
+        assert_eq!(first_matching_index(&words, String::is_empty), Some(1));
+
+        let empty: VecDeque<i32> = VecDeque::new();
+        assert_eq!(first_matching_index(&empty, |_| true), None);
+    }
+
+    #[test]
+    fn canon_head_by_strings() {
+        let words = VecDeque::from([
+            String::from("apple"),
+            String::from("Banana"),
+            String::from("cherry"),
+        ]);
+        let is_capitalized = |s: &String| s.starts_with(char::is_uppercase);
+
+        match canon_head_by(&words, is_capitalized) {
+            Some(Cow::Owned(deque)) => {
+                assert_eq!(
+                    deque,
+                    VecDeque::from(["Banana", "cherry", "apple"].map(String::from))
+                )
+            }
+            _ => panic!("Expected owned rotation"),
+        }
+        assert!(matches!(
+            canon_head_by(&words, |s: &String| s.starts_with('a')),
+            Some(Cow::Borrowed(_))
+        ));
+        assert!(canon_head_by(&words, |s: &String| s.is_empty()).is_none());
+    }
+
+    #[test]
+    fn bfs_order_on_cycle() {
+        let start = cycle(5);
+
+        assert_eq!(bfs_order(&start, 0), vec![0]);
+        assert_eq!(bfs_order(&start, 1), vec![0, 1]);
+        assert_eq!(bfs_order(&start, 3), vec![0, 1, 2, 3]);
+        assert_eq!(bfs_order(&start, 4), vec![0, 1, 2, 3, 4]);
+        assert_eq!(bfs_order(&start, 10), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn bfs_order_visits_each_vertex_once() {
+        let start = Rc::new(RefCell::new(Vertex::new()));
+        let left = start.borrow_mut().create_neighbor();
+        let right = start.borrow_mut().create_neighbor();
+        left.borrow_mut().data = 1;
+        right.borrow_mut().data = 2;
+        left.borrow_mut().link_to(&right);
+        right.borrow_mut().link_to(&start);
+
+        assert_eq!(bfs_order(&start, 5), vec![0, 1, 2]);
     }
 }
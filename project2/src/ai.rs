@@ -2,7 +2,10 @@ use bevy::prelude::*;
 
 use crate::{
     InGameStates,
-    ai::{resources::AiProcessing, systems::AiTurnMessage},
+    ai::{
+        resources::{AiActionQueue, AiProcessing},
+        systems::AiTurnMessage,
+    },
     log_error,
     map::systems::army_position_sync_system,
 };
@@ -16,12 +19,18 @@ impl Plugin for AiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            systems::ai_system
-                .pipe(log_error)
-                .after(army_position_sync_system)
+            (
+                systems::ai_system
+                    .pipe(log_error)
+                    .after(army_position_sync_system),
+                systems::ai_action_system,
+            )
+                .chain()
                 .run_if(in_state(InGameStates::AiTurn)),
         )
+        .add_systems(Update, systems::toggle_ai_playback_mode_system)
         .add_message::<AiTurnMessage>()
-        .init_resource::<AiProcessing>();
+        .init_resource::<AiProcessing>()
+        .init_resource::<AiActionQueue>();
     }
 }
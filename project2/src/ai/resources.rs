@@ -1,6 +1,167 @@
+use std::{collections::VecDeque, time::Duration};
+
 use bevy::prelude::*;
 
+use crate::{
+    country::messages::{ChangeRelationMessage, ProposePeaceMessage},
+    map::messages::{BuildBuildingMessage, MoveArmyMessage, SpawnArmyMessage},
+};
+
 #[derive(Resource, Default)]
 pub struct AiProcessing {
     pub country_idx: usize,
 }
+
+pub const AI_ACTION_DELAY_SECS: f32 = 0.4;
+
+// Manhattan distance from own tiles and armies within which the AI sees armies.
+pub const AI_VISION_RADIUS: i32 = 3;
+
+pub enum AiAction {
+    ChangeRelation(ChangeRelationMessage),
+    ProposePeace(ProposePeaceMessage),
+    Build(BuildBuildingMessage),
+    Spawn(SpawnArmyMessage),
+    Move(MoveArmyMessage),
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum AiPlaybackMode {
+    #[default]
+    Instant,
+    Delayed,
+    StepThrough,
+}
+
+impl AiPlaybackMode {
+    pub fn next(self) -> Self {
+        match self {
+            AiPlaybackMode::Instant => AiPlaybackMode::Delayed,
+            AiPlaybackMode::Delayed => AiPlaybackMode::StepThrough,
+            AiPlaybackMode::StepThrough => AiPlaybackMode::Instant,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct AiActionQueue {
+    pub actions: VecDeque<AiAction>,
+    pub mode: AiPlaybackMode,
+    pub timer: Timer,
+}
+
+impl Default for AiActionQueue {
+    fn default() -> Self {
+        Self {
+            actions: VecDeque::new(),
+            mode: AiPlaybackMode::default(),
+            timer: Timer::from_seconds(AI_ACTION_DELAY_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl AiActionQueue {
+    pub fn push(&mut self, action: AiAction) {
+        self.actions.push_back(action);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    pub fn has_move_of(&self, army_entity: Entity) -> bool {
+        self.actions.iter().any(
+            |action| matches!(action, AiAction::Move(msg) if msg.moved_army_entity == army_entity),
+        )
+    }
+
+    pub fn actions_to_run(&mut self, delta: Duration, step_pressed: bool) -> Vec<AiAction> {
+        let ready = match self.mode {
+            AiPlaybackMode::Instant => return self.actions.drain(..).collect(),
+            AiPlaybackMode::Delayed => self.timer.tick(delta).just_finished(),
+            AiPlaybackMode::StepThrough => step_pressed,
+        };
+        if !ready {
+            return Vec::new();
+        }
+        self.actions.pop_front().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::components::GridPosition;
+
+    use super::*;
+
+    fn queue_with_moves(mode: AiPlaybackMode, entities: &[Entity]) -> AiActionQueue {
+        let mut queue = AiActionQueue {
+            mode,
+            ..Default::default()
+        };
+        for (i, &entity) in entities.iter().enumerate() {
+            queue.push(AiAction::Move(MoveArmyMessage {
+                moved_army_entity: entity,
+                target_position: GridPosition::new(i as i32, 0),
+                number_of_units_to_move: 1,
+            }));
+        }
+        queue
+    }
+
+    fn moved_entities(actions: Vec<AiAction>) -> Vec<Entity> {
+        actions
+            .into_iter()
+            .map(|action| match action {
+                AiAction::Move(msg) => msg.moved_army_entity,
+                _ => panic!("Expected move action"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn step_through_dequeues_one_action_per_step_test() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..3).map(|_| world.spawn_empty().id()).collect();
+        let mut queue = queue_with_moves(AiPlaybackMode::StepThrough, &entities);
+
+        assert!(queue.actions_to_run(Duration::ZERO, false).is_empty());
+        for &entity in &entities {
+            let run = moved_entities(queue.actions_to_run(Duration::ZERO, true));
+            assert_eq!(run, vec![entity]);
+        }
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn delayed_mode_waits_for_timer_test() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..2).map(|_| world.spawn_empty().id()).collect();
+        let mut queue = queue_with_moves(AiPlaybackMode::Delayed, &entities);
+        let delay = Duration::from_secs_f32(AI_ACTION_DELAY_SECS);
+
+        assert!(queue.actions_to_run(delay / 2, false).is_empty());
+        assert_eq!(
+            moved_entities(queue.actions_to_run(delay / 2, false)),
+            vec![entities[0]]
+        );
+        assert_eq!(
+            moved_entities(queue.actions_to_run(delay, false)),
+            vec![entities[1]]
+        );
+    }
+
+    #[test]
+    fn instant_mode_runs_all_actions_in_order_test() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..3).map(|_| world.spawn_empty().id()).collect();
+        let mut queue = queue_with_moves(AiPlaybackMode::Instant, &entities);
+
+        assert!(queue.has_move_of(entities[1]));
+        assert_eq!(
+            moved_entities(queue.actions_to_run(Duration::ZERO, false)),
+            entities
+        );
+        assert!(queue.is_empty());
+    }
+}
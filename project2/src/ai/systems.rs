@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{Result, anyhow};
 use bevy::{ecs::system::SystemParam, prelude::*};
@@ -6,8 +6,12 @@ use rand::{Rng, rng};
 
 use crate::{
     InGameStates,
-    ai::resources::AiProcessing,
-    common::{components::GridPosition, messages::NextTurnMessage},
+    ai::resources::{AI_VISION_RADIUS, AiAction, AiActionQueue, AiProcessing},
+    common::{
+        components::GridPosition,
+        grid::{manhattan, neighbors},
+        messages::NextTurnMessage,
+    },
     country::{
         components::OwnershipTile,
         messages::{
@@ -16,7 +20,7 @@ use crate::{
         resources::{Countries, Country, Diplomacy, PeaceOffers, RelationStatus},
     },
     map::{
-        components::{Army, Building, MapTile},
+        components::{Army, Building, MapTile, MapTileType},
         messages::{BuildBuildingMessage, MoveArmyMessage, SpawnArmyMessage},
         resources::{ArmyMovements, MapSettings, TileMapGrid},
     },
@@ -35,21 +39,21 @@ pub struct AiSystemParams<'w, 's> {
     map_settings: Res<'w, MapSettings>,
     tile_grid: Res<'w, TileMapGrid>,
     ai_processing: ResMut<'w, AiProcessing>,
-    army_movements: ResMut<'w, ArmyMovements>,
+    actions: ResMut<'w, AiActionQueue>,
     next_state: ResMut<'w, NextState<InGameStates>>,
-    build_msg: MessageWriter<'w, BuildBuildingMessage>,
-    spawn_msg: MessageWriter<'w, SpawnArmyMessage>,
-    relation_msg: MessageWriter<'w, ChangeRelationMessage>,
-    propose_peace_msg: MessageWriter<'w, ProposePeaceMessage>,
     accept_peace_msg: MessageWriter<'w, AcceptPeaceMessage>,
     reject_peace_msg: MessageWriter<'w, RejectPeaceMessage>,
     peace_offers: Res<'w, PeaceOffers>,
     ownership_tiles: Query<'w, 's, (&'static OwnershipTile, &'static GridPosition)>,
     map_tiles: Query<'w, 's, Has<Building>, With<MapTile>>,
+    land_tiles: Query<'w, 's, (&'static MapTile, &'static GridPosition)>,
     armies: Query<'w, 's, (Entity, &'static Army, &'static GridPosition)>,
 }
 
 pub fn ai_system(mut params: AiSystemParams) -> Result<()> {
+    if !params.actions.is_empty() {
+        return Ok(());
+    }
     let (ownership_map, country_owned_positions) = build_maps(&params.ownership_tiles);
     let country_strengths =
         calculate_country_strengths(&params.countries, &country_owned_positions, &params.armies);
@@ -74,6 +78,51 @@ pub fn ai_system(mut params: AiSystemParams) -> Result<()> {
     Ok(())
 }
 
+#[derive(SystemParam)]
+pub struct AiActionWriters<'w> {
+    army_movements: ResMut<'w, ArmyMovements>,
+    build_msg: MessageWriter<'w, BuildBuildingMessage>,
+    spawn_msg: MessageWriter<'w, SpawnArmyMessage>,
+    relation_msg: MessageWriter<'w, ChangeRelationMessage>,
+    propose_peace_msg: MessageWriter<'w, ProposePeaceMessage>,
+}
+
+pub fn ai_action_system(
+    mut actions: ResMut<AiActionQueue>,
+    time: Res<Time>,
+    button_input: Res<ButtonInput<KeyCode>>,
+    mut writers: AiActionWriters,
+) {
+    let step_pressed = button_input.just_pressed(KeyCode::KeyN);
+    for action in actions.actions_to_run(time.delta(), step_pressed) {
+        match action {
+            AiAction::ChangeRelation(msg) => {
+                writers.relation_msg.write(msg);
+            }
+            AiAction::ProposePeace(msg) => {
+                writers.propose_peace_msg.write(msg);
+            }
+            AiAction::Build(msg) => {
+                writers.build_msg.write(msg);
+            }
+            AiAction::Spawn(msg) => {
+                writers.spawn_msg.write(msg);
+            }
+            AiAction::Move(msg) => writers.army_movements.add_movement(msg),
+        }
+    }
+}
+
+pub fn toggle_ai_playback_mode_system(
+    button_input: Res<ButtonInput<KeyCode>>,
+    mut actions: ResMut<AiActionQueue>,
+) {
+    if button_input.just_pressed(KeyCode::KeyP) {
+        actions.mode = actions.mode.next();
+        println!("Ai playback mode: {:?}", actions.mode);
+    }
+}
+
 fn handle_current_country(
     params: &mut AiSystemParams<'_, '_>,
 ) -> Option<std::result::Result<(), anyhow::Error>> {
@@ -105,8 +154,7 @@ fn process_country_ai(
         &params.diplomacy,
         country_strengths,
         &params.armies,
-        &mut params.relation_msg,
-        &mut params.propose_peace_msg,
+        &mut params.actions,
     )?;
     process_economy(
         (current_country, current_country_idx),
@@ -114,24 +162,83 @@ fn process_country_ai(
         country_owned_positions,
         &params.tile_grid,
         &params.map_tiles,
-        &mut params.build_msg,
+        &mut params.actions,
         ownership_map,
     )?;
-    process_recruitment(
-        (current_country, current_country_idx),
-        &params.map_settings,
+    let building_positions = owned_building_positions(
+        current_country_idx,
         country_owned_positions,
         &params.tile_grid,
-        &mut params.spawn_msg,
+        &params.map_tiles,
+    );
+    let all_army_positions: Vec<(usize, (i32, i32))> = params
+        .armies
+        .iter()
+        .map(|(_, army, pos)| (army.country_idx, (pos.x, pos.y)))
+        .collect();
+    let army_positions = visible_army_positions(
+        current_country_idx,
+        country_owned_positions
+            .get(&current_country_idx)
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        &all_army_positions,
+        AI_VISION_RADIUS,
+    );
+    let threatened_tiles = find_threatened_tiles(
+        current_country_idx,
+        ownership_map,
+        &building_positions,
+        &army_positions,
+        &params.diplomacy,
+    );
+    if threatened_tiles.is_empty() {
+        process_recruitment(
+            (current_country, current_country_idx),
+            &params.map_settings,
+            country_owned_positions,
+            &params.tile_grid,
+            &mut params.actions,
+            ownership_map,
+            &params.armies,
+        )?;
+    } else {
+        process_defense(
+            (current_country, current_country_idx),
+            &params.map_settings,
+            &threatened_tiles,
+            &params.tile_grid,
+            &params.armies,
+            &mut params.actions,
+        )?;
+    }
+    let land_tiles: HashSet<(i32, i32)> = params
+        .land_tiles
+        .iter()
+        .filter(|(tile, _)| tile.tile_type != MapTileType::Water)
+        .map(|(_, pos)| (pos.x, pos.y))
+        .collect();
+    let claimable_tiles = find_claimable_tiles(
+        current_country_idx,
         ownership_map,
+        &land_tiles,
+        &army_positions,
+    );
+    process_expansion(
+        current_country_idx,
+        &claimable_tiles,
         &params.armies,
-    )?;
+        &mut params.actions,
+    );
+    let visible_enemies = visible_enemy_units(current_country_idx, &params.armies, &army_positions);
     process_army_movement(
         current_country_idx,
         &params.armies,
+        &params.map_settings,
         ownership_map,
+        &visible_enemies,
         &params.diplomacy,
-        &mut params.army_movements,
+        &mut params.actions,
     )?;
     Ok(())
 }
@@ -184,8 +291,7 @@ fn process_diplomacy(
     diplomacy: &Diplomacy,
     country_strengths: &HashMap<usize, i32>,
     armies: &Query<(Entity, &Army, &GridPosition)>,
-    relation_msg: &mut MessageWriter<ChangeRelationMessage>,
-    propose_peace_msg: &mut MessageWriter<ProposePeaceMessage>,
+    actions: &mut AiActionQueue,
 ) -> Result<()> {
     for other_idx in 0..countries.countries.len() {
         if country_idx == other_idx {
@@ -193,10 +299,10 @@ fn process_diplomacy(
         }
         match diplomacy.get_relation(country_idx, other_idx) {
             RelationStatus::AtWar => {
-                handle_diplomacy_at_war(country_idx, armies, propose_peace_msg, other_idx);
+                handle_diplomacy_at_war(country_idx, armies, actions, other_idx);
             }
             RelationStatus::Neutral => {
-                handle_diplomacy_neutral(country_idx, country_strengths, relation_msg, other_idx)?;
+                handle_diplomacy_neutral(country_idx, country_strengths, actions, other_idx)?;
             }
         }
     }
@@ -206,7 +312,7 @@ fn process_diplomacy(
 fn handle_diplomacy_neutral(
     country_idx: usize,
     country_strengths: &HashMap<usize, i32>,
-    relation_msg: &mut MessageWriter<'_, ChangeRelationMessage>,
+    actions: &mut AiActionQueue,
     other_idx: usize,
 ) -> Result<(), anyhow::Error> {
     let my_strength = country_strengths
@@ -216,11 +322,11 @@ fn handle_diplomacy_neutral(
         .get(&other_idx)
         .ok_or_else(|| anyhow!("Failed to get other strength"))?;
     let _: () = if my_strength > other_strength && rng().random_bool(0.1) {
-        relation_msg.write(ChangeRelationMessage {
+        actions.push(AiAction::ChangeRelation(ChangeRelationMessage {
             country_a_idx: country_idx,
             country_b_idx: other_idx,
             relation: RelationStatus::AtWar,
-        });
+        }));
     };
     Ok(())
 }
@@ -228,7 +334,7 @@ fn handle_diplomacy_neutral(
 fn handle_diplomacy_at_war(
     country_idx: usize,
     armies: &Query<'_, '_, (Entity, &Army, &GridPosition)>,
-    propose_peace_msg: &mut MessageWriter<'_, ProposePeaceMessage>,
+    actions: &mut AiActionQueue,
     other_idx: usize,
 ) {
     let my_army_strength: i32 = armies
@@ -244,10 +350,10 @@ fn handle_diplomacy_at_war(
 
     if my_army_strength > 0 && my_army_strength < other_army_strength / 2 && rng().random_bool(0.3)
     {
-        propose_peace_msg.write(ProposePeaceMessage {
+        actions.push(AiAction::ProposePeace(ProposePeaceMessage {
             from: country_idx,
             to: other_idx,
-        });
+        }));
     }
 }
 
@@ -257,7 +363,7 @@ fn process_economy(
     country_owned_positions: &CountryOwnedPositionsMap,
     tile_grid: &TileMapGrid,
     map_tiles: &Query<Has<Building>, With<MapTile>>,
-    build_msg: &mut MessageWriter<BuildBuildingMessage>,
+    actions: &mut AiActionQueue,
     ownership_map: &OwnershipMap,
 ) -> Result<()> {
     let (country, country_idx) = country_with_idx;
@@ -273,10 +379,10 @@ fn process_economy(
             .collect();
         if !candidates.is_empty() && rng().random_bool(0.5) {
             let tile_entity = candidates[rng().random_range(0..candidates.len())];
-            build_msg.write(BuildBuildingMessage {
+            actions.push(AiAction::Build(BuildBuildingMessage {
                 tile_entity,
                 country_idx,
-            });
+            }));
         }
     }
     Ok(())
@@ -320,7 +426,7 @@ fn process_recruitment(
     map_settings: &MapSettings,
     country_owned_positions: &CountryOwnedPositionsMap,
     tile_grid: &TileMapGrid,
-    spawn_msg: &mut MessageWriter<SpawnArmyMessage>,
+    actions: &mut AiActionQueue,
     ownership_map: &OwnershipMap,
     armies: &Query<(Entity, &Army, &GridPosition)>,
 ) -> Result<()> {
@@ -345,48 +451,250 @@ fn process_recruitment(
             .ok_or_else(|| anyhow!("Invalid spawn position selected"))?;
         let amount = (country.money as f32 * 0.3 / map_settings.unit_cost as f32) as i32;
         if amount > 0 {
-            spawn_msg.write(SpawnArmyMessage {
+            actions.push(AiAction::Spawn(SpawnArmyMessage {
                 tile_entity: *tile_entity,
                 country_idx,
                 amount,
-            });
+            }));
         }
     }
     Ok(())
 }
 
+fn owned_building_positions(
+    country_idx: usize,
+    country_owned_positions: &CountryOwnedPositionsMap,
+    tile_grid: &TileMapGrid,
+    map_tiles: &Query<Has<Building>, With<MapTile>>,
+) -> HashSet<(i32, i32)> {
+    country_owned_positions
+        .get(&country_idx)
+        .into_iter()
+        .flatten()
+        .filter(|pos| {
+            tile_grid
+                .grid
+                .get(*pos)
+                .is_some_and(|&e| map_tiles.get(e).is_ok_and(|has| has))
+        })
+        .copied()
+        .collect()
+}
+
+fn visible_army_positions(
+    country_idx: usize,
+    owned_positions: &[(i32, i32)],
+    army_positions: &[(usize, (i32, i32))],
+    radius: i32,
+) -> Vec<(usize, (i32, i32))> {
+    let observers: Vec<GridPosition> = owned_positions
+        .iter()
+        .copied()
+        .chain(
+            army_positions
+                .iter()
+                .filter(|(owner, _)| *owner == country_idx)
+                .map(|&(_, pos)| pos),
+        )
+        .map(|(x, y)| GridPosition::new(x, y))
+        .collect();
+
+    army_positions
+        .iter()
+        .filter(|&&(owner, (x, y))| {
+            owner == country_idx
+                || observers
+                    .iter()
+                    .any(|observer| manhattan(observer, &GridPosition::new(x, y)) <= radius)
+        })
+        .copied()
+        .collect()
+}
+
+// Units of the first enemy army on each tile the country can see.
+fn visible_enemy_units(
+    country_idx: usize,
+    armies: &Query<(Entity, &Army, &GridPosition)>,
+    visible_positions: &[(usize, (i32, i32))],
+) -> HashMap<(i32, i32), i32> {
+    let visible: HashSet<(i32, i32)> = visible_positions
+        .iter()
+        .filter(|(owner, _)| *owner != country_idx)
+        .map(|&(_, pos)| pos)
+        .collect();
+
+    let mut units = HashMap::new();
+    for (_, army, pos) in armies.iter() {
+        if army.country_idx != country_idx && visible.contains(&(pos.x, pos.y)) {
+            units.entry((pos.x, pos.y)).or_insert(army.number_of_units);
+        }
+    }
+    units
+}
+
+fn find_threatened_tiles(
+    country_idx: usize,
+    ownership_map: &OwnershipMap,
+    valuable_tiles: &HashSet<(i32, i32)>,
+    army_positions: &[(usize, (i32, i32))],
+    diplomacy: &Diplomacy,
+) -> HashSet<(i32, i32)> {
+    valuable_tiles
+        .iter()
+        .filter(|pos| ownership_map.get(*pos) == Some(&country_idx))
+        .filter(|&&pos| {
+            army_positions.iter().any(|&(owner, army_pos)| {
+                owner != country_idx
+                    && matches!(
+                        diplomacy.get_relation(country_idx, owner),
+                        RelationStatus::AtWar
+                    )
+                    && is_adjacent(pos, army_pos)
+            })
+        })
+        .copied()
+        .collect()
+}
+
+fn is_adjacent(a: (i32, i32), b: (i32, i32)) -> bool {
+    manhattan(&GridPosition::new(a.0, a.1), &GridPosition::new(b.0, b.1)) == 1
+}
+
+fn process_defense(
+    country_with_idx: (&Country, usize),
+    map_settings: &MapSettings,
+    threatened_tiles: &HashSet<(i32, i32)>,
+    tile_grid: &TileMapGrid,
+    armies: &Query<(Entity, &Army, &GridPosition)>,
+    actions: &mut AiActionQueue,
+) -> Result<()> {
+    let (country, country_idx) = country_with_idx;
+    let mut targets: Vec<&(i32, i32)> = threatened_tiles.iter().collect();
+    targets.sort();
+    let mut assigned_armies = HashSet::new();
+    let mut recruited = false;
+
+    for &target in targets {
+        let garrisoned = armies
+            .iter()
+            .any(|(_, army, pos)| army.country_idx == country_idx && (pos.x, pos.y) == target);
+        if garrisoned {
+            continue;
+        }
+
+        let defender = armies.iter().find(|(entity, army, pos)| {
+            army.country_idx == country_idx
+                && !assigned_armies.contains(entity)
+                && !threatened_tiles.contains(&(pos.x, pos.y))
+                && is_adjacent((pos.x, pos.y), target)
+        });
+        if let Some((entity, army, _)) = defender {
+            assigned_armies.insert(entity);
+            actions.push(AiAction::Move(MoveArmyMessage {
+                moved_army_entity: entity,
+                target_position: GridPosition::new(target.0, target.1),
+                number_of_units_to_move: army.number_of_units,
+            }));
+            continue;
+        }
+
+        if recruited || country.money < map_settings.unit_cost * 5 {
+            continue;
+        }
+        let tile_entity = tile_grid
+            .grid
+            .get(&target)
+            .ok_or_else(|| anyhow!("Invalid defense position selected"))?;
+        let amount = (country.money as f32 * 0.3 / map_settings.unit_cost as f32) as i32;
+        if amount > 0 {
+            actions.push(AiAction::Spawn(SpawnArmyMessage {
+                tile_entity: *tile_entity,
+                country_idx,
+                amount,
+            }));
+            recruited = true;
+        }
+    }
+    Ok(())
+}
+
+fn find_claimable_tiles(
+    country_idx: usize,
+    ownership_map: &OwnershipMap,
+    land_tiles: &HashSet<(i32, i32)>,
+    army_positions: &[(usize, (i32, i32))],
+) -> Vec<(i32, i32)> {
+    let mut claimable: Vec<(i32, i32)> = land_tiles
+        .iter()
+        .filter(|pos| !ownership_map.contains_key(*pos))
+        .filter(|&&pos| {
+            army_positions
+                .iter()
+                .any(|&(owner, army_pos)| owner == country_idx && is_adjacent(pos, army_pos))
+        })
+        .copied()
+        .collect();
+    claimable.sort_by_key(|&(x, y)| {
+        (
+            -count_friendly_neighbors(&GridPosition::new(x, y), country_idx, ownership_map),
+            x,
+            y,
+        )
+    });
+    claimable
+}
+
+fn process_expansion(
+    country_idx: usize,
+    claimable_tiles: &[(i32, i32)],
+    armies: &Query<(Entity, &Army, &GridPosition)>,
+    actions: &mut AiActionQueue,
+) {
+    for &target in claimable_tiles {
+        let expanding_army = armies.iter().find(|(entity, army, pos)| {
+            army.country_idx == country_idx
+                && army.number_of_units > 1
+                && !actions.has_move_of(*entity)
+                && is_adjacent((pos.x, pos.y), target)
+        });
+        if let Some((entity, army, _)) = expanding_army {
+            actions.push(AiAction::Move(MoveArmyMessage {
+                moved_army_entity: entity,
+                target_position: GridPosition::new(target.0, target.1),
+                number_of_units_to_move: army.number_of_units / 2,
+            }));
+        }
+    }
+}
+
 fn process_army_movement(
     country_idx: usize,
     armies: &Query<(Entity, &Army, &GridPosition)>,
+    map_settings: &MapSettings,
     ownership_map: &OwnershipMap,
+    visible_enemies: &HashMap<(i32, i32), i32>,
     diplomacy: &Diplomacy,
-    army_movements: &mut ResMut<ArmyMovements>,
+    actions: &mut AiActionQueue,
 ) -> Result<()> {
     for (entity, army, pos) in armies.iter() {
-        if army.country_idx != country_idx {
+        if army.country_idx != country_idx || actions.has_move_of(entity) {
             continue;
         }
 
-        let neighbors = [
-            (pos.x + 1, pos.y),
-            (pos.x - 1, pos.y),
-            (pos.x, pos.y + 1),
-            (pos.x, pos.y - 1),
-        ];
-        let valid_moves: Vec<GridPosition> = neighbors
-            .iter()
-            .filter(|&&(nx, ny)| is_valid_move(nx, ny, country_idx, ownership_map, diplomacy))
-            .map(|&(nx, ny)| GridPosition::new(nx, ny))
-            .collect();
+        let valid_moves: Vec<GridPosition> =
+            neighbors(pos, map_settings.width, map_settings.height, false)
+                .into_iter()
+                .filter(|n| is_valid_move(n.x, n.y, country_idx, ownership_map, diplomacy))
+                .collect();
 
-        if let Ok(target) = select_target(&valid_moves, army, ownership_map, armies)
+        if let Ok(target) = select_target(&valid_moves, army, ownership_map, visible_enemies)
             && target != *pos
         {
-            army_movements.add_movement(MoveArmyMessage {
+            actions.push(AiAction::Move(MoveArmyMessage {
                 moved_army_entity: entity,
                 target_position: target,
                 number_of_units_to_move: army.number_of_units,
-            });
+            }));
         }
     }
     Ok(())
@@ -404,7 +712,7 @@ fn is_valid_move(
             owner == country_idx
                 || matches!(dip.get_relation(country_idx, owner), RelationStatus::AtWar)
         }
-        None => false, // Unowned tiles are water and not walkable
+        None => false, // Unowned land is claimed by expansion, water is not walkable
     }
 }
 
@@ -412,19 +720,14 @@ fn calculate_move_score(
     target_pos: &GridPosition,
     army: &Army,
     ownership_map: &OwnershipMap,
-    armies: &Query<(Entity, &Army, &GridPosition)>,
+    visible_enemies: &HashMap<(i32, i32), i32>,
 ) -> i32 {
     let mut score = 0;
     let target_key = &(target_pos.x, target_pos.y);
-    let enemy_army_on_tile = armies.iter().find(|(_, other_army, other_pos)| {
-        other_pos.x == target_pos.x
-            && other_pos.y == target_pos.y
-            && other_army.country_idx != army.country_idx
-    });
-    if let Some((_, enemy_army, _)) = enemy_army_on_tile {
-        if army.number_of_units > enemy_army.number_of_units {
+    if let Some(&enemy_units) = visible_enemies.get(target_key) {
+        if army.number_of_units > enemy_units {
             score += 1000;
-        } else if army.number_of_units > enemy_army.number_of_units / 2 {
+        } else if army.number_of_units > enemy_units / 2 {
             score += 200;
         } else {
             score -= 1000;
@@ -446,7 +749,7 @@ fn select_target(
     moves: &[GridPosition],
     army: &Army,
     ownership_map: &OwnershipMap,
-    armies: &Query<(Entity, &Army, &GridPosition)>,
+    visible_enemies: &HashMap<(i32, i32), i32>,
 ) -> Result<GridPosition> {
     if moves.is_empty() {
         return Err(anyhow!("No valid moves for army"));
@@ -454,7 +757,9 @@ fn select_target(
 
     moves
         .iter()
-        .max_by_key(|&target_pos| calculate_move_score(target_pos, army, ownership_map, armies))
+        .max_by_key(|&target_pos| {
+            calculate_move_score(target_pos, army, ownership_map, visible_enemies)
+        })
         .copied()
         .ok_or_else(|| anyhow!("Could not determine best move"))
 }
@@ -540,3 +845,148 @@ fn ai_evaluate_peace_offers(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ownership(tiles: &[((i32, i32), usize)]) -> OwnershipMap {
+        tiles.iter().copied().collect()
+    }
+
+    #[test]
+    fn move_score_ignores_hidden_enemy_armies_test() {
+        let ownership_map = ownership(&[((0, 0), 0), ((1, 0), 0)]);
+        let army = Army {
+            country_idx: 0,
+            number_of_units: 10,
+        };
+        let target = GridPosition::new(1, 0);
+
+        let hidden = calculate_move_score(&target, &army, &ownership_map, &HashMap::new());
+        let visible = calculate_move_score(
+            &target,
+            &army,
+            &ownership_map,
+            &HashMap::from([((1, 0), 50)]),
+        );
+
+        assert!(hidden >= 0);
+        assert!(visible < 0);
+        assert_eq!(
+            select_target(
+                &[GridPosition::new(1, 0), GridPosition::new(0, 0)],
+                &army,
+                &ownership_map,
+                &HashMap::from([((1, 0), 50)]),
+            )
+            .unwrap(),
+            GridPosition::new(0, 0)
+        );
+    }
+
+    #[test]
+    fn threatened_tiles_require_adjacent_enemy_at_war_test() {
+        let ownership_map = ownership(&[((0, 0), 0), ((1, 0), 0), ((0, 1), 0), ((2, 0), 1)]);
+        let valuable: HashSet<(i32, i32)> = [(1, 0), (0, 1)].into_iter().collect();
+        let armies = [(1, (2, 0)), (2, (0, 2))];
+
+        let mut diplomacy = Diplomacy::new();
+        diplomacy.set_relation(0, 1, RelationStatus::AtWar);
+
+        let threatened = find_threatened_tiles(0, &ownership_map, &valuable, &armies, &diplomacy);
+
+        assert_eq!(threatened, [(1, 0)].into_iter().collect());
+    }
+
+    #[test]
+    fn threatened_tiles_ignore_unowned_and_friendly_test() {
+        let ownership_map = ownership(&[((0, 0), 0), ((1, 0), 1)]);
+        let valuable: HashSet<(i32, i32)> = [(0, 0), (1, 0)].into_iter().collect();
+        let armies = [(0, (0, 1)), (1, (1, 1))];
+
+        let mut diplomacy = Diplomacy::new();
+        diplomacy.set_relation(0, 1, RelationStatus::AtWar);
+
+        let threatened = find_threatened_tiles(0, &ownership_map, &valuable, &armies, &diplomacy);
+
+        assert!(threatened.is_empty());
+    }
+
+    #[test]
+    fn claimable_tiles_are_unowned_land_next_to_own_armies_test() {
+        let ownership_map = ownership(&[((0, 0), 0), ((1, 1), 0), ((5, 5), 1)]);
+        let land: HashSet<(i32, i32)> = [(0, 0), (1, 0), (0, -1), (1, 1), (5, 6), (4, 5)]
+            .into_iter()
+            .collect();
+        let armies = [(0, (0, 0)), (1, (5, 5))];
+
+        let claimable = find_claimable_tiles(0, &ownership_map, &land, &armies);
+
+        assert_eq!(claimable, vec![(1, 0), (0, -1)]);
+    }
+
+    #[test]
+    fn claimable_tiles_skip_water_and_owned_test() {
+        let ownership_map = ownership(&[((0, 0), 0), ((1, 0), 1)]);
+        let land: HashSet<(i32, i32)> = [(0, 0), (1, 0), (0, 1)].into_iter().collect();
+        let armies = [(0, (0, 0))];
+
+        let claimable = find_claimable_tiles(0, &ownership_map, &land, &armies);
+
+        assert_eq!(claimable, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn visible_armies_exclude_enemies_outside_vision_test() {
+        let owned = [(0, 0), (1, 0)];
+        let armies = [
+            (0, (1, 0)),
+            (1, (3, 0)),
+            (1, (9, 9)),
+            (2, (1, 5)),
+            (0, (20, 20)),
+        ];
+
+        let visible = visible_army_positions(0, &owned, &armies, 3);
+
+        // (1, 5) is 5 tiles away, (9, 9) is far from every observer
+        assert_eq!(visible, vec![(0, (1, 0)), (1, (3, 0)), (0, (20, 20))]);
+        assert_eq!(
+            visible_army_positions(0, &owned, &armies, 5),
+            vec![(0, (1, 0)), (1, (3, 0)), (2, (1, 5)), (0, (20, 20))]
+        );
+    }
+
+    #[test]
+    fn threats_only_come_from_visible_armies_test() {
+        let ownership_map = ownership(&[((0, 0), 0), ((1, 0), 0), ((2, 0), 1)]);
+        let owned = [(0, 0), (1, 0)];
+        let valuable: HashSet<(i32, i32)> = [(1, 0)].into_iter().collect();
+        let mut diplomacy = Diplomacy::new();
+        diplomacy.set_relation(0, 1, RelationStatus::AtWar);
+
+        let enemy_nearby = [(1, (1, 1))];
+        let visible = visible_army_positions(0, &owned, &enemy_nearby, 1);
+        let threatened = find_threatened_tiles(0, &ownership_map, &valuable, &visible, &diplomacy);
+        assert_eq!(threatened, [(1, 0)].into_iter().collect());
+
+        // with a zero radius only tiles the AI stands on are seen
+        let hidden = visible_army_positions(0, &owned, &enemy_nearby, 0);
+        assert!(hidden.is_empty());
+        let threatened = find_threatened_tiles(0, &ownership_map, &valuable, &hidden, &diplomacy);
+        assert!(threatened.is_empty());
+    }
+
+    #[test]
+    fn threatened_tiles_ignore_neutral_neighbours_test() {
+        let ownership_map = ownership(&[((0, 0), 0)]);
+        let valuable: HashSet<(i32, i32)> = [(0, 0)].into_iter().collect();
+        let armies = [(1, (1, 0))];
+
+        let threatened =
+            find_threatened_tiles(0, &ownership_map, &valuable, &armies, &Diplomacy::new());
+
+        assert!(threatened.is_empty());
+    }
+}
@@ -9,6 +9,7 @@ use crate::{
 };
 
 pub mod components;
+pub mod grid;
 pub mod messages;
 pub mod systems;
 
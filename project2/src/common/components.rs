@@ -11,8 +11,4 @@ impl GridPosition {
     pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
-
-    pub fn distance(&self, other: &Self) -> f32 {
-        (((self.x - other.x) as f32).powi(2) + ((self.y - other.y) as f32).powi(2)).sqrt()
-    }
 }
@@ -0,0 +1,85 @@
+use crate::common::components::GridPosition;
+
+/// The distance used for movement range, AI vision and starting territory.
+pub fn manhattan(a: &GridPosition, b: &GridPosition) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+
+pub fn chebyshev(a: &GridPosition, b: &GridPosition) -> i32 {
+    (a.x - b.x).abs().max((a.y - b.y).abs())
+}
+
+/// In-bounds neighbors of `pos` on a `width` x `height` grid, in row-major order.
+pub fn neighbors(
+    pos: &GridPosition,
+    width: i32,
+    height: i32,
+    include_diagonals: bool,
+) -> Vec<GridPosition> {
+    (pos.y - 1..=pos.y + 1)
+        .flat_map(|y| (pos.x - 1..=pos.x + 1).map(move |x| GridPosition::new(x, y)))
+        .filter(|other| (0..width).contains(&other.x) && (0..height).contains(&other.y))
+        .filter(|other| chebyshev(pos, other) == 1)
+        .filter(|other| include_diagonals || manhattan(pos, other) == 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_test() {
+        let a = GridPosition::new(1, 2);
+        let b = GridPosition::new(4, -2);
+        assert_eq!(manhattan(&a, &b), 7);
+        assert_eq!(chebyshev(&a, &b), 4);
+        assert_eq!(manhattan(&a, &a), 0);
+        assert_eq!(chebyshev(&b, &a), chebyshev(&a, &b));
+    }
+
+    #[test]
+    fn neighbors_in_corner_test() {
+        let corner = GridPosition::new(0, 0);
+        assert_eq!(
+            neighbors(&corner, 5, 4, false),
+            vec![GridPosition::new(1, 0), GridPosition::new(0, 1)]
+        );
+        assert_eq!(neighbors(&corner, 5, 4, true).len(), 3);
+        assert_eq!(
+            neighbors(&GridPosition::new(4, 3), 5, 4, false),
+            vec![GridPosition::new(4, 2), GridPosition::new(3, 3)]
+        );
+    }
+
+    #[test]
+    fn neighbors_on_edge_test() {
+        let edge = GridPosition::new(2, 0);
+        assert_eq!(
+            neighbors(&edge, 5, 4, false),
+            vec![
+                GridPosition::new(1, 0),
+                GridPosition::new(3, 0),
+                GridPosition::new(2, 1)
+            ]
+        );
+        assert_eq!(neighbors(&edge, 5, 4, true).len(), 5);
+    }
+
+    #[test]
+    fn neighbors_in_interior_test() {
+        let center = GridPosition::new(2, 2);
+        let orthogonal = neighbors(&center, 5, 4, false);
+        assert_eq!(orthogonal.len(), 4);
+        assert!(orthogonal.iter().all(|n| manhattan(&center, n) == 1));
+
+        let all = neighbors(&center, 5, 4, true);
+        assert_eq!(all.len(), 8);
+        assert!(all.iter().all(|n| chebyshev(&center, n) == 1));
+    }
+
+    #[test]
+    fn neighbors_outside_grid_test() {
+        assert!(neighbors(&GridPosition::new(0, 0), 1, 1, true).is_empty());
+    }
+}
@@ -14,11 +14,12 @@ use crate::{
         messages::{
             AcceptPeaceMessage, ChangeRelationMessage, ProposePeaceMessage, RejectPeaceMessage,
         },
-        resources::{Countries, Diplomacy, PeaceOffers},
+        resources::{Countries, CountryIncomes, Diplomacy, PeaceOffers},
         systems::*,
     },
     log_error,
     map::systems::setup_map,
+    ui::systems::update_turn_counter,
 };
 
 pub mod components;
@@ -33,6 +34,8 @@ impl Plugin for CountryPlugin {
         app.init_resource::<Countries>()
             .insert_resource(Diplomacy::new())
             .init_resource::<PeaceOffers>()
+            .init_resource::<CountryIncomes>()
+            .init_resource::<GameConfig>()
             .add_message::<ChangeRelationMessage>()
             .add_message::<ProposePeaceMessage>()
             .add_message::<AcceptPeaceMessage>()
@@ -57,12 +60,15 @@ impl Plugin for CountryPlugin {
             .add_systems(
                 Update,
                 (
+                    country_income_system.pipe(log_error),
                     money_gathering_system.pipe(log_error),
                     propose_peace_system,
                     accept_peace_system,
                     reject_peace_system,
                     relation_managing_system,
                     clean_peace_offers_on_relation_change_system.after(relation_managing_system),
+                    // offers are checked against the turn that has just started
+                    expire_peace_offers_system.after(update_turn_counter),
                 )
                     .run_if(in_state(GameState::InGame)),
             )
@@ -64,11 +64,16 @@ impl Display for RelationStatus {
     }
 }
 
+pub const TRUCE_DURATION_TURNS: u32 = 5;
+
 #[serde_as]
 #[derive(Resource, Default, Serialize, Deserialize, Clone)]
 pub struct Diplomacy {
     #[serde_as(as = "Vec<(_, _)>")]
     relatons: std::collections::HashMap<(usize, usize), RelationStatus>,
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(default)]
+    truces: std::collections::HashMap<(usize, usize), u32>,
 }
 
 impl Diplomacy {
@@ -79,6 +84,7 @@ impl Diplomacy {
     pub fn new() -> Self {
         Self {
             relatons: std::collections::HashMap::new(),
+            truces: std::collections::HashMap::new(),
         }
     }
 
@@ -101,15 +107,160 @@ impl Diplomacy {
 
         self.relatons.insert(key, relation);
     }
+
+    pub fn start_truce(&mut self, country_a_idx: usize, country_b_idx: usize, turn: u32) {
+        let key = Diplomacy::handle_key(country_a_idx, country_b_idx);
+
+        self.truces.insert(key, turn);
+    }
+
+    pub fn is_in_truce(&self, country_a_idx: usize, country_b_idx: usize, turn: u32) -> bool {
+        let key = Diplomacy::handle_key(country_a_idx, country_b_idx);
+
+        self.truces
+            .get(&key)
+            .is_some_and(|truce_start| turn < truce_start + TRUCE_DURATION_TURNS)
+    }
+}
+
+pub const BUILDING_INCOME: i32 = 100;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncomeBreakdown {
+    pub tiles: i32,
+    pub buildings: i32,
+}
+
+impl IncomeBreakdown {
+    pub fn total(&self) -> i32 {
+        self.tiles + self.buildings
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CountryIncomes {
+    pub incomes: std::collections::HashMap<usize, IncomeBreakdown>,
+}
+
+pub const DEFAULT_DISBAND_REFUND_FRACTION: f32 = 0.5;
+pub const DEFAULT_STARTING_TERRITORY_RADIUS: i32 = 4;
+
+#[derive(Resource, Clone, Copy)]
+pub struct GameConfig {
+    pub starting_money: i32,
+    pub starting_army: i32,
+    pub disband_refund_fraction: f32,
+    pub starting_territory_radius: i32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            starting_money: 0,
+            starting_army: 0,
+            disband_refund_fraction: DEFAULT_DISBAND_REFUND_FRACTION,
+            starting_territory_radius: DEFAULT_STARTING_TERRITORY_RADIUS,
+        }
+    }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub const PEACE_OFFER_EXPIRY_TURNS: u32 = 3;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
 pub struct PeaceOffer {
     pub from: usize,
     pub to: usize,
+    pub made_on_turn: u32,
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct PeaceOffers {
     pub offers: Vec<PeaceOffer>,
+    pub expiry_turns: u32,
+}
+
+impl Default for PeaceOffers {
+    fn default() -> Self {
+        Self {
+            offers: Vec::new(),
+            expiry_turns: PEACE_OFFER_EXPIRY_TURNS,
+        }
+    }
+}
+
+impl PeaceOffers {
+    pub fn remove_expired(&mut self, current_turn: u32) {
+        self.offers = still_valid_offers(&self.offers, current_turn, self.expiry_turns);
+    }
+}
+
+pub fn still_valid_offers(
+    offers: &[PeaceOffer],
+    current_turn: u32,
+    expiry_turns: u32,
+) -> Vec<PeaceOffer> {
+    offers
+        .iter()
+        .filter(|offer| current_turn.saturating_sub(offer.made_on_turn) < expiry_turns)
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truce_blocks_war_within_window_test() {
+        let mut diplomacy = Diplomacy::new();
+        diplomacy.start_truce(0, 1, 3);
+
+        assert!(diplomacy.is_in_truce(0, 1, 3));
+        assert!(diplomacy.is_in_truce(1, 0, 3 + TRUCE_DURATION_TURNS - 1));
+    }
+
+    #[test]
+    fn truce_allows_war_after_window_test() {
+        let mut diplomacy = Diplomacy::new();
+        diplomacy.start_truce(0, 1, 3);
+
+        assert!(!diplomacy.is_in_truce(0, 1, 3 + TRUCE_DURATION_TURNS));
+        assert!(!diplomacy.is_in_truce(0, 2, 3));
+    }
+
+    #[test]
+    fn still_valid_offers_drops_stale_offers_test() {
+        let offers = [
+            PeaceOffer {
+                from: 0,
+                to: 1,
+                made_on_turn: 2,
+            },
+            PeaceOffer {
+                from: 2,
+                to: 3,
+                made_on_turn: 5,
+            },
+            PeaceOffer {
+                from: 1,
+                to: 4,
+                made_on_turn: 7,
+            },
+        ];
+
+        let valid = still_valid_offers(&offers, 7, 3);
+
+        assert_eq!(valid, vec![offers[1], offers[2]]);
+        assert_eq!(still_valid_offers(&offers, 100, 3), Vec::new());
+        assert_eq!(still_valid_offers(&offers, 2, 3), offers.to_vec());
+    }
+
+    #[test]
+    fn truce_restarts_on_new_peace_test() {
+        let mut diplomacy = Diplomacy::new();
+        diplomacy.start_truce(0, 1, 0);
+        diplomacy.start_truce(1, 0, 10);
+
+        assert!(diplomacy.is_in_truce(0, 1, 10 + TRUCE_DURATION_TURNS - 1));
+    }
 }
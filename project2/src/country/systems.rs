@@ -1,9 +1,12 @@
 use anyhow::anyhow;
+use std::collections::HashMap;
+
 use bevy::{
     asset::AssetServer,
     color::*,
     ecs::{
         entity::Entity,
+        lifecycle::RemovedComponents,
         message::{MessageReader, MessageWriter},
         query::{Changed, Has, With, Without},
         system::*,
@@ -20,6 +23,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     common::{
         components::GridPosition,
+        grid::manhattan,
         messages::{NextTurnMessage, SaveGameMessage},
         systems::{SAVE_PATH, get_save_path},
     },
@@ -31,19 +35,51 @@ use crate::{
         resources::*,
     },
     log_error,
-    map::{components::*, resources::MapSettings},
-    ui::resources::GameLoadState,
+    map::{components::*, resources::MapSettings, systems::spawn_army_unit},
+    ui::resources::{GameLoadState, TurnCounter},
 };
 
-pub fn setup_countries_system(mut countries: ResMut<Countries>) {
-    let countries = countries.as_mut();
+pub fn initial_countries(count: u8, game_config: &GameConfig) -> Vec<Country> {
+    (0..count)
+        .map(|i| Country {
+            money: game_config.starting_money,
+            ..Country::new(
+                &format!("C{i}"),
+                Color::Hsva(Hsva::hsv(360.0 / count as f32 * i as f32, 1.0, 1.0)),
+            )
+        })
+        .collect()
+}
+
+pub fn setup_countries_system(mut countries: ResMut<Countries>, game_config: Res<GameConfig>) {
     const COUNTRY_NUM: u8 = 5;
-    for i in 0..COUNTRY_NUM {
-        countries.countries.push(Country::new(
-            &format!("C{i}"),
-            Color::Hsva(Hsva::hsv(360.0 / COUNTRY_NUM as f32 * i as f32, 1.0, 1.0)),
-        ));
+    countries
+        .countries
+        .extend(initial_countries(COUNTRY_NUM, &game_config));
+}
+
+pub fn starting_armies(
+    capitals: impl Iterator<Item = (i32, i32)>,
+    game_config: &GameConfig,
+    map_settings: &MapSettings,
+) -> Vec<(Army, GridPosition)> {
+    let number_of_units = map_settings.clamp_units_to_tile_cap(0, game_config.starting_army);
+    if number_of_units < 1 {
+        return Vec::new();
     }
+
+    capitals
+        .enumerate()
+        .map(|(country_idx, (x, y))| {
+            (
+                Army {
+                    country_idx,
+                    number_of_units,
+                },
+                GridPosition::new(x, y),
+            )
+        })
+        .collect()
 }
 
 fn select_capitals(
@@ -78,8 +114,24 @@ pub fn setup_ownership_tiles(
     countries: Res<Countries>,
     tiles_query: Query<(&MapTile, &GridPosition, &Transform)>,
     map_settings: Res<MapSettings>,
+    game_config: Res<GameConfig>,
+    asset_server: Res<AssetServer>,
 ) {
-    let countries_capitals_set = select_capitals(&countries, &tiles_query);
+    let capitals: Vec<(i32, i32)> = select_capitals(&countries, &tiles_query)
+        .into_iter()
+        .collect();
+
+    for (army, grid_position) in
+        starting_armies(capitals.iter().copied(), &game_config, &map_settings)
+    {
+        spawn_army_unit(
+            &mut commands,
+            army,
+            grid_position,
+            &asset_server,
+            &map_settings,
+        );
+    }
 
     for (tile, pos, transform) in tiles_query.iter() {
         if tile.tile_type == MapTileType::Water {
@@ -87,25 +139,23 @@ pub fn setup_ownership_tiles(
             continue;
         }
 
-        if let Some(closest_country_idx) = countries_capitals_set
-            .iter()
-            .enumerate()
-            .min_by_key(|(_, (x, y))| {
-                (*x as i64 - pos.x as i64).pow(2) + (*y as i64 - pos.y as i64).pow(2)
-            })
-            .map(|(idx, _)| idx)
-        {
-            spawn_ownership_tile(
-                &mut commands,
-                pos,
-                transform,
-                &map_settings,
-                Some(closest_country_idx),
-            );
-        }
+        let owner = starting_owner(pos, &capitals, game_config.starting_territory_radius);
+        spawn_ownership_tile(&mut commands, pos, transform, &map_settings, owner);
     }
 }
 
+// Land farther than `radius` from every capital starts neutral and can be claimed
+// by moving an army onto it.
+fn starting_owner(pos: &GridPosition, capitals: &[(i32, i32)], radius: i32) -> Option<usize> {
+    capitals
+        .iter()
+        .map(|&(x, y)| manhattan(pos, &GridPosition::new(x, y)))
+        .enumerate()
+        .filter(|(_, distance)| *distance <= radius)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(idx, _)| idx)
+}
+
 fn spawn_ownership_tile(
     commands: &mut Commands<'_, '_>,
     pos: &GridPosition,
@@ -143,29 +193,85 @@ pub fn update_ownership_tiles(
     }
 }
 
+pub fn calculate_income<'a>(
+    owned_tiles: impl Iterator<Item = (&'a MapTileType, bool)>,
+) -> IncomeBreakdown {
+    owned_tiles.fold(
+        IncomeBreakdown::default(),
+        |mut income, (tile_type, has_building)| {
+            income.tiles += tile_type.income_yield();
+            if has_building {
+                income.buildings += BUILDING_INCOME;
+            }
+            income
+        },
+    )
+}
+
+fn compute_country_incomes(
+    map_tiles: &Query<(&MapTile, &GridPosition, Has<Building>)>,
+    ownership_tiles: &Query<(&OwnershipTile, &GridPosition)>,
+) -> anyhow::Result<HashMap<usize, IncomeBreakdown>> {
+    let map_tiles_by_pos: HashMap<GridPosition, (&MapTileType, bool)> = map_tiles
+        .iter()
+        .map(|(map_tile, pos, has_building)| (*pos, (&map_tile.tile_type, has_building)))
+        .collect();
+    let mut owned_tiles_by_country: HashMap<usize, Vec<(&MapTileType, bool)>> = HashMap::new();
+
+    for (ownership_tile, ownership_tile_grid_pos) in ownership_tiles {
+        if let Some(country_id) = ownership_tile.country_id {
+            let Some(map_tile_at_country_pos) = map_tiles_by_pos.get(ownership_tile_grid_pos)
+            else {
+                return Err(anyhow!("Found ownership tile without Map Tile"));
+            };
+            owned_tiles_by_country
+                .entry(country_id)
+                .or_default()
+                .push(*map_tile_at_country_pos);
+        }
+    }
+
+    Ok(owned_tiles_by_country
+        .into_iter()
+        .map(|(country_id, owned_tiles)| (country_id, calculate_income(owned_tiles.into_iter())))
+        .collect())
+}
+
+// incomes only depend on ownership and buildings, so they are recomputed when either of them
+// changes or a new turn starts instead of every frame
+pub fn country_income_system(
+    mut country_incomes: ResMut<CountryIncomes>,
+    mut next_turn_reader: MessageReader<NextTurnMessage>,
+    mut removed_buildings: RemovedComponents<Building>,
+    changed_ownership: Query<(), Changed<OwnershipTile>>,
+    changed_buildings: Query<(), Changed<Building>>,
+    map_tiles: Query<(&MapTile, &GridPosition, Has<Building>)>,
+    ownership_tiles: Query<(&OwnershipTile, &GridPosition)>,
+) -> anyhow::Result<()> {
+    let turn_changed = next_turn_reader.read().count() > 0;
+    let buildings_removed = removed_buildings.read().count() > 0;
+
+    if !turn_changed
+        && !buildings_removed
+        && changed_ownership.is_empty()
+        && changed_buildings.is_empty()
+    {
+        return Ok(());
+    }
+
+    country_incomes.incomes = compute_country_incomes(&map_tiles, &ownership_tiles)?;
+    Ok(())
+}
+
 pub fn money_gathering_system(
     mut msgr: MessageReader<NextTurnMessage>,
     mut countries_resource: ResMut<Countries>,
-    map_tiles: Query<(&GridPosition, Has<Building>), With<MapTile>>,
+    map_tiles: Query<(&MapTile, &GridPosition, Has<Building>)>,
     ownership_tiles: Query<(&OwnershipTile, &GridPosition)>,
 ) -> anyhow::Result<()> {
     for _ in msgr.read() {
-        for (ownership_tile, ownership_tile_grid_pos) in &ownership_tiles {
-            if let Some(country_id) = ownership_tile.country_id {
-                countries_resource.countries[country_id].money += 1;
-                let map_tile_at_country_pos = map_tiles
-                    .iter()
-                    .find(|(pos, _)| *pos == ownership_tile_grid_pos);
-
-                let (_, has_building) = match map_tile_at_country_pos {
-                    Some(t) => t,
-                    None => return Err(anyhow!("Found ownership tile without Map Tile")),
-                };
-
-                if has_building {
-                    countries_resource.countries[country_id].money += 100;
-                }
-            }
+        for (country_id, income) in compute_country_incomes(&map_tiles, &ownership_tiles)? {
+            countries_resource.countries[country_id].money += income.total();
         }
     }
     Ok(())
@@ -174,8 +280,23 @@ pub fn money_gathering_system(
 pub fn relation_managing_system(
     mut change_relation_message_reader: MessageReader<ChangeRelationMessage>,
     mut diplomacy_resource: ResMut<Diplomacy>,
+    turn_counter: Res<TurnCounter>,
 ) {
     for change_relation_message in change_relation_message_reader.read() {
+        if matches!(change_relation_message.relation, RelationStatus::AtWar)
+            && diplomacy_resource.is_in_truce(
+                change_relation_message.country_a_idx,
+                change_relation_message.country_b_idx,
+                turn_counter.count,
+            )
+        {
+            println!(
+                "Country {} cannot declare war on {} during a truce",
+                change_relation_message.country_a_idx, change_relation_message.country_b_idx
+            );
+            continue;
+        }
+
         diplomacy_resource.set_relation(
             change_relation_message.country_a_idx,
             change_relation_message.country_b_idx,
@@ -188,6 +309,7 @@ pub fn propose_peace_system(
     mut propose_peace_reader: MessageReader<ProposePeaceMessage>,
     mut peace_offers: ResMut<PeaceOffers>,
     diplomacy: Res<Diplomacy>,
+    turn_counter: Res<TurnCounter>,
 ) {
     for proposal in propose_peace_reader.read() {
         if !matches!(
@@ -209,6 +331,7 @@ pub fn propose_peace_system(
         peace_offers.offers.push(PeaceOffer {
             from: proposal.from,
             to: proposal.to,
+            made_on_turn: turn_counter.count,
         });
 
         println!(
@@ -222,8 +345,12 @@ pub fn accept_peace_system(
     mut accept_peace_reader: MessageReader<AcceptPeaceMessage>,
     mut peace_offers: ResMut<PeaceOffers>,
     mut change_relation_writer: MessageWriter<ChangeRelationMessage>,
+    mut diplomacy_resource: ResMut<Diplomacy>,
+    turn_counter: Res<TurnCounter>,
 ) {
     for message in accept_peace_reader.read() {
+        diplomacy_resource.start_truce(message.from, message.to, turn_counter.count);
+
         peace_offers.offers.retain(|offer| {
             !((offer.from == message.from && offer.to == message.to)
                 || (offer.from == message.to && offer.to == message.from))
@@ -255,6 +382,18 @@ pub fn reject_peace_system(
     }
 }
 
+pub fn expire_peace_offers_system(
+    mut next_turn_reader: MessageReader<NextTurnMessage>,
+    mut peace_offers: ResMut<PeaceOffers>,
+    turn_counter: Res<TurnCounter>,
+) {
+    if next_turn_reader.read().count() == 0 {
+        return;
+    }
+
+    peace_offers.remove_expired(turn_counter.count);
+}
+
 pub fn clean_peace_offers_on_relation_change_system(
     mut change_relation_reader: MessageReader<ChangeRelationMessage>,
     mut peace_offers: ResMut<PeaceOffers>,
@@ -446,3 +585,94 @@ pub fn ownership_tile_position_sync_system(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_countries_use_configured_starting_money_test() {
+        let game_config = GameConfig {
+            starting_money: 750,
+            starting_army: 20,
+            ..GameConfig::default()
+        };
+
+        let countries = initial_countries(3, &game_config);
+
+        assert_eq!(countries.len(), 3);
+        assert!(countries.iter().all(|country| country.money == 750));
+        assert_eq!(countries[2].name, "C2");
+    }
+
+    #[test]
+    fn starting_owner_leaves_distant_land_neutral_test() {
+        let capitals = [(0, 0), (10, 0)];
+
+        assert_eq!(
+            starting_owner(&GridPosition::new(2, 1), &capitals, 4),
+            Some(0)
+        );
+        assert_eq!(
+            starting_owner(&GridPosition::new(9, 2), &capitals, 4),
+            Some(1)
+        );
+        assert_eq!(starting_owner(&GridPosition::new(5, 0), &capitals, 4), None);
+        assert_eq!(starting_owner(&GridPosition::new(3, 3), &capitals, 4), None);
+        assert_eq!(
+            starting_owner(&GridPosition::new(5, 0), &capitals, 5),
+            Some(0)
+        );
+        assert_eq!(starting_owner(&GridPosition::new(0, 0), &[], 4), None);
+    }
+
+    #[test]
+    fn starting_armies_place_configured_units_on_capitals_test() {
+        let game_config = GameConfig {
+            starting_army: 20,
+            ..GameConfig::default()
+        };
+        let map_settings = MapSettings::new(10, 10, 100, 2000, 100, 15);
+        let capitals = [(1, 2), (5, 5), (8, 3)];
+
+        let armies = starting_armies(capitals.into_iter(), &game_config, &map_settings);
+
+        assert_eq!(armies.len(), 3);
+        for (idx, (army, pos)) in armies.iter().enumerate() {
+            assert_eq!(army.country_idx, idx);
+            assert_eq!(army.number_of_units, 15);
+            assert_eq!(*pos, GridPosition::new(capitals[idx].0, capitals[idx].1));
+        }
+
+        let no_army = GameConfig::default();
+        assert!(starting_armies(capitals.into_iter(), &no_army, &map_settings).is_empty());
+    }
+
+    #[test]
+    fn calculate_income_mixed_tiles_test() {
+        let owned_tiles = [
+            (MapTileType::Flat, false),
+            (MapTileType::Forest, true),
+            (MapTileType::Sand, false),
+            (MapTileType::Water, false),
+            (MapTileType::Mountain, true),
+        ];
+
+        let income = calculate_income(
+            owned_tiles
+                .iter()
+                .map(|(tile_type, has_building)| (tile_type, *has_building)),
+        );
+
+        assert_eq!(income.tiles, 5);
+        assert_eq!(income.buildings, 2 * BUILDING_INCOME);
+        assert_eq!(income.total(), 5 + 2 * BUILDING_INCOME);
+    }
+
+    #[test]
+    fn calculate_income_no_tiles_test() {
+        let income = calculate_income(std::iter::empty());
+
+        assert_eq!(income, IncomeBreakdown::default());
+    }
+}
@@ -4,7 +4,10 @@ use crate::{
     common::{GenerateSet, LoadSet},
     log_error,
     map::{
-        messages::{ArmyBattleMessage, BuildBuildingMessage, SaveMapMessage, SpawnArmyMessage},
+        messages::{
+            ArmyBattleMessage, ArmyCapReachedMessage, BuildBuildingMessage, DisbandArmyMessage,
+            ExportTerrainMessage, SaveMapMessage, SpawnArmyMessage,
+        },
         resources::*,
         systems::*,
     },
@@ -24,8 +27,9 @@ enum MapSystemSet {
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(MapSettings::new(100, 50, 100, 2000, 100))
+        app.insert_resource(MapSettings::new(100, 50, 100, 2000, 100, 1000))
             .init_resource::<TileMapGrid>()
+            .init_resource::<TileNeighborCache>()
             .init_resource::<SelectionState>()
             .init_resource::<MapVisibilityState>()
             .init_resource::<ArmyMovements>()
@@ -50,10 +54,15 @@ impl Plugin for MapPlugin {
                         .pipe(log_error)
                         .before(army_position_sync_system),
                     army_ownership_claim_system.pipe(log_error),
+                    disband_army_system,
                     army_position_sync_system,
                 )
                     .run_if(in_state(GameState::InGame)),
             )
+            .add_systems(
+                Update,
+                army_cycling_system.run_if(in_state(InGameStates::Idle)),
+            )
             .add_systems(
                 OnEnter(InGameStates::MovingArmy),
                 show_movement_range_system.pipe(log_error),
@@ -73,13 +82,17 @@ impl Plugin for MapPlugin {
                     save_map_terrain_system
                         .pipe(log_error)
                         .after(MapSystemSet::Save),
+                    export_terrain_system.pipe(log_error),
                     sync_army_colors_system,
                 )
                     .run_if(in_state(GameState::InGame)),
             )
             .add_message::<BuildBuildingMessage>()
             .add_message::<SpawnArmyMessage>()
+            .add_message::<DisbandArmyMessage>()
             .add_message::<SaveMapMessage>()
-            .add_message::<ArmyBattleMessage>();
+            .add_message::<ExportTerrainMessage>()
+            .add_message::<ArmyBattleMessage>()
+            .add_message::<ArmyCapReachedMessage>();
     }
 }
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum MapTileType {
     Water,
     Sand,
@@ -10,6 +10,16 @@ pub enum MapTileType {
     Forest,
 }
 
+impl MapTileType {
+    pub fn income_yield(&self) -> i32 {
+        match self {
+            MapTileType::Flat | MapTileType::Forest => 2,
+            MapTileType::Sand => 1,
+            MapTileType::Water | MapTileType::Mountain => 0,
+        }
+    }
+}
+
 impl From<&MapTileType> for Color {
     fn from(value: &MapTileType) -> Self {
         match value {
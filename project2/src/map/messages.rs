@@ -7,6 +7,11 @@ pub struct SaveMapMessage {
     pub map_name: String,
 }
 
+#[derive(Message, Clone)]
+pub struct ExportTerrainMessage {
+    pub export_name: String,
+}
+
 #[derive(Message)]
 pub struct BuildBuildingMessage {
     pub tile_entity: Entity,
@@ -20,6 +25,12 @@ pub struct SpawnArmyMessage {
     pub amount: i32,
 }
 
+#[derive(Message)]
+pub struct DisbandArmyMessage {
+    pub army_entity: Entity,
+    pub country_idx: usize,
+}
+
 #[derive(Message, Debug, Clone, PartialEq, Eq)]
 pub struct MoveArmyMessage {
     pub moved_army_entity: Entity,
@@ -32,3 +43,10 @@ pub struct ArmyBattleMessage {
     pub army_a_entity: Entity,
     pub army_b_entity: Entity,
 }
+
+#[derive(Message)]
+pub struct ArmyCapReachedMessage {
+    pub country_idx: usize,
+    pub position: GridPosition,
+    pub rejected_units: i32,
+}
@@ -3,7 +3,10 @@ use std::collections::VecDeque;
 use bevy::{platform::collections::HashMap, prelude::*};
 use serde::{Deserialize, Serialize};
 
-use crate::map::messages::{ArmyBattleMessage, MoveArmyMessage};
+use crate::{
+    common::{components::GridPosition, grid::manhattan},
+    map::messages::{ArmyBattleMessage, MoveArmyMessage},
+};
 
 #[derive(Resource, Serialize, Deserialize, Clone)]
 pub struct MapSettings {
@@ -12,6 +15,12 @@ pub struct MapSettings {
     pub tile_size: i32,
     pub building_cost: i32,
     pub unit_cost: i32,
+    #[serde(default = "default_max_units_per_tile")]
+    pub max_units_per_tile: i32,
+}
+
+fn default_max_units_per_tile() -> i32 {
+    1000
 }
 
 impl MapSettings {
@@ -21,6 +30,7 @@ impl MapSettings {
         tile_size: i32,
         building_cost: i32,
         unit_cost: i32,
+        max_units_per_tile: i32,
     ) -> Self {
         Self {
             width,
@@ -28,8 +38,13 @@ impl MapSettings {
             tile_size,
             building_cost,
             unit_cost,
+            max_units_per_tile,
         }
     }
+
+    pub fn clamp_units_to_tile_cap(&self, units_on_tile: i32, units_to_add: i32) -> i32 {
+        units_to_add.min((self.max_units_per_tile - units_on_tile).max(0))
+    }
 }
 
 #[derive(Resource)]
@@ -51,6 +66,47 @@ impl FromWorld for TileMapGrid {
     }
 }
 
+pub const MAX_MOVEMENT_RADIUS: i32 = 2;
+
+// tiles within `radius` of every tile, so movement range doesn't scan the whole map
+#[derive(Resource, Default)]
+pub struct TileNeighborCache {
+    pub neighbors: HashMap<(i32, i32), Vec<GridPosition>>,
+}
+
+impl TileNeighborCache {
+    pub fn build(width: i32, height: i32, radius: i32) -> Self {
+        let mut neighbors = HashMap::new();
+
+        for x in 0..width {
+            for y in 0..height {
+                let pos = GridPosition::new(x, y);
+                let in_range = (y - radius..=y + radius)
+                    .flat_map(|ny| {
+                        (x - radius..=x + radius).map(move |nx| GridPosition::new(nx, ny))
+                    })
+                    .filter(|other| (0..width).contains(&other.x) && (0..height).contains(&other.y))
+                    .filter(|other| *other != pos && manhattan(&pos, other) <= radius)
+                    .collect();
+                neighbors.insert((x, y), in_range);
+            }
+        }
+
+        Self { neighbors }
+    }
+
+    pub fn for_map(map_settings: &MapSettings) -> Self {
+        Self::build(map_settings.width, map_settings.height, MAX_MOVEMENT_RADIUS)
+    }
+
+    pub fn within_radius(&self, pos: &GridPosition) -> &[GridPosition] {
+        self.neighbors
+            .get(&(pos.x, pos.y))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Resource)]
 pub struct SelectionState {
     pub selected_tile: Option<(i32, i32)>,
@@ -94,6 +150,11 @@ impl ArmyMovements {
     pub fn get_movement(&mut self) -> Option<MoveArmyMessage> {
         self.movements.pop_front()
     }
+
+    pub fn remove_movements_of(&mut self, army_entity: Entity) {
+        self.movements
+            .retain(|movement| movement.moved_army_entity != army_entity);
+    }
 }
 
 #[derive(Resource)]
@@ -118,3 +179,70 @@ impl ArmyBattles {
         self.battles.pop_front()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_settings_with_cap(max_units_per_tile: i32) -> MapSettings {
+        MapSettings::new(10, 10, 100, 2000, 100, max_units_per_tile)
+    }
+
+    #[test]
+    fn tile_neighbor_cache_matches_brute_force_test() {
+        let (width, height) = (7, 5);
+
+        for radius in [1, MAX_MOVEMENT_RADIUS, 3] {
+            let cache = TileNeighborCache::build(width, height, radius);
+            assert_eq!(cache.neighbors.len(), (width * height) as usize);
+
+            for x in 0..width {
+                for y in 0..height {
+                    let pos = GridPosition::new(x, y);
+                    let mut expected: Vec<GridPosition> = (0..width)
+                        .flat_map(|ox| (0..height).map(move |oy| GridPosition::new(ox, oy)))
+                        .filter(|other| *other != pos && manhattan(&pos, other) <= radius)
+                        .collect();
+                    let mut cached = cache.within_radius(&pos).to_vec();
+                    expected.sort_by_key(|p| (p.x, p.y));
+                    cached.sort_by_key(|p| (p.x, p.y));
+
+                    assert_eq!(cached, expected, "tile ({x}, {y}), radius {radius}");
+                }
+            }
+        }
+
+        let cache = TileNeighborCache::build(width, height, MAX_MOVEMENT_RADIUS);
+        assert_eq!(cache.within_radius(&GridPosition::new(3, 2)).len(), 12);
+        assert!(cache.within_radius(&GridPosition::new(-1, 0)).is_empty());
+    }
+
+    #[test]
+    fn clamp_units_to_tile_cap_below_cap_test() {
+        let map_settings = map_settings_with_cap(100);
+
+        assert_eq!(map_settings.clamp_units_to_tile_cap(20, 30), 30);
+        assert_eq!(map_settings.clamp_units_to_tile_cap(0, 100), 100);
+    }
+
+    #[test]
+    fn clamp_units_to_tile_cap_recruitment_stops_at_cap_test() {
+        let map_settings = map_settings_with_cap(100);
+
+        let mut units_on_tile = 0;
+        for _ in 0..5 {
+            units_on_tile += map_settings.clamp_units_to_tile_cap(units_on_tile, 30);
+        }
+
+        assert_eq!(units_on_tile, 100);
+        assert_eq!(map_settings.clamp_units_to_tile_cap(units_on_tile, 30), 0);
+    }
+
+    #[test]
+    fn clamp_units_to_tile_cap_overflowing_merge_test() {
+        let map_settings = map_settings_with_cap(100);
+
+        assert_eq!(map_settings.clamp_units_to_tile_cap(70, 50), 30);
+        assert_eq!(map_settings.clamp_units_to_tile_cap(120, 10), 0);
+    }
+}
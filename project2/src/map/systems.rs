@@ -20,17 +20,18 @@ use crate::{
     },
     country::{
         components::OwnershipTile,
-        resources::{Countries, Diplomacy, RelationStatus},
+        resources::{Countries, Diplomacy, GameConfig, RelationStatus},
     },
     log_error,
     map::{
         components::*,
         messages::{
-            ArmyBattleMessage, BuildBuildingMessage, MoveArmyMessage, SaveMapMessage,
-            SpawnArmyMessage,
+            ArmyBattleMessage, ArmyCapReachedMessage, BuildBuildingMessage, DisbandArmyMessage,
+            ExportTerrainMessage, MoveArmyMessage, SaveMapMessage, SpawnArmyMessage,
         },
         resources::*,
     },
+    player::resources::PlayerData,
     ui::resources::GameLoadState,
 };
 
@@ -76,6 +77,69 @@ fn spawn_map_terrain_save_thread(save_map_message: &SaveMapMessage, map_save_sta
     .detach();
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TerrainExport {
+    width: i32,
+    height: i32,
+    tiles: Vec<Vec<MapTileType>>,
+}
+
+pub fn export_terrain_system(
+    mut export_terrain_message_reader: MessageReader<ExportTerrainMessage>,
+    map_tiles: Query<&MapTile>,
+    tile_grid: Res<TileMapGrid>,
+    map_settings: Res<MapSettings>,
+) -> anyhow::Result<()> {
+    for export_terrain_message in export_terrain_message_reader.read() {
+        let terrain_export = build_terrain_export(&map_tiles, &tile_grid, &map_settings)?;
+        let export_json = terrain_to_json(&terrain_export)?;
+        let export_name = export_terrain_message.export_name.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(e) = fs::create_dir_all("exports") {
+                    log_error(In(anyhow::Result::Err(e.into())));
+                    return;
+                };
+                if let Err(e) =
+                    fs::write(format!("exports/{}_terrain.json", export_name), export_json)
+                {
+                    log_error(In(anyhow::Result::Err(e.into())));
+                };
+            })
+            .detach();
+    }
+    Ok(())
+}
+
+fn build_terrain_export(
+    map_tiles: &Query<&MapTile>,
+    tile_grid: &TileMapGrid,
+    map_settings: &MapSettings,
+) -> anyhow::Result<TerrainExport> {
+    let mut tiles = Vec::new();
+    for y in 0..map_settings.height {
+        let mut row = Vec::new();
+        for x in 0..map_settings.width {
+            let tile_entity = tile_grid.grid.get(&(x, y)).ok_or(anyhow!(
+                "Map tile not found on position {} {}",
+                x,
+                y
+            ))?;
+            row.push(map_tiles.get(*tile_entity)?.tile_type.clone());
+        }
+        tiles.push(row);
+    }
+    Ok(TerrainExport {
+        width: map_settings.width,
+        height: map_settings.height,
+        tiles,
+    })
+}
+
+fn terrain_to_json(terrain_export: &TerrainExport) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(terrain_export)?)
+}
+
 fn generate_new_map(
     commands: &mut Commands,
     map_settings: &Res<MapSettings>,
@@ -124,12 +188,14 @@ pub fn setup_map(
             && let Ok(state) = serde_json::from_str::<MapSaveState>(&data)
         {
             commands.insert_resource(state.map_settings.clone());
+            commands.insert_resource(TileNeighborCache::for_map(&state.map_settings));
             spawn_loaded_tiles(&mut commands, &state, &asset_server, &mut tile_grid);
         } else {
             error!("Encountered an error while deserializing the map");
             app_exit.write(AppExit::error());
         }
     } else {
+        commands.insert_resource(TileNeighborCache::for_map(&map_settings));
         generate_new_map(&mut commands, &map_settings, &mut tile_grid);
     }
 }
@@ -227,6 +293,60 @@ fn handle_tile_selection(
     );
 }
 
+#[derive(SystemParam)]
+pub struct ArmyCyclingSystemResources<'w> {
+    button_input: Res<'w, ButtonInput<KeyCode>>,
+    tile_grid: Res<'w, TileMapGrid>,
+    map_settings: Res<'w, MapSettings>,
+    player_data: Res<'w, PlayerData>,
+}
+
+pub fn army_cycling_system(
+    mut camera_transform: Single<&mut Transform, (With<Camera2d>, Without<SelectionCursor>)>,
+    cursor_visibility_query: Single<(&mut Visibility, &mut Transform), With<SelectionCursor>>,
+    selected_state: ResMut<SelectionState>,
+    army_query: Query<(&Army, &GridPosition)>,
+    read_resources: ArmyCyclingSystemResources,
+) {
+    if !read_resources.button_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let player_army_positions: Vec<GridPosition> = army_query
+        .iter()
+        .filter(|(army, _)| army.country_idx == read_resources.player_data.country_idx)
+        .map(|(_, pos)| *pos)
+        .collect();
+    let Some(next_position) =
+        next_army_position_in_cycle(&player_army_positions, selected_state.selected_tile)
+    else {
+        return;
+    };
+    let Some(tile) = read_resources
+        .tile_grid
+        .grid
+        .get(&(next_position.x, next_position.y))
+    else {
+        return;
+    };
+
+    let world_pos = grid_to_world(&next_position, &read_resources.map_settings);
+    camera_transform.translation.x = world_pos.x;
+    camera_transform.translation.y = world_pos.y;
+
+    let origin = grid_to_world(&GridPosition::new(0, 0), &read_resources.map_settings);
+    let (mut cursor_visibility, mut cursor_transform) = cursor_visibility_query.into_inner();
+    update_selection_and_cursor(
+        &mut cursor_transform,
+        read_resources.map_settings,
+        selected_state,
+        (origin.x, origin.y),
+        (next_position.x, next_position.y),
+        &mut cursor_visibility,
+        tile,
+    );
+}
+
 pub fn map_visibility_toggling_system(
     input: Res<ButtonInput<KeyCode>>,
     mut map_state: ResMut<MapVisibilityState>,
@@ -296,7 +416,7 @@ pub fn build_building_system(
 
 #[derive(SystemParam)]
 pub struct SpawnArmySystemQueries<'w, 's> {
-    army_query: Query<'w, 's, (&'static mut Army, &'static GridPosition)>,
+    army_query: Query<'w, 's, (Entity, &'static mut Army, &'static GridPosition)>,
     map_tile_query: Query<'w, 's, &'static GridPosition, With<MapTile>>,
     ownership_tile_query: Query<'w, 's, (&'static GridPosition, &'static OwnershipTile)>,
 }
@@ -328,6 +448,34 @@ fn validate_spawn_location(
     Ok(true)
 }
 
+fn clamp_number_of_units_to_tile_cap(
+    spawn_army_message: &SpawnArmyMessage,
+    amount: i32,
+    queries: &SpawnArmySystemQueries,
+    map_settings: &MapSettings,
+    army_cap_reached_writer: &mut MessageWriter<ArmyCapReachedMessage>,
+) -> Result<i32> {
+    let map_tile_grid_position = queries.map_tile_query.get(spawn_army_message.tile_entity)?;
+    let units_on_tile: i32 = queries
+        .army_query
+        .iter()
+        .filter(|(_, army, pos)| {
+            **pos == *map_tile_grid_position && army.country_idx == spawn_army_message.country_idx
+        })
+        .map(|(_, army, _)| army.number_of_units)
+        .sum();
+    let clamped_amount = map_settings.clamp_units_to_tile_cap(units_on_tile, amount);
+
+    if clamped_amount < amount {
+        army_cap_reached_writer.write(ArmyCapReachedMessage {
+            country_idx: spawn_army_message.country_idx,
+            position: *map_tile_grid_position,
+            rejected_units: amount - clamped_amount,
+        });
+    }
+    Ok(clamped_amount)
+}
+
 fn update_or_spawn_army(
     commands: &mut Commands,
     queries: &mut SpawnArmySystemQueries,
@@ -337,12 +485,17 @@ fn update_or_spawn_army(
     map_settings: &MapSettings,
 ) -> Result<()> {
     let map_tile_grid_position = queries.map_tile_query.get(spawn_army_message.tile_entity)?;
-    let existing_army = queries
-        .army_query
-        .iter_mut()
-        .find(|(_, pos)| **pos == *map_tile_grid_position);
-
-    if let Some((mut army, _)) = existing_army {
+    let existing_army_entity = army_at_position(
+        queries
+            .army_query
+            .iter()
+            .map(|(entity, army, pos)| (entity, pos, army)),
+        *map_tile_grid_position,
+    )
+    .map(|(entity, _, _)| entity);
+
+    if let Some(entity) = existing_army_entity {
+        let (_, mut army, _) = queries.army_query.get_mut(entity)?;
         if army.country_idx == spawn_army_message.country_idx {
             army.number_of_units += amount;
         } else {
@@ -372,9 +525,10 @@ pub fn spawn_army_system(
     mut queries: SpawnArmySystemQueries,
     map_settings: Res<MapSettings>,
     asset_server: Res<AssetServer>,
+    mut army_cap_reached_writer: MessageWriter<ArmyCapReachedMessage>,
 ) -> anyhow::Result<()> {
     for spawn_army_message in msgr.read() {
-        let (amount, spawn_army_cost) =
+        let (amount, _) =
             clamp_number_of_units_to_country_budget(&countries, &map_settings, spawn_army_message);
         if amount < 1 {
             continue;
@@ -384,6 +538,18 @@ pub fn spawn_army_system(
             continue;
         }
 
+        let amount = clamp_number_of_units_to_tile_cap(
+            spawn_army_message,
+            amount,
+            &queries,
+            &map_settings,
+            &mut army_cap_reached_writer,
+        )?;
+        if amount < 1 {
+            continue;
+        }
+        let spawn_army_cost = map_settings.unit_cost * amount;
+
         update_or_spawn_army(
             &mut commands,
             &mut queries,
@@ -560,6 +726,7 @@ pub fn load_map_system(
         let state: MapSaveState = serde_json::from_str(&data)?;
 
         commands.insert_resource(state.map_settings.clone());
+        commands.insert_resource(TileNeighborCache::for_map(&state.map_settings));
 
         spawn_loaded_tiles(&mut commands, &state, &asset_server, &mut tile_grid);
         spawn_loaded_armies(&mut commands, &state, &asset_server);
@@ -611,19 +778,17 @@ fn highlight_tile_in_range(
     let (army_entity, start_pos, army) = army_info;
     let (tile_pos, map_tile) = tile_info;
 
-    if start_pos.distance(tile_pos) <= 2.0
-        && *start_pos != *tile_pos
-        && validate_army_movement(
-            army,
-            &MoveArmyMessage {
-                moved_army_entity: *army_entity,
-                target_position: **tile_pos,
-                number_of_units_to_move: 0,
-            },
-            ownership_tiles_query,
-            &resources.diplomacy,
-        )?
-    {
+    if validate_army_movement(
+        army,
+        &MoveArmyMessage {
+            moved_army_entity: *army_entity,
+            target_position: **tile_pos,
+            number_of_units_to_move: 0,
+        },
+        ownership_tiles_query,
+        map_tile.tile_type != MapTileType::Water,
+        &resources.diplomacy,
+    )? {
         highlight_tile(
             commands,
             &mut resources.materials,
@@ -642,19 +807,28 @@ pub fn show_movement_range_system(
     army_query: Query<(Entity, &GridPosition, &Army)>,
     tiles_query: Query<(&GridPosition, &MapTile)>,
     ownership_tiles_query: Query<(&OwnershipTile, &GridPosition), Without<Army>>,
+    neighbor_cache: Res<TileNeighborCache>,
+    tile_grid: Res<TileMapGrid>,
 ) -> anyhow::Result<()> {
     let Some((selected_tile_x, selected_tile_y)) = resources.selection.selected_tile else {
         return Ok(());
     };
 
-    let Some(army_info) = army_query
-        .iter()
-        .find(|(_, pos, _)| pos.x == selected_tile_x && pos.y == selected_tile_y)
-    else {
+    let Some(army_info) = army_at_position(
+        army_query.iter(),
+        GridPosition::new(selected_tile_x, selected_tile_y),
+    ) else {
         return Ok(());
     };
 
-    for tile_info in tiles_query.iter() {
+    for tile_pos in neighbor_cache.within_radius(army_info.1) {
+        let Some(tile_info) = tile_grid
+            .grid
+            .get(&(tile_pos.x, tile_pos.y))
+            .and_then(|entity| tiles_query.get(*entity).ok())
+        else {
+            continue;
+        };
         highlight_tile_in_range(
             &mut commands,
             &mut resources,
@@ -681,6 +855,8 @@ pub struct MoveArmySystemQueries<'w, 's> {
     army_queries: Query<'w, 's, (&'static mut Army, &'static mut GridPosition)>,
     ownership_tiles_query:
         Query<'w, 's, (&'static OwnershipTile, &'static GridPosition), Without<Army>>,
+    map_tiles_query: Query<'w, 's, (&'static MapTile, &'static GridPosition), Without<Army>>,
+    army_cap_reached_writer: MessageWriter<'w, ArmyCapReachedMessage>,
 }
 
 pub fn detect_army_collisions_system(
@@ -821,9 +997,7 @@ pub fn army_ownership_claim_system(
             .find(|(_, pos)| *pos == position)
             .ok_or(anyhow!("Map tile without ownership tile found"))?;
 
-        if let Some(country_idx) = ownership_tile.country_id
-            && country_idx != army.country_idx
-        {
+        if ownership_tile.country_id != Some(army.country_idx) {
             ownership_tile.country_id = Some(army.country_idx);
         }
     }
@@ -875,25 +1049,50 @@ fn move_or_split_army(
 fn process_army_movement(
     commands: &mut Commands,
     queries: &mut MoveArmySystemQueries,
-    move_army_message: MoveArmyMessage,
+    mut move_army_message: MoveArmyMessage,
     diplomacy_resource: &Diplomacy,
     asset_server: &AssetServer,
     map_settings: &MapSettings,
 ) -> anyhow::Result<()> {
+    let friendly_units_on_target = friendly_units_on_target_position(queries, &move_army_message)?;
     let (mut army, mut source_army_position) = queries
         .army_queries
         .get_mut(move_army_message.moved_army_entity)?;
 
+    let target_is_land = queries.map_tiles_query.iter().any(|(tile, pos)| {
+        *pos == move_army_message.target_position && tile.tile_type != MapTileType::Water
+    });
     if !validate_army_movement(
         &army,
         &move_army_message,
         &queries.ownership_tiles_query,
+        target_is_land,
         diplomacy_resource,
     )? || move_army_message.number_of_units_to_move <= 0
     {
         return Ok(());
     }
 
+    let units_to_move = min(
+        army.number_of_units,
+        move_army_message.number_of_units_to_move,
+    );
+    let clamped_units_to_move =
+        map_settings.clamp_units_to_tile_cap(friendly_units_on_target, units_to_move);
+    if clamped_units_to_move < units_to_move {
+        queries
+            .army_cap_reached_writer
+            .write(ArmyCapReachedMessage {
+                country_idx: army.country_idx,
+                position: move_army_message.target_position,
+                rejected_units: units_to_move - clamped_units_to_move,
+            });
+    }
+    if clamped_units_to_move <= 0 {
+        return Ok(());
+    }
+    move_army_message.number_of_units_to_move = clamped_units_to_move;
+
     move_or_split_army(
         commands,
         &mut army,
@@ -906,10 +1105,29 @@ fn process_army_movement(
     Ok(())
 }
 
+fn friendly_units_on_target_position(
+    queries: &MoveArmySystemQueries,
+    move_army_message: &MoveArmyMessage,
+) -> anyhow::Result<i32> {
+    let (moved_army, _) = queries
+        .army_queries
+        .get(move_army_message.moved_army_entity)?;
+
+    Ok(queries
+        .army_queries
+        .iter()
+        .filter(|(army, pos)| {
+            **pos == move_army_message.target_position && army.country_idx == moved_army.country_idx
+        })
+        .map(|(army, _)| army.number_of_units)
+        .sum())
+}
+
 fn validate_army_movement(
     army: &Army,
     move_army_message: &MoveArmyMessage,
     ownership_tiles_query: &Query<(&OwnershipTile, &GridPosition), Without<Army>>,
+    target_is_land: bool,
     diplomacy_resource: &Diplomacy,
 ) -> anyhow::Result<bool> {
     let Some((ownership_tile, _)) = ownership_tiles_query
@@ -923,7 +1141,7 @@ fn validate_army_movement(
     };
 
     let Some(target_tile_country_idx) = ownership_tile.country_id else {
-        return Ok(false); // cant move on unowned land
+        return Ok(target_is_land); // unowned land can be claimed, water is not walkable
     };
 
     if army.country_idx != target_tile_country_idx
@@ -954,6 +1172,38 @@ fn clamp_number_of_units_to_country_budget(
     (amount, spawn_army_cost)
 }
 
+fn next_army_position_in_cycle(
+    army_positions: &[GridPosition],
+    selected_tile: Option<(i32, i32)>,
+) -> Option<GridPosition> {
+    let ordered_positions: Vec<GridPosition> = army_positions
+        .iter()
+        .copied()
+        .sorted_by_key(|pos| (pos.x, pos.y))
+        .dedup()
+        .collect();
+
+    selected_tile
+        .and_then(|selected| {
+            ordered_positions
+                .iter()
+                .find(|pos| (pos.x, pos.y) > selected)
+        })
+        .or(ordered_positions.first())
+        .copied()
+}
+
+// query iteration order is not stable, so ties are broken by the lowest entity
+fn army_at_position<'a>(
+    armies: impl IntoIterator<Item = (Entity, &'a GridPosition, &'a Army)>,
+    position: GridPosition,
+) -> Option<(Entity, &'a GridPosition, &'a Army)> {
+    armies
+        .into_iter()
+        .filter(|(_, pos, _)| **pos == position)
+        .min_by_key(|(entity, _, _)| *entity)
+}
+
 fn grid_to_world(grid_position: &GridPosition, map_settings: &MapSettings) -> Vec3 {
     let half_tile = map_settings.tile_size as f32 / 2.0;
     let offset_x = -((map_settings.width * map_settings.tile_size) as f32) / 2.0 + half_tile;
@@ -963,7 +1213,57 @@ fn grid_to_world(grid_position: &GridPosition, map_settings: &MapSettings) -> Ve
     Vec3::new(world_pos_x, world_pos_y, 0.0)
 }
 
-fn spawn_army_unit(
+pub fn disband_refund(army: &Army, unit_cost: i32, refund_fraction: f32) -> i32 {
+    (army.number_of_units as f32 * unit_cost as f32 * refund_fraction.clamp(0.0, 1.0)) as i32
+}
+
+// the label is cleaned up by `remove_army_label_system` once the army is gone
+pub fn disband_army(
+    commands: &mut Commands,
+    countries: &mut Countries,
+    army_movements: &mut ArmyMovements,
+    army_entity: Entity,
+    army: &Army,
+    unit_cost: i32,
+    refund_fraction: f32,
+) -> i32 {
+    let refund = disband_refund(army, unit_cost, refund_fraction);
+    countries.countries[army.country_idx].money += refund;
+    army_movements.remove_movements_of(army_entity);
+    commands.entity(army_entity).despawn();
+    refund
+}
+
+pub fn disband_army_system(
+    mut commands: Commands,
+    mut msgr: MessageReader<DisbandArmyMessage>,
+    mut countries: ResMut<Countries>,
+    mut army_movements: ResMut<ArmyMovements>,
+    army_query: Query<&Army>,
+    map_settings: Res<MapSettings>,
+    game_config: Res<GameConfig>,
+) {
+    for msg in msgr.read() {
+        let Ok(army) = army_query.get(msg.army_entity) else {
+            continue;
+        };
+        if army.country_idx != msg.country_idx {
+            continue;
+        }
+
+        disband_army(
+            &mut commands,
+            &mut countries,
+            &mut army_movements,
+            msg.army_entity,
+            army,
+            map_settings.unit_cost,
+            game_config.disband_refund_fraction,
+        );
+    }
+}
+
+pub fn spawn_army_unit(
     commands: &mut Commands,
     army: Army,
     grid_position: GridPosition,
@@ -1078,6 +1378,7 @@ pub fn army_position_sync_system(
     mut army_query: Query<(Entity, &GridPosition, &mut Transform, &mut Army)>,
     map_settings: Res<MapSettings>,
     mut army_battle_message_writer: MessageWriter<ArmyBattleMessage>,
+    mut army_cap_reached_writer: MessageWriter<ArmyCapReachedMessage>,
 ) {
     let mut armies_by_pos: HashMap<GridPosition, Vec<(Entity, usize, i32)>> = HashMap::new();
 
@@ -1091,13 +1392,15 @@ pub fn army_position_sync_system(
         ));
     }
 
-    for (_, armies) in armies_by_pos {
+    for (position, armies) in armies_by_pos {
         if armies.len() > 1 {
             resolve_armies_on_tile(
                 &mut commands,
                 &mut army_query,
-                armies,
+                (position, armies),
+                &map_settings,
                 &mut army_battle_message_writer,
+                &mut army_cap_reached_writer,
             );
         }
     }
@@ -1106,17 +1409,29 @@ pub fn army_position_sync_system(
 fn resolve_armies_on_tile(
     commands: &mut Commands,
     army_query: &mut Query<(Entity, &GridPosition, &mut Transform, &mut Army)>,
-    armies: Vec<(Entity, usize, i32)>,
+    (position, armies): (GridPosition, Vec<(Entity, usize, i32)>),
+    map_settings: &MapSettings,
     army_battle_message_writer: &mut MessageWriter<ArmyBattleMessage>,
+    army_cap_reached_writer: &mut MessageWriter<ArmyCapReachedMessage>,
 ) {
-    let mut armies_by_country: HashMap<usize, (Entity, i32)> = HashMap::new();
-    for (entity, country_idx, units) in armies {
-        if let Some((_existing_entity, existing_units)) = armies_by_country.get_mut(&country_idx) {
-            *existing_units += units;
-            commands.entity(entity).despawn();
-        } else {
-            armies_by_country.insert(country_idx, (entity, units));
+    let (armies_by_country, absorbed_armies) = merge_friendly_armies(armies, map_settings);
+    for absorbed in absorbed_armies {
+        if absorbed.units_left == 0 {
+            commands.entity(absorbed.entity).despawn();
+            continue;
+        }
+        // an army that could not merge at all is left alone, so it is not reported every frame
+        if absorbed.merged_units == 0 {
+            continue;
         }
+        if let Ok((_, _, _, mut army)) = army_query.get_mut(absorbed.entity) {
+            army.number_of_units = absorbed.units_left;
+        }
+        army_cap_reached_writer.write(ArmyCapReachedMessage {
+            country_idx: absorbed.country_idx,
+            position,
+            rejected_units: absorbed.units_left,
+        });
     }
     for (_, (entity, units)) in &armies_by_country {
         if let Ok((_, _, _, mut army)) = army_query.get_mut(*entity) {
@@ -1133,6 +1448,39 @@ fn resolve_armies_on_tile(
     }
 }
 
+struct AbsorbedArmy {
+    entity: Entity,
+    country_idx: usize,
+    merged_units: i32,
+    units_left: i32,
+}
+
+// The oldest army of each country absorbs the others up to the tile cap; units over the cap
+// stay in the absorbed army instead of being destroyed.
+fn merge_friendly_armies(
+    mut armies: Vec<(Entity, usize, i32)>,
+    map_settings: &MapSettings,
+) -> (HashMap<usize, (Entity, i32)>, Vec<AbsorbedArmy>) {
+    armies.sort_by_key(|(entity, _, _)| *entity);
+    let mut armies_by_country: HashMap<usize, (Entity, i32)> = HashMap::new();
+    let mut absorbed_armies = Vec::new();
+    for (entity, country_idx, units) in armies {
+        if let Some((_existing_entity, existing_units)) = armies_by_country.get_mut(&country_idx) {
+            let merged_units = map_settings.clamp_units_to_tile_cap(*existing_units, units);
+            *existing_units += merged_units;
+            absorbed_armies.push(AbsorbedArmy {
+                entity,
+                country_idx,
+                merged_units,
+                units_left: units - merged_units,
+            });
+        } else {
+            armies_by_country.insert(country_idx, (entity, units));
+        }
+    }
+    (armies_by_country, absorbed_armies)
+}
+
 fn arrange_battles(
     commands: &mut Commands<'_, '_>,
     army_query: &mut Query<'_, '_, (Entity, &GridPosition, &mut Transform, &mut Army)>,
@@ -1159,3 +1507,185 @@ fn arrange_battles(
             );
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::country::resources::Country;
+
+    #[test]
+    fn terrain_json_round_trip_test() {
+        let terrain_export = TerrainExport {
+            width: 3,
+            height: 2,
+            tiles: vec![
+                vec![MapTileType::Water, MapTileType::Sand, MapTileType::Flat],
+                vec![
+                    MapTileType::Mountain,
+                    MapTileType::Forest,
+                    MapTileType::Water,
+                ],
+            ],
+        };
+
+        let json = terrain_to_json(&terrain_export).unwrap();
+        let loaded_terrain: TerrainExport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded_terrain, terrain_export);
+    }
+
+    #[test]
+    fn army_at_position_prefers_lowest_entity_test() {
+        let mut world = World::new();
+        let first = world.spawn_empty().id();
+        let second = world.spawn_empty().id();
+        let third = world.spawn_empty().id();
+        let shared = GridPosition::new(2, 2);
+        let other = GridPosition::new(2, 3);
+        let army = |country_idx| Army {
+            country_idx,
+            number_of_units: 1,
+        };
+        let (army_a, army_b, army_c) = (army(0), army(1), army(2));
+
+        let forward = [
+            (first, &shared, &army_a),
+            (second, &shared, &army_b),
+            (third, &other, &army_c),
+        ];
+        let reversed = [
+            (third, &other, &army_c),
+            (second, &shared, &army_b),
+            (first, &shared, &army_a),
+        ];
+
+        for armies in [forward, reversed] {
+            let (entity, _, found) = army_at_position(armies, shared).unwrap();
+            assert_eq!(entity, first);
+            assert_eq!(found.country_idx, 0);
+            assert_eq!(army_at_position(armies, other).unwrap().0, third);
+            assert!(army_at_position(armies, GridPosition::new(0, 0)).is_none());
+        }
+    }
+
+    #[test]
+    fn next_army_position_in_cycle_order_test() {
+        let army_positions = [
+            GridPosition::new(3, 1),
+            GridPosition::new(1, 5),
+            GridPosition::new(1, 2),
+        ];
+
+        let mut selected_tile = None;
+        let mut visited = Vec::new();
+        for _ in 0..3 {
+            let next = next_army_position_in_cycle(&army_positions, selected_tile).unwrap();
+            selected_tile = Some((next.x, next.y));
+            visited.push(next);
+        }
+
+        assert_eq!(
+            visited,
+            vec![
+                GridPosition::new(1, 2),
+                GridPosition::new(1, 5),
+                GridPosition::new(3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_army_position_in_cycle_wraparound_test() {
+        let army_positions = [GridPosition::new(0, 0), GridPosition::new(2, 2)];
+
+        assert_eq!(
+            next_army_position_in_cycle(&army_positions, Some((2, 2))),
+            Some(GridPosition::new(0, 0))
+        );
+        assert_eq!(
+            next_army_position_in_cycle(&army_positions, Some((5, 5))),
+            Some(GridPosition::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn disband_army_refunds_owner_test() {
+        let mut world = World::new();
+        let army = Army {
+            country_idx: 1,
+            number_of_units: 10,
+        };
+        let army_entity = world.spawn(army.clone()).id();
+        let other_army_entity = world.spawn(army.clone()).id();
+        let mut countries = Countries {
+            countries: vec![
+                Country::new("C0", Color::WHITE),
+                Country::new("C1", Color::BLACK),
+            ],
+        };
+        countries.countries[1].money = 25;
+        let mut army_movements = ArmyMovements::from_world(&mut world);
+        for moved_army_entity in [army_entity, other_army_entity, army_entity] {
+            army_movements.add_movement(MoveArmyMessage {
+                moved_army_entity,
+                target_position: GridPosition::new(1, 1),
+                number_of_units_to_move: 5,
+            });
+        }
+
+        let refund = disband_army(
+            &mut world.commands(),
+            &mut countries,
+            &mut army_movements,
+            army_entity,
+            &army,
+            100,
+            0.25,
+        );
+        world.flush();
+
+        assert_eq!(refund, 250);
+        assert!(world.get_entity(army_entity).is_err());
+        assert_eq!(countries.countries[0].money, 0);
+        assert_eq!(countries.countries[1].money, 275);
+        assert_eq!(disband_refund(&army, 100, 1.5), 1000);
+        assert_eq!(army_movements.movements.len(), 1);
+        assert_eq!(
+            army_movements.movements[0].moved_army_entity,
+            other_army_entity
+        );
+    }
+
+    #[test]
+    fn merge_friendly_armies_keeps_units_over_cap_test() {
+        let mut world = World::new();
+        let oldest = world.spawn_empty().id();
+        let fits = world.spawn_empty().id();
+        let overflows = world.spawn_empty().id();
+        let enemy = world.spawn_empty().id();
+        let map_settings = MapSettings::new(10, 10, 100, 2000, 100, 100);
+
+        let (armies_by_country, absorbed) = merge_friendly_armies(
+            vec![
+                (overflows, 0, 50),
+                (enemy, 1, 70),
+                (fits, 0, 30),
+                (oldest, 0, 40),
+            ],
+            &map_settings,
+        );
+
+        assert_eq!(armies_by_country[&0], (oldest, 100));
+        assert_eq!(armies_by_country[&1], (enemy, 70));
+        let outcome: Vec<_> = absorbed
+            .iter()
+            .map(|army| (army.entity, army.merged_units, army.units_left))
+            .collect();
+        assert_eq!(outcome, vec![(fits, 30, 0), (overflows, 30, 20)]);
+    }
+
+    #[test]
+    fn next_army_position_in_cycle_no_armies_test() {
+        assert_eq!(next_army_position_in_cycle(&[], Some((1, 1))), None);
+    }
+}
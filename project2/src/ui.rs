@@ -4,7 +4,7 @@ use crate::{
     log_error,
     ui::{
         messages::UiClickMessage,
-        resources::{GameLoadState, TurnCounter, UiModel},
+        resources::{GameLoadState, Settings, TurnCounter, UiModel},
         systems::*,
     },
 };
@@ -73,6 +73,7 @@ impl Plugin for UiPlugin {
                     handle_change_relation_audio,
                     handle_battle_audio,
                     update_turn_counter.run_if(in_state(GameState::InGame)),
+                    army_cap_notice_system.run_if(in_state(GameState::InGame)),
                 ),
             )
             .add_systems(
@@ -94,6 +95,7 @@ impl Plugin for UiPlugin {
             .add_message::<UiClickMessage>()
             .init_resource::<TurnCounter>()
             .init_resource::<UiModel>()
+            .init_resource::<Settings>()
             .init_resource::<GameLoadState>()
             .init_resource::<resources::MenuIcons>();
     }
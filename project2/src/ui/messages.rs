@@ -6,15 +6,20 @@ use bevy::ecs::{
 use crate::{
     common::messages::{NextTurnMessage, SaveGameMessage},
     country::messages::{ChangeRelationMessage, ProposePeaceMessage},
-    map::messages::{BuildBuildingMessage, SaveMapMessage, SpawnArmyMessage},
+    map::messages::{
+        BuildBuildingMessage, DisbandArmyMessage, ExportTerrainMessage, SaveMapMessage,
+        SpawnArmyMessage,
+    },
 };
 
 #[derive(SystemParam)]
 pub struct UiGameMessages<'w> {
     pub build_building: MessageWriter<'w, BuildBuildingMessage>,
     pub spawn_army: MessageWriter<'w, SpawnArmyMessage>,
+    pub disband_army: MessageWriter<'w, DisbandArmyMessage>,
     pub change_relation: MessageWriter<'w, ChangeRelationMessage>,
     pub save_map: MessageWriter<'w, SaveMapMessage>,
+    pub export_terrain: MessageWriter<'w, ExportTerrainMessage>,
     pub save_game: MessageWriter<'w, SaveGameMessage>,
     pub ui_click_message: MessageWriter<'w, UiClickMessage>,
     pub next_turn_message: MessageWriter<'w, NextTurnMessage>,
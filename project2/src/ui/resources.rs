@@ -1,4 +1,4 @@
-use bevy::audio::AudioSource;
+use bevy::audio::{AudioSource, PlaybackSettings, Volume};
 use bevy::ecs::{entity::Entity, resource::Resource, world::FromWorld};
 use bevy::image::Image;
 use bevy::prelude::Handle;
@@ -34,6 +34,41 @@ pub struct UiSounds {
     pub battle_sound: Handle<AudioSource>,
 }
 
+pub const SETTINGS_PATH: &str = "settings.json";
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Settings {
+    pub volume: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { volume: 1.0 }
+    }
+}
+
+impl Settings {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn playback_settings(&self) -> PlaybackSettings {
+        PlaybackSettings::DESPAWN.with_volume(Volume::Linear(self.volume.clamp(0.0, 1.0)))
+    }
+}
+
+impl FromWorld for Settings {
+    fn from_world(_: &mut bevy::ecs::world::World) -> Self {
+        Settings::load(SETTINGS_PATH).unwrap_or_default()
+    }
+}
+
 #[derive(Resource)]
 pub struct UiModel {
     pub selected_number_of_units: i32,
@@ -43,6 +78,7 @@ pub struct UiModel {
     pub save_file_name: String,
     pub map_file_name: String,
     pub ai_on: bool,
+    pub army_cap_notice: Option<String>,
 }
 
 impl FromWorld for UiModel {
@@ -55,6 +91,34 @@ impl FromWorld for UiModel {
             save_file_name: "".into(),
             map_file_name: "".into(),
             ai_on: true,
+            army_cap_notice: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_save_load_round_trip_test() {
+        let path = std::env::temp_dir().join("project2_settings_round_trip.json");
+        let path = path.to_str().unwrap();
+        let settings = Settings { volume: 0.25 };
+
+        settings.save(path).unwrap();
+        let loaded = Settings::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded, settings);
+    }
+
+    #[test]
+    fn settings_playback_volume_test() {
+        let playback = Settings { volume: 0.5 }.playback_settings();
+        assert_eq!(playback.volume.to_linear(), 0.5);
+
+        let playback = Settings { volume: 3.0 }.playback_settings();
+        assert_eq!(playback.volume.to_linear(), 1.0);
+    }
+}
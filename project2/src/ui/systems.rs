@@ -29,7 +29,9 @@ use crate::common::systems::{SAVE_PATH, get_save_path};
 use crate::country::messages::{
     AcceptPeaceMessage, ChangeRelationMessage, ProposePeaceMessage, RejectPeaceMessage,
 };
-use crate::map::messages::{ArmyBattleMessage, SaveMapMessage};
+use crate::map::messages::{
+    ArmyBattleMessage, ArmyCapReachedMessage, ExportTerrainMessage, SaveMapMessage,
+};
 use crate::ui::messages::UiClickMessage;
 use crate::ui::resources::UiSounds;
 use crate::{
@@ -38,14 +40,14 @@ use crate::{
     country::{components::OwnershipTile, resources::*},
     map::{
         components::*,
-        messages::{BuildBuildingMessage, MoveArmyMessage, SpawnArmyMessage},
+        messages::{BuildBuildingMessage, DisbandArmyMessage, MoveArmyMessage, SpawnArmyMessage},
         resources::{ArmyMovements, MapSettings, SelectionState},
     },
     player::resources::PlayerData,
     ui::{
         components::{ArmySizeLabel, CountryLabel},
         messages::UiGameMessages,
-        resources::{GameLoadState, MenuIcons, TurnCounter, UiModel},
+        resources::{GameLoadState, MenuIcons, SETTINGS_PATH, Settings, TurnCounter, UiModel},
     },
 };
 use crate::{common::components::GridPosition, log_error};
@@ -68,11 +70,12 @@ pub fn handle_audio(
     mut commands: Commands,
     mut ui_click_message_reader: MessageReader<UiClickMessage>,
     ui_sounds: Res<UiSounds>,
+    settings: Res<Settings>,
 ) {
     for _ in ui_click_message_reader.read() {
         commands.spawn((
             AudioPlayer(ui_sounds.click_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            settings.playback_settings(),
         ));
     }
 }
@@ -81,6 +84,7 @@ pub fn handle_change_relation_audio(
     mut commands: Commands,
     mut relation_change_message_reader: MessageReader<ChangeRelationMessage>,
     ui_sounds: Res<UiSounds>,
+    settings: Res<Settings>,
     player_data: Res<PlayerData>,
 ) {
     for msg in relation_change_message_reader.read() {
@@ -94,13 +98,13 @@ pub fn handle_change_relation_audio(
             RelationStatus::Neutral => {
                 commands.spawn((
                     AudioPlayer(ui_sounds.peace_sound.clone()),
-                    PlaybackSettings::DESPAWN,
+                    settings.playback_settings(),
                 ));
             }
             RelationStatus::AtWar => {
                 commands.spawn((
                     AudioPlayer(ui_sounds.war_sound.clone()),
-                    PlaybackSettings::DESPAWN,
+                    settings.playback_settings(),
                 ));
             }
         }
@@ -111,11 +115,12 @@ pub fn handle_battle_audio(
     mut commands: Commands,
     mut army_battle_message_reader: MessageReader<ArmyBattleMessage>,
     ui_sounds: Res<UiSounds>,
+    settings: Res<Settings>,
 ) {
     for _ in army_battle_message_reader.read() {
         commands.spawn((
             AudioPlayer(ui_sounds.battle_sound.clone()),
-            PlaybackSettings::DESPAWN,
+            settings.playback_settings(),
         ));
     }
 }
@@ -204,6 +209,7 @@ pub struct ControlsUiResources<'w> {
     map_settings: Res<'w, MapSettings>,
     current_state: Res<'w, State<InGameStates>>,
     diplomacy: Res<'w, Diplomacy>,
+    country_incomes: Res<'w, CountryIncomes>,
     next_state: ResMut<'w, NextState<InGameStates>>,
     ui_model: ResMut<'w, UiModel>,
 }
@@ -258,6 +264,13 @@ fn save_map_popup_ui(ctx: &egui::Context, ui_model: &mut UiModel, msgs: &mut UiG
                     });
                     saved_and_close = true;
                 }
+                if ui.button("Eksportuj teren").clicked() {
+                    msgs.ui_click_message.write(UiClickMessage {});
+                    msgs.export_terrain.write(ExportTerrainMessage {
+                        export_name: ui_model.map_file_name.clone(),
+                    });
+                    saved_and_close = true;
+                }
             });
     }
 
@@ -310,7 +323,7 @@ fn selection_ui_components(
     ) && let Some((selected_tile_pos, selected_tile_entity)) =
         get_selected_tile_from_selection_state(&resources.selection_state)
     {
-        country_ui(ui, country);
+        country_ui(ui, country, resources.country_incomes.incomes.get(&idx));
         let (_, has_building) = map_tiles.get(selected_tile_entity)?;
         let army_at_pos = army_query
             .iter()
@@ -440,8 +453,15 @@ fn diplomacy_ui(
             to: idx,
         });
     }
+    let in_truce = resources.diplomacy.is_in_truce(
+        resources.player_data.country_idx,
+        idx,
+        resources.turn_counter.count,
+    );
     if let RelationStatus::Neutral = relation
-        && ui.button("Declare war").clicked()
+        && ui
+            .add_enabled(!in_truce, egui::Button::new("Declare war"))
+            .clicked()
     {
         msgs.ui_click_message.write(UiClickMessage {});
         msgs.change_relation.write(ChangeRelationMessage {
@@ -476,6 +496,27 @@ pub fn display_country_name(
     label.as_mut().0 = "No country selected ".into();
 }
 
+pub fn army_cap_notice_system(
+    mut army_cap_reached_reader: MessageReader<ArmyCapReachedMessage>,
+    mut next_turn_reader: MessageReader<NextTurnMessage>,
+    mut ui_model: ResMut<UiModel>,
+    player_data: Res<PlayerData>,
+) {
+    if next_turn_reader.read().count() > 0 {
+        ui_model.army_cap_notice = None;
+    }
+
+    for message in army_cap_reached_reader.read() {
+        if message.country_idx != player_data.country_idx {
+            continue;
+        }
+        ui_model.army_cap_notice = Some(format!(
+            "Tile {} {} is full, {} units were turned away",
+            message.position.x, message.position.y, message.rejected_units
+        ));
+    }
+}
+
 pub fn update_turn_counter(
     mut turn_counter: ResMut<TurnCounter>,
     mut msgr: MessageReader<NextTurnMessage>,
@@ -491,6 +532,8 @@ fn main_menu_buttons(
     exit: &mut MessageWriter<AppExit>,
     sound: &mut MessageWriter<UiClickMessage>,
     ui_model: &mut UiModel,
+    settings: &mut Settings,
+    game_config: &mut GameConfig,
 ) {
     ui.heading("Project 2");
     ui.add_space(10.0);
@@ -505,12 +548,42 @@ fn main_menu_buttons(
     ui.add_space(5.0);
     ui.checkbox(&mut ui_model.ai_on, "AI on?");
     ui.add_space(5.0);
+    game_config_ui(ui, game_config);
+    ui.add_space(5.0);
+    volume_slider(ui, settings);
+    ui.add_space(5.0);
     if ui.button("Quit").clicked() {
         sound.write(UiClickMessage {});
         exit.write(AppExit::Success);
     }
 }
 
+fn game_config_ui(ui: &mut egui::Ui, game_config: &mut GameConfig) {
+    ui.horizontal(|ui| {
+        ui.label("Starting money:");
+        ui.add(DragValue::new(&mut game_config.starting_money).range(0..=i32::MAX));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Starting army:");
+        ui.add(DragValue::new(&mut game_config.starting_army).range(0..=i32::MAX));
+    });
+    ui.add(
+        egui::Slider::new(&mut game_config.disband_refund_fraction, 0.0..=1.0)
+            .text("Disband refund"),
+    );
+    ui.horizontal(|ui| {
+        ui.label("Starting territory radius:");
+        ui.add(DragValue::new(&mut game_config.starting_territory_radius).range(0..=i32::MAX));
+    });
+}
+
+fn volume_slider(ui: &mut egui::Ui, settings: &mut Settings) {
+    let response = ui.add(egui::Slider::new(&mut settings.volume, 0.0..=1.0).text("Volume"));
+    if response.drag_stopped() || (response.changed() && !response.dragged()) {
+        log_error(In(settings.save(SETTINGS_PATH)));
+    }
+}
+
 fn loading_menu_items(
     ui: &mut egui::Ui,
     next_state: &mut ResMut<'_, NextState<GameState>>,
@@ -540,6 +613,8 @@ pub fn main_menu_system(
     mut exit: MessageWriter<AppExit>,
     mut sound: MessageWriter<UiClickMessage>,
     mut ui_model: ResMut<UiModel>,
+    mut settings: ResMut<Settings>,
+    mut game_config: ResMut<GameConfig>,
 ) -> anyhow::Result<()> {
     let ctx = contexts.ctx_mut()?;
 
@@ -548,7 +623,15 @@ pub fn main_menu_system(
         .resizable(false)
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
         .show(ctx, |ui| {
-            main_menu_buttons(ui, &mut next_state, &mut exit, &mut sound, &mut ui_model);
+            main_menu_buttons(
+                ui,
+                &mut next_state,
+                &mut exit,
+                &mut sound,
+                &mut ui_model,
+                &mut settings,
+                &mut game_config,
+            );
         });
 
     Ok(())
@@ -715,6 +798,9 @@ pub fn handle_selection_change_when_moving_army(
     button_input: Res<ButtonInput<KeyCode>>,
 ) -> anyhow::Result<()> {
     if button_input.just_pressed(KeyCode::Escape) {
+        if let Some(army_entity_being_moved) = ui_model.army_entity_being_moved {
+            army_movements.remove_movements_of(army_entity_being_moved);
+        }
         next_state.set(InGameStates::Idle);
         return Ok(());
     }
@@ -746,10 +832,15 @@ pub fn handle_selection_change_when_moving_army(
 
 // helpers
 
-fn country_ui(ui: &mut egui::Ui, country: &Country) {
+fn country_ui(ui: &mut egui::Ui, country: &Country, income: Option<&IncomeBreakdown>) {
     ui.heading("Country");
     ui.label(format!("Name: {}", country.name));
     ui.label(format!("Money: {}", country.money));
+    if let Some(income) = income {
+        ui.label(format!("Income per turn: {}", income.total()));
+        ui.label(format!("  from tiles: {}", income.tiles));
+        ui.label(format!("  from buildings: {}", income.buildings));
+    }
     ui.separator();
 }
 
@@ -764,6 +855,10 @@ fn turn_ui(
 
     ui.label(format!("Turn number: {turn_number}"));
 
+    if let Some(army_cap_notice) = &resources.ui_model.army_cap_notice {
+        ui.label(army_cap_notice);
+    }
+
     if ui.button("End Turn").clicked() {
         println!("End turn click");
         msgs.ui_click_message.write(UiClickMessage {});
@@ -815,6 +910,18 @@ fn army_ui(
         resources.next_state.set(InGameStates::MovingArmy);
     }
 
+    if *resources.current_state != InGameStates::MovingArmy
+        && let Some((entity, army)) = army_at_pos
+        && army.country_idx == idx
+        && ui.button("Disband").clicked()
+    {
+        ui_game_messages.ui_click_message.write(UiClickMessage {});
+        ui_game_messages.disband_army.write(DisbandArmyMessage {
+            army_entity: entity,
+            country_idx: idx,
+        });
+    }
+
     ui.separator();
 }
 
@@ -995,3 +1102,52 @@ pub fn display_peace_offers_system(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn escape_when_moving_army_removes_pending_movement_test() {
+        let mut world = World::new();
+        let moved_army = world.spawn_empty().id();
+        let other_army = world.spawn_empty().id();
+
+        let mut army_movements = ArmyMovements::from_world(&mut world);
+        army_movements.add_movement(MoveArmyMessage {
+            moved_army_entity: moved_army,
+            target_position: GridPosition::new(1, 0),
+            number_of_units_to_move: 3,
+        });
+        army_movements.add_movement(MoveArmyMessage {
+            moved_army_entity: other_army,
+            target_position: GridPosition::new(0, 1),
+            number_of_units_to_move: 2,
+        });
+        world.insert_resource(army_movements);
+
+        let mut ui_model = UiModel::from_world(&mut world);
+        ui_model.army_entity_being_moved = Some(moved_army);
+        world.insert_resource(ui_model);
+
+        let mut button_input = ButtonInput::<KeyCode>::default();
+        button_input.press(KeyCode::Escape);
+        world.insert_resource(button_input);
+
+        world.init_resource::<SelectionState>();
+        world.init_resource::<NextState<InGameStates>>();
+
+        let result = world.run_system_once(handle_selection_change_when_moving_army);
+
+        assert!(matches!(result, Ok(Ok(()))));
+        let movements = &world.resource::<ArmyMovements>().movements;
+        assert_eq!(movements.len(), 1);
+        assert_eq!(movements[0].moved_army_entity, other_army);
+        assert!(matches!(
+            world.resource::<NextState<InGameStates>>(),
+            NextState::Pending(InGameStates::Idle)
+        ));
+    }
+}
@@ -2,6 +2,7 @@
 #define RED_BLACK_TREE_DICT_H
 
 #include <stdbool.h>
+#include <stddef.h>
 #include <stdint.h>
 
 // Opaque pointer to the Dictionary struct.
@@ -19,12 +20,24 @@ void dict_free(NumberStringDictionary *dict);
 // If the key already exists, the value is updated.
 void dict_insert(NumberStringDictionary *dict, uint64_t key, const char *value);
 
+// Inserts n key-value pairs taken from the parallel keys and values arrays.
+// Entries whose value is NULL are skipped.
+void dict_insert_many(NumberStringDictionary *dict, const uint64_t *keys,
+                      const char *const *values, size_t n);
+
 // Retrieves the value associated with a key.
 // Returns a pointer to the value string, or NULL if the key is not found.
 // The returned string is owned by the dictionary and should not be freed by the
 // caller. It remains valid until the next mutable operation on the dictionary.
 const char *dict_get(const NumberStringDictionary *dict, uint64_t key);
 
+// Copies the value associated with a key into the caller's buffer.
+// At most cap - 1 bytes are copied and the result is always NUL-terminated when
+// cap > 0. Returns the full length of the value (a result >= cap means the copy
+// was truncated), or -1 if the key is not found.
+ptrdiff_t dict_get_copy(const NumberStringDictionary *dict, uint64_t key,
+                        char *out, size_t cap);
+
 // Checks if the dictionary contains a key.
 // Returns true if the key exists, false otherwise.
 bool dict_contains_key(const NumberStringDictionary *dict, uint64_t key);
@@ -1,4 +1,7 @@
-use libc::{c_char, free, malloc, strcpy};
+use libc::c_char;
+#[cfg(not(test))]
+use libc::{free, malloc};
+use std::marker::PhantomData;
 use std::ptr;
 
 // custom string type that manages its own memory.
@@ -43,6 +46,27 @@ impl CustomString {
         self.ptr as *const c_char
     }
 
+    // exact copy, or None if malloc fails
+    pub fn try_clone(&self) -> Option<CustomString> {
+        if self.ptr.is_null() {
+            return Some(Self {
+                ptr: ptr::null_mut(),
+                len: 0,
+            });
+        }
+        let new_ptr = unsafe { malloc(self.len + 1) as *mut c_char };
+        if new_ptr.is_null() {
+            return None;
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr, new_ptr, self.len + 1);
+        }
+        Some(Self {
+            ptr: new_ptr,
+            len: self.len,
+        })
+    }
+
     pub fn as_str(&self) -> &str {
         if self.ptr.is_null() {
             return "";
@@ -66,28 +90,14 @@ impl Drop for CustomString {
     }
 }
 
+// infallible but lossy: yields an empty string if malloc fails, use
+// `try_clone` when the copy must equal the original
 impl Clone for CustomString {
     fn clone(&self) -> Self {
-        if self.ptr.is_null() {
-            return Self {
-                ptr: ptr::null_mut(),
-                len: 0,
-            };
-        }
-        let new_ptr = unsafe { malloc(self.len + 1) as *mut c_char };
-        if new_ptr.is_null() {
-            return Self {
-                ptr: ptr::null_mut(),
-                len: 0,
-            };
-        }
-        unsafe {
-            strcpy(new_ptr, self.ptr);
-        }
-        Self {
-            ptr: new_ptr,
-            len: self.len,
-        }
+        self.try_clone().unwrap_or(Self {
+            ptr: ptr::null_mut(),
+            len: 0,
+        })
     }
 }
 
@@ -117,17 +127,27 @@ enum Color {
     Black,
 }
 
+// how the tree is kept balanced; both share the plain BST insert/remove and rotations
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Balancing {
+    #[default]
+    RedBlack,
+    Avl,
+}
+
 struct Node {
     key: u64,
     value: CustomString,
+    seq: u64,
     color: Color,
+    height: u32, // only kept up to date by the AVL mode
     parent: *mut Node,
     left: *mut Node,
     right: *mut Node,
 }
 
 impl Node {
-    fn new(key: u64, value: CustomString) -> *mut Self {
+    fn new(key: u64, value: CustomString, seq: u64) -> *mut Self {
         unsafe {
             let layout = std::alloc::Layout::new::<Self>();
             let node_ptr = malloc(layout.size()) as *mut Self;
@@ -139,7 +159,9 @@ impl Node {
                 Self {
                     key,
                     value,
+                    seq,
                     color: Color::Red, // easiest to fix
+                    height: 1,
                     parent: ptr::null_mut(),
                     left: ptr::null_mut(),
                     right: ptr::null_mut(),
@@ -152,13 +174,138 @@ impl Node {
 
 pub struct NumberStringDictionary {
     root: *mut Node,
+    next_seq: u64,
+    balancing: Balancing,
 }
 
 impl NumberStringDictionary {
     pub fn new() -> Self {
+        Self::with_balancing(Balancing::RedBlack)
+    }
+
+    pub fn with_balancing(balancing: Balancing) -> Self {
         Self {
             root: ptr::null_mut(),
+            next_seq: 0,
+            balancing,
+        }
+    }
+
+    pub fn balancing(&self) -> Balancing {
+        self.balancing
+    }
+
+    pub fn from_sorted(pairs: Vec<(u64, CustomString)>) -> Self {
+        let mut dict = Self::new();
+        if !pairs.windows(2).all(|w| w[0].0 < w[1].0) {
+            for (key, value) in pairs {
+                dict.insert(key, value);
+            }
+            return dict;
+        }
+        if pairs.is_empty() {
+            return dict;
+        }
+
+        let len = pairs.len();
+        let mut entries = pairs
+            .into_iter()
+            .zip(0..)
+            .map(|((key, value), seq)| (key, value, seq));
+        dict.root = Self::build_balanced(&mut entries, len, 0, len.ilog2() as usize);
+        dict.next_seq = len as u64;
+        dict
+    }
+
+    // merges the batch with the current entries and rebuilds the tree once; as with repeated
+    // `insert` the last value for a key wins and a key keeps the position it was first inserted at
+    pub fn bulk_insert(&mut self, pairs: Vec<(u64, CustomString)>) {
+        let balancing = self.balancing;
+        let first_seq = self.next_seq;
+        let batch_len = pairs.len() as u64;
+        let mut incoming: Vec<_> = pairs
+            .into_iter()
+            .zip(first_seq..)
+            .map(|((key, value), seq)| (key, value, seq))
+            .collect();
+        incoming.sort_by_key(|&(key, _, _)| key);
+
+        let mut sequences = Vec::new();
+        unsafe {
+            Self::collect_sequences(self.root, &mut sequences);
+        }
+        sequences.sort_unstable_by_key(|&(_, key)| key);
+        let mut existing = std::mem::take(self)
+            .into_iter()
+            .zip(sequences)
+            .map(|((key, value), (seq, _))| (key, value, seq))
+            .peekable();
+
+        let mut merged: Vec<(u64, CustomString, u64)> = Vec::with_capacity(incoming.len());
+        for (key, value, seq) in incoming {
+            while let Some(entry) = existing.next_if(|&(existing_key, _, _)| existing_key < key) {
+                merged.push(entry);
+            }
+            match merged.last_mut() {
+                Some(last) if last.0 == key => last.1 = value,
+                _ => {
+                    let seq = existing
+                        .next_if(|&(existing_key, _, _)| existing_key == key)
+                        .map_or(seq, |(_, _, existing_seq)| existing_seq);
+                    merged.push((key, value, seq));
+                }
+            }
+        }
+        merged.extend(existing);
+
+        let len = merged.len();
+        if len > 0 {
+            self.root = Self::build_balanced(&mut merged.into_iter(), len, 0, len.ilog2() as usize);
         }
+        self.next_seq = first_seq + batch_len;
+        self.balancing = balancing;
+    }
+
+    // builds the subtree in order; only the deepest (possibly incomplete) level is red, and
+    // halving the length keeps sibling heights within one of each other for the AVL mode too
+    fn build_balanced(
+        entries: &mut impl Iterator<Item = (u64, CustomString, u64)>,
+        len: usize,
+        depth: usize,
+        red_depth: usize,
+    ) -> *mut Node {
+        if len == 0 {
+            return ptr::null_mut();
+        }
+
+        let left_len = len / 2;
+        let left = Self::build_balanced(entries, left_len, depth + 1, red_depth);
+        let Some((key, value, seq)) = entries.next() else {
+            return left;
+        };
+        let node = Node::new(key, value, seq);
+        let right = Self::build_balanced(entries, len - left_len - 1, depth + 1, red_depth);
+        if node.is_null() {
+            return ptr::null_mut();
+        }
+
+        unsafe {
+            (*node).color = if depth == red_depth && depth > 0 {
+                Color::Red
+            } else {
+                Color::Black
+            };
+            (*node).left = left;
+            (*node).right = right;
+            if !left.is_null() {
+                (*left).parent = node;
+            }
+            if !right.is_null() {
+                (*right).parent = node;
+            }
+            Self::update_height(node);
+        }
+        node
     }
 
     fn find_node(&self, key: u64) -> *mut Node {
@@ -189,8 +336,67 @@ impl NumberStringDictionary {
         !self.find_node(key).is_null()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &CustomString)> {
+        DictIter::new(self.root, false)
+    }
+
+    pub fn iter_rev(&self) -> impl Iterator<Item = (u64, &CustomString)> {
+        DictIter::new(self.root, true)
+    }
+
+    pub fn count_range(&self, lo: u64, hi: u64) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        unsafe { Self::count_subtree_range(self.root, lo, hi) }
+    }
+
+    // skips subtrees lying entirely outside [lo, hi)
+    unsafe fn count_subtree_range(node: *mut Node, lo: u64, hi: u64) -> usize {
+        if node.is_null() {
+            return 0;
+        }
+        let key = (*node).key;
+        if key < lo {
+            return Self::count_subtree_range((*node).right, lo, hi);
+        }
+        if key >= hi {
+            return Self::count_subtree_range((*node).left, lo, hi);
+        }
+        1 + Self::count_subtree_range((*node).left, lo, hi)
+            + Self::count_subtree_range((*node).right, lo, hi)
+    }
+
+    // keys ordered by when they were first inserted; overwrites keep their original position
+    pub fn insertion_order(&self) -> Vec<u64> {
+        let mut entries = Vec::new();
+        unsafe {
+            Self::collect_sequences(self.root, &mut entries);
+        }
+        entries.sort_unstable_by_key(|&(seq, _)| seq);
+        entries.into_iter().map(|(_, key)| key).collect()
+    }
+
+    unsafe fn collect_sequences(node: *mut Node, entries: &mut Vec<(u64, u64)>) {
+        if node.is_null() {
+            return;
+        }
+        entries.push(((*node).seq, (*node).key));
+        Self::collect_sequences((*node).left, entries);
+        Self::collect_sequences((*node).right, entries);
+    }
+
+    // "insert once": an existing key keeps its value and `value` is dropped
+    pub fn insert_if_absent(&mut self, key: u64, value: CustomString) -> bool {
+        if self.contains_key(key) {
+            return false;
+        }
+        self.insert(key, value);
+        self.contains_key(key)
+    }
+
     pub fn insert(&mut self, key: u64, value: CustomString) {
-        let new_node = Node::new(key, value);
+        let new_node = Node::new(key, value, self.next_seq);
         if new_node.is_null() {
             return;
         }
@@ -217,6 +423,7 @@ impl NumberStringDictionary {
         unsafe {
             self.link_new_node(new_node, parent);
         }
+        self.next_seq += 1;
     }
 
     unsafe fn link_new_node(&mut self, new_node: *mut Node, parent: *mut Node) {
@@ -228,7 +435,10 @@ impl NumberStringDictionary {
         } else {
             (*parent).right = new_node;
         }
-        self.insert_fixup(new_node);
+        match self.balancing {
+            Balancing::RedBlack => self.insert_fixup(new_node),
+            Balancing::Avl => self.avl_rebalance(parent),
+        }
     }
 
     unsafe fn insert_fixup(&mut self, mut z: *mut Node) {
@@ -357,8 +567,13 @@ impl NumberStringDictionary {
         }
         unsafe {
             let (x, x_parent, y_original_color) = self.handle_remove_cases(z);
-            if y_original_color == Color::Black {
-                self.remove_fixup(x, x_parent);
+            match self.balancing {
+                Balancing::RedBlack if y_original_color == Color::Black => {
+                    self.remove_fixup(x, x_parent);
+                }
+                Balancing::RedBlack => (),
+                // every height change starts at `x_parent`, the replacement is on its way up
+                Balancing::Avl => self.avl_rebalance(x_parent),
             }
             Self::free_node(z);
         }
@@ -575,6 +790,56 @@ impl NumberStringDictionary {
         self.root
     }
 
+    unsafe fn height(node: *mut Node) -> u32 {
+        if node.is_null() {
+            0
+        } else {
+            (*node).height
+        }
+    }
+
+    unsafe fn update_height(node: *mut Node) {
+        (*node).height = 1 + Self::height((*node).left).max(Self::height((*node).right));
+    }
+
+    unsafe fn balance_factor(node: *mut Node) -> i64 {
+        i64::from(Self::height((*node).left)) - i64::from(Self::height((*node).right))
+    }
+
+    unsafe fn avl_left_rotate(&mut self, x: *mut Node) {
+        self.left_rotate(x);
+        Self::update_height(x);
+        Self::update_height((*x).parent);
+    }
+
+    unsafe fn avl_right_rotate(&mut self, y: *mut Node) {
+        self.right_rotate(y);
+        Self::update_height(y);
+        Self::update_height((*y).parent);
+    }
+
+    // walks up to the root fixing heights and rotating wherever the subtrees differ by two
+    unsafe fn avl_rebalance(&mut self, mut node: *mut Node) {
+        while !node.is_null() {
+            Self::update_height(node);
+            let balance = Self::balance_factor(node);
+            if balance > 1 {
+                if Self::balance_factor((*node).left) < 0 {
+                    self.avl_left_rotate((*node).left);
+                }
+                self.avl_right_rotate(node);
+                node = (*node).parent;
+            } else if balance < -1 {
+                if Self::balance_factor((*node).right) > 0 {
+                    self.avl_right_rotate((*node).right);
+                }
+                self.avl_left_rotate(node);
+                node = (*node).parent;
+            }
+            node = (*node).parent;
+        }
+    }
+
     unsafe fn drop_node(node_ptr: *mut Node) {
         if node_ptr.is_null() {
             return;
@@ -596,6 +861,102 @@ impl NumberStringDictionary {
     }
 }
 
+// in-order traversal with an explicit stack, right-first when reversed
+struct DictIter<'a> {
+    stack: Vec<*mut Node>,
+    reverse: bool,
+    _dict: PhantomData<&'a NumberStringDictionary>,
+}
+
+impl DictIter<'_> {
+    fn new(root: *mut Node, reverse: bool) -> Self {
+        let mut iter = Self {
+            stack: Vec::new(),
+            reverse,
+            _dict: PhantomData,
+        };
+        iter.push_branch(root);
+        iter
+    }
+
+    fn push_branch(&mut self, mut node: *mut Node) {
+        while !node.is_null() {
+            self.stack.push(node);
+            node = unsafe {
+                if self.reverse {
+                    (*node).right
+                } else {
+                    (*node).left
+                }
+            };
+        }
+    }
+}
+
+impl<'a> Iterator for DictIter<'a> {
+    type Item = (u64, &'a CustomString);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        unsafe {
+            let next_branch = if self.reverse {
+                (*node).left
+            } else {
+                (*node).right
+            };
+            self.push_branch(next_branch);
+            Some(((*node).key, &(*node).value))
+        }
+    }
+}
+
+// consuming in-order traversal, each node is freed once its value is moved out
+pub struct DictIntoIter {
+    stack: Vec<*mut Node>,
+}
+
+impl DictIntoIter {
+    fn push_branch(&mut self, mut node: *mut Node) {
+        while !node.is_null() {
+            self.stack.push(node);
+            node = unsafe { (*node).left };
+        }
+    }
+}
+
+impl Iterator for DictIntoIter {
+    type Item = (u64, CustomString);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        unsafe {
+            self.push_branch((*node).right);
+            let key = (*node).key;
+            let value = ptr::read(&(*node).value);
+            free(node as *mut _);
+            Some((key, value))
+        }
+    }
+}
+
+impl Drop for DictIntoIter {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
+
+impl IntoIterator for NumberStringDictionary {
+    type Item = (u64, CustomString);
+    type IntoIter = DictIntoIter;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let root = std::mem::replace(&mut self.root, ptr::null_mut());
+        let mut iter = DictIntoIter { stack: Vec::new() };
+        iter.push_branch(root);
+        iter
+    }
+}
+
 impl Default for NumberStringDictionary {
     fn default() -> Self {
         Self::new()
@@ -674,6 +1035,30 @@ pub mod ffi {
         }
     }
 
+    /// Inserts `n` key/value pairs, skipping values that are not valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// `dict` must be null or a pointer returned by `dict_new` that has not been freed.
+    /// Unless null, `keys` and `values` must each point to at least `n` readable elements,
+    /// and every entry of `values` must be null or a valid NUL-terminated C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn dict_insert_many(
+        dict: *mut NumberStringDictionary,
+        keys: *const u64,
+        values: *const *const c_char,
+        n: usize,
+    ) {
+        if dict.is_null() || keys.is_null() || values.is_null() {
+            return;
+        }
+        let keys = unsafe { std::slice::from_raw_parts(keys, n) };
+        let values = unsafe { std::slice::from_raw_parts(values, n) };
+        for (&key, &value) in keys.iter().zip(values) {
+            unsafe { dict_insert(dict, key, value) };
+        }
+    }
+
     #[no_mangle]
     pub unsafe extern "C" fn dict_get(
         dict: *const NumberStringDictionary,
@@ -689,6 +1074,37 @@ pub mod ffi {
         }
     }
 
+    /// Copies the value for `key` into `out` like `snprintf`: at most `cap - 1` bytes
+    /// plus a terminating NUL. Returns the full value length, or -1 if the key is missing.
+    ///
+    /// # Safety
+    ///
+    /// `dict` must be null or a pointer returned by `dict_new` that has not been freed.
+    /// Unless `out` is null or `cap` is 0, `out` must point to at least `cap` writable bytes.
+    #[no_mangle]
+    pub unsafe extern "C" fn dict_get_copy(
+        dict: *const NumberStringDictionary,
+        key: u64,
+        out: *mut c_char,
+        cap: usize,
+    ) -> isize {
+        if dict.is_null() {
+            return -1;
+        }
+        let dict = unsafe { &*dict };
+        let Some(value) = dict.get(key) else {
+            return -1;
+        };
+        if !out.is_null() && cap > 0 {
+            let copied = value.len().min(cap - 1);
+            unsafe {
+                ptr::copy_nonoverlapping(value.as_ptr(), out, copied);
+                *out.add(copied) = 0;
+            }
+        }
+        value.len() as isize
+    }
+
     #[no_mangle]
     pub unsafe extern "C" fn dict_contains_key(
         dict: *const NumberStringDictionary,
@@ -711,10 +1127,167 @@ pub mod ffi {
     }
 }
 
+#[cfg(test)]
+use tests::alloc::{free, malloc};
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // stand-ins for libc's malloc/free that let tests fail the next allocation and count frees
+    pub mod alloc {
+        use libc::{c_void, size_t};
+        use std::cell::Cell;
+
+        thread_local! {
+            pub static FAIL_NEXT_MALLOC: Cell<bool> = const { Cell::new(false) };
+            pub static FREED: Cell<usize> = const { Cell::new(0) };
+        }
+
+        pub unsafe fn malloc(size: size_t) -> *mut c_void {
+            if FAIL_NEXT_MALLOC.with(|fail| fail.replace(false)) {
+                return std::ptr::null_mut();
+            }
+            unsafe { libc::malloc(size) }
+        }
+
+        pub unsafe fn free(ptr: *mut c_void) {
+            if !ptr.is_null() {
+                FREED.with(|freed| freed.set(freed.get() + 1));
+            }
+            unsafe { libc::free(ptr) }
+        }
+    }
+
+    // returns the black height of the subtree, panicking on any red-black violation
+    fn validate_subtree(node: *mut Node, min: Option<u64>, max: Option<u64>) -> usize {
+        if node.is_null() {
+            return 1;
+        }
+        unsafe {
+            let key = (*node).key;
+            assert!(min.is_none_or(|min| key > min));
+            assert!(max.is_none_or(|max| key < max));
+            for child in [(*node).left, (*node).right] {
+                if !child.is_null() {
+                    assert_eq!((*child).parent, node);
+                    if (*node).color == Color::Red {
+                        assert_eq!((*child).color, Color::Black);
+                    }
+                }
+            }
+            let left_height = validate_subtree((*node).left, min, Some(key));
+            let right_height = validate_subtree((*node).right, Some(key), max);
+            assert_eq!(left_height, right_height);
+            left_height + usize::from((*node).color == Color::Black)
+        }
+    }
+
+    fn validate_tree(dict: &NumberStringDictionary) {
+        if !dict.root.is_null() {
+            unsafe {
+                assert_eq!((*dict.root).color, Color::Black);
+                assert!((*dict.root).parent.is_null());
+            }
+        }
+        validate_subtree(dict.root, None, None);
+    }
+
+    // builds a node with the given children, no balancing or coloring
+    fn node(key: u64, left: *mut Node, right: *mut Node) -> *mut Node {
+        let node = Node::new(key, CustomString::from_s(&key.to_string()), key);
+        unsafe {
+            (*node).left = left;
+            (*node).right = right;
+            for child in [left, right] {
+                if !child.is_null() {
+                    (*child).parent = node;
+                }
+            }
+        }
+        node
+    }
+
+    fn leaf(key: u64) -> *mut Node {
+        node(key, ptr::null_mut(), ptr::null_mut())
+    }
+
+    fn dict_with_root(root: *mut Node) -> NumberStringDictionary {
+        NumberStringDictionary {
+            root,
+            next_seq: 0,
+            balancing: Balancing::RedBlack,
+        }
+    }
+
+    // renders the subtree as "(key left right)", checking parent links on the way
+    fn shape(node: *mut Node) -> String {
+        if node.is_null() {
+            return "-".to_string();
+        }
+        unsafe {
+            for child in [(*node).left, (*node).right] {
+                if !child.is_null() {
+                    assert_eq!((*child).parent, node);
+                }
+            }
+            if (*node).left.is_null() && (*node).right.is_null() {
+                return format!("{}", (*node).key);
+            }
+            format!(
+                "({} {} {})",
+                (*node).key,
+                shape((*node).left),
+                shape((*node).right)
+            )
+        }
+    }
+
+    #[test]
+    fn test_left_rotate_then_right_rotate_at_root() {
+        let mut dict = dict_with_root(node(2, leaf(1), node(4, leaf(3), leaf(5))));
+        let original = shape(dict.root);
+        assert_eq!(original, "(2 1 (4 3 5))");
+
+        unsafe {
+            dict.left_rotate(dict.root);
+            assert_eq!(shape(dict.root), "(4 (2 1 3) 5)");
+            assert!((*dict.root).parent.is_null());
+            assert_eq!((*dict.root).key, 4);
+
+            dict.right_rotate(dict.root);
+            assert!((*dict.root).parent.is_null());
+        }
+        assert_eq!(shape(dict.root), original);
+        assert_eq!(
+            dict.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_rotations_below_root_update_parent_links() {
+        let left = node(2, leaf(1), node(4, leaf(3), leaf(5)));
+        let right = node(8, node(7, leaf(6), ptr::null_mut()), leaf(9));
+        let mut dict = dict_with_root(node(10, left, right));
+        let original = shape(dict.root);
+
+        unsafe {
+            dict.left_rotate(left);
+            assert_eq!(shape(dict.root), "(10 (4 (2 1 3) 5) (8 (7 6 -) 9))");
+            assert_eq!((*(*dict.root).left).key, 4);
+
+            dict.right_rotate(right);
+            assert_eq!(shape(dict.root), "(10 (4 (2 1 3) 5) (7 6 (8 - 9)))");
+            assert_eq!((*(*dict.root).right).key, 7);
+
+            dict.right_rotate((*dict.root).left);
+            dict.left_rotate((*dict.root).right);
+            assert_eq!((*dict.root).key, 10);
+        }
+        assert_eq!(shape(dict.root), original);
+    }
+
     #[test]
     fn test_mystring() {
         let s1 = CustomString::from_s("hello");
@@ -729,6 +1302,24 @@ mod tests {
         assert_ne!(s1, s3);
     }
 
+    #[test]
+    fn test_mystring_try_clone() {
+        let s1 = CustomString::from_s("hello world");
+        let s2 = s1.try_clone().unwrap();
+        assert_eq!(s1, s2);
+        assert_eq!(s2.as_str(), "hello world");
+        assert_ne!(s1.as_ptr(), s2.as_ptr());
+
+        let with_nul = CustomString::from_s("a\0b");
+        assert_eq!(with_nul.try_clone().unwrap(), with_nul);
+
+        let empty = CustomString::from_s("");
+        let empty_clone = empty.try_clone().unwrap();
+        assert_eq!(empty, empty_clone);
+        assert!(empty_clone.is_empty());
+        assert_eq!(empty_clone.as_str(), "");
+    }
+
     #[test]
     fn test_dict_insert_and_get() {
         let mut dict = NumberStringDictionary::new();
@@ -751,6 +1342,41 @@ mod tests {
         assert_eq!(dict.get(10), Some(&CustomString::from_s("ten-updated")));
     }
 
+    #[test]
+    fn test_dict_insert_if_absent() {
+        let mut dict = NumberStringDictionary::new();
+
+        assert!(dict.insert_if_absent(7, CustomString::from_s("first")));
+        assert!(!dict.insert_if_absent(7, CustomString::from_s("second")));
+        assert_eq!(dict.get(7).unwrap().as_str(), "first");
+
+        assert!(dict.insert_if_absent(3, CustomString::from_s("other")));
+        assert_eq!(dict.iter().count(), 2);
+        assert_eq!(dict.insertion_order(), vec![7, 3]);
+        validate_tree(&dict);
+    }
+
+    #[test]
+    fn test_dict_insert_drops_value_on_alloc_failure() {
+        let mut dict = dict! { 1 => "one" };
+
+        let value = CustomString::from_s("two");
+        alloc::FAIL_NEXT_MALLOC.with(|fail| fail.set(true));
+        let freed_before = alloc::FREED.with(|freed| freed.get());
+        dict.insert(2, value);
+        let freed_after = alloc::FREED.with(|freed| freed.get());
+
+        // only the value's buffer is released, the node itself was never allocated
+        assert_eq!(freed_after, freed_before + 1);
+        assert!(!dict.contains_key(2));
+        assert_eq!(dict.get(1).unwrap().as_str(), "one");
+        validate_tree(&dict);
+
+        // the stand-in only fails a single allocation
+        dict.insert(2, CustomString::from_s("two"));
+        assert_eq!(dict.get(2).unwrap().as_str(), "two");
+    }
+
     #[test]
     fn test_dict_remove() {
         let mut dict = NumberStringDictionary::new();
@@ -794,6 +1420,392 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dict_iter_rev() {
+        let mut dict = NumberStringDictionary::new();
+        for key in 0..10 {
+            dict.insert(key, CustomString::from_s(&key.to_string()));
+        }
+
+        let keys: Vec<u64> = dict.iter_rev().map(|(key, _)| key).collect();
+        assert_eq!(keys, (0..10).rev().collect::<Vec<u64>>());
+
+        for (key, value) in dict.iter_rev() {
+            assert_eq!(*value, CustomString::from_s(&key.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_dict_iter_and_iter_rev_same_entries() {
+        let mut dict = NumberStringDictionary::new();
+        let keys = [10, 20, 5, 15, 25, 3, 8, 1, 4, 7, 9];
+        for &key in &keys {
+            dict.insert(key, CustomString::from_s(&key.to_string()));
+        }
+        dict.remove(5);
+
+        let forward: Vec<u64> = dict.iter().map(|(key, _)| key).collect();
+        let mut reverse: Vec<u64> = dict.iter_rev().map(|(key, _)| key).collect();
+        reverse.reverse();
+
+        assert_eq!(forward, vec![1, 3, 4, 7, 8, 9, 10, 15, 20, 25]);
+        assert_eq!(forward, reverse);
+    }
+
+    #[test]
+    fn test_dict_into_iter() {
+        let keys = [10, 20, 5, 15, 25, 3, 8, 1, 4, 7, 9];
+        let mut dict = NumberStringDictionary::new();
+        for &key in &keys {
+            dict.insert(key, CustomString::from_s(&format!("v{key}")));
+        }
+        dict.remove(5);
+
+        let pairs: Vec<(u64, CustomString)> = dict.into_iter().collect();
+
+        let mut expected: Vec<u64> = keys.iter().copied().filter(|&key| key != 5).collect();
+        expected.sort();
+        assert_eq!(
+            pairs.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            expected
+        );
+        for (key, value) in &pairs {
+            assert_eq!(*value, CustomString::from_s(&format!("v{key}")));
+        }
+    }
+
+    #[test]
+    fn test_dict_into_iter_partial_and_empty() {
+        let dict = dict! { 3 => "c", 1 => "a", 2 => "b" };
+        let mut iter = dict.into_iter();
+        let (key, value) = iter.next().unwrap();
+        assert_eq!(key, 1);
+        assert_eq!(value.as_str(), "a");
+        // remaining nodes are freed on drop
+        drop(iter);
+
+        let mut count = 0;
+        for _ in NumberStringDictionary::new() {
+            count += 1;
+        }
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_dict_iter_empty() {
+        let dict = NumberStringDictionary::new();
+
+        assert_eq!(dict.iter().count(), 0);
+        assert_eq!(dict.iter_rev().count(), 0);
+    }
+
+    #[test]
+    fn test_dict_from_sorted() {
+        let pairs = (0..1000)
+            .map(|key| (key, CustomString::from_s(&key.to_string())))
+            .collect();
+        let dict = NumberStringDictionary::from_sorted(pairs);
+
+        validate_tree(&dict);
+        for key in 0..1000 {
+            assert_eq!(dict.get(key), Some(&CustomString::from_s(&key.to_string())));
+        }
+        assert!(!dict.contains_key(1000));
+    }
+
+    #[test]
+    fn test_dict_from_sorted_small_sizes() {
+        for len in 0..70 {
+            let pairs = (0..len)
+                .map(|key| (key, CustomString::from_s(&key.to_string())))
+                .collect();
+            let mut dict = NumberStringDictionary::from_sorted(pairs);
+
+            validate_tree(&dict);
+            assert_eq!(dict.iter().count(), len as usize);
+
+            dict.insert(len, CustomString::from_s("new"));
+            dict.remove(0);
+            validate_tree(&dict);
+        }
+    }
+
+    #[test]
+    fn test_dict_from_unsorted_falls_back_to_insert() {
+        let pairs = vec![
+            (3, CustomString::from_s("three")),
+            (1, CustomString::from_s("one")),
+            (3, CustomString::from_s("three-updated")),
+        ];
+        let dict = NumberStringDictionary::from_sorted(pairs);
+
+        validate_tree(&dict);
+        assert_eq!(dict.get(1), Some(&CustomString::from_s("one")));
+        assert_eq!(dict.get(3), Some(&CustomString::from_s("three-updated")));
+    }
+
+    // returns the subtree height, panicking on a wrong stored height or an AVL violation
+    fn validate_avl_subtree(node: *mut Node, min: Option<u64>, max: Option<u64>) -> u32 {
+        if node.is_null() {
+            return 0;
+        }
+        unsafe {
+            let key = (*node).key;
+            assert!(min.is_none_or(|min| key > min));
+            assert!(max.is_none_or(|max| key < max));
+            for child in [(*node).left, (*node).right] {
+                if !child.is_null() {
+                    assert_eq!((*child).parent, node);
+                }
+            }
+            let left_height = validate_avl_subtree((*node).left, min, Some(key));
+            let right_height = validate_avl_subtree((*node).right, Some(key), max);
+            assert!(left_height.abs_diff(right_height) <= 1);
+            assert_eq!((*node).height, 1 + left_height.max(right_height));
+            (*node).height
+        }
+    }
+
+    fn validate_avl_tree(dict: &NumberStringDictionary) {
+        if !dict.root.is_null() {
+            unsafe {
+                assert!((*dict.root).parent.is_null());
+            }
+        }
+        validate_avl_subtree(dict.root, None, None);
+    }
+
+    #[test]
+    fn test_dict_balancing_modes_agree() {
+        let mut red_black = NumberStringDictionary::new();
+        let mut avl = NumberStringDictionary::with_balancing(Balancing::Avl);
+        assert_eq!(red_black.balancing(), Balancing::RedBlack);
+        assert_eq!(avl.balancing(), Balancing::Avl);
+
+        let mut state: u64 = 42;
+        let mut next_key = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1);
+            (state >> 33) % 500
+        };
+        let inserted: Vec<u64> = (0..1000).map(|_| next_key()).collect();
+        let removed: Vec<u64> = (0..400).map(|_| next_key()).collect();
+
+        for dict in [&mut red_black, &mut avl] {
+            for key in 0..64 {
+                dict.insert(key, CustomString::from_s(&format!("seq{key}")));
+            }
+            for key in &inserted {
+                dict.insert(*key, CustomString::from_s(&key.to_string()));
+            }
+            for key in &removed {
+                dict.remove(*key);
+            }
+            for key in (0..64).rev().step_by(3) {
+                dict.remove(key);
+            }
+        }
+
+        validate_tree(&red_black);
+        validate_avl_tree(&avl);
+        for key in 0..520 {
+            assert_eq!(red_black.get(key), avl.get(key), "key = {key}");
+        }
+        assert!(red_black.iter().eq(avl.iter()));
+        assert_eq!(red_black.insertion_order(), avl.insertion_order());
+
+        for key in 0..500 {
+            avl.remove(key);
+            validate_avl_tree(&avl);
+        }
+        assert!(avl.root.is_null());
+    }
+
+    #[test]
+    fn test_dict_avl_bulk_insert_keeps_mode() {
+        let mut avl = NumberStringDictionary::with_balancing(Balancing::Avl);
+        avl.insert(5, CustomString::from_s("five"));
+        avl.bulk_insert(
+            (0..100)
+                .map(|key| (key * 2, CustomString::from_s(&key.to_string())))
+                .collect(),
+        );
+
+        assert_eq!(avl.balancing(), Balancing::Avl);
+        validate_avl_tree(&avl);
+        for key in 200..260 {
+            avl.insert(key, CustomString::from_s("new"));
+        }
+        validate_avl_tree(&avl);
+        assert_eq!(avl.get(5), Some(&CustomString::from_s("five")));
+    }
+
+    #[test]
+    fn test_dict_bulk_insert_random_keys() {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next_key = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % 1_000_000
+        };
+        let keys: Vec<u64> = (0..100_000).map(|_| next_key()).collect();
+
+        let mut dict = NumberStringDictionary::new();
+        for key in keys.iter().take(1000) {
+            dict.insert(*key, CustomString::from_s("old"));
+        }
+        dict.bulk_insert(
+            keys.iter()
+                .map(|key| (*key, CustomString::from_s(&key.to_string())))
+                .collect(),
+        );
+
+        validate_tree(&dict);
+        for key in &keys {
+            assert_eq!(
+                dict.get(*key),
+                Some(&CustomString::from_s(&key.to_string()))
+            );
+        }
+        let mut unique = keys.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(dict.iter().map(|(key, _)| key).collect::<Vec<_>>(), unique);
+
+        dict.insert(1_000_001, CustomString::from_s("after"));
+        dict.remove(keys[0]);
+        validate_tree(&dict);
+    }
+
+    #[test]
+    fn test_dict_bulk_insert_merges_existing() {
+        let mut dict = NumberStringDictionary::new();
+        dict.insert(5, CustomString::from_s("five"));
+        dict.insert(1, CustomString::from_s("one"));
+
+        dict.bulk_insert(vec![
+            (7, CustomString::from_s("seven")),
+            (1, CustomString::from_s("one-updated")),
+            (3, CustomString::from_s("three")),
+            (7, CustomString::from_s("seven-updated")),
+        ]);
+        dict.insert(2, CustomString::from_s("two"));
+
+        validate_tree(&dict);
+        assert_eq!(dict.get(1), Some(&CustomString::from_s("one-updated")));
+        assert_eq!(dict.get(5), Some(&CustomString::from_s("five")));
+        assert_eq!(dict.get(7), Some(&CustomString::from_s("seven-updated")));
+        assert_eq!(dict.insertion_order(), vec![5, 1, 7, 3, 2]);
+
+        dict.bulk_insert(Vec::new());
+        validate_tree(&dict);
+        assert_eq!(dict.iter().count(), 5);
+    }
+
+    #[test]
+    fn test_dict_count_range() {
+        let mut dict = NumberStringDictionary::new();
+        for key in 0..100 {
+            dict.insert(key, CustomString::from_s(&key.to_string()));
+        }
+
+        assert_eq!(dict.count_range(10, 20), 10);
+        assert_eq!(dict.count_range(0, 100), 100);
+        assert_eq!(dict.count_range(95, 1000), 5);
+        assert_eq!(dict.count_range(20, 20), 0);
+        assert_eq!(dict.count_range(30, 10), 0);
+        assert_eq!(NumberStringDictionary::new().count_range(0, 10), 0);
+    }
+
+    #[test]
+    fn test_dict_insertion_order() {
+        let mut dict = NumberStringDictionary::new();
+        let keys = [42, 7, 19, 3, 88, 1];
+        for &key in &keys {
+            dict.insert(key, CustomString::from_s(&key.to_string()));
+        }
+        dict.insert(19, CustomString::from_s("nineteen"));
+
+        assert_eq!(dict.insertion_order(), keys.to_vec());
+        let sorted: Vec<u64> = dict.iter().map(|(key, _)| key).collect();
+        assert_eq!(sorted, vec![1, 3, 7, 19, 42, 88]);
+        assert_eq!(dict.get(19), Some(&CustomString::from_s("nineteen")));
+    }
+
+    #[test]
+    fn test_dict_insertion_order_after_remove() {
+        let mut dict = NumberStringDictionary::new();
+        for key in [10, 20, 5, 15, 25, 3] {
+            dict.insert(key, CustomString::from_s(&key.to_string()));
+        }
+        dict.remove(10);
+        dict.insert(10, CustomString::from_s("back"));
+
+        assert_eq!(dict.insertion_order(), vec![20, 5, 15, 25, 3, 10]);
+    }
+
+    #[test]
+    fn test_ffi_dict_insert_many() {
+        use std::ffi::{CStr, CString};
+
+        let keys = [3u64, 1, 2, 4];
+        let strings: Vec<CString> = ["three", "one", "two"]
+            .iter()
+            .map(|s| CString::new(*s).unwrap())
+            .collect();
+        let mut values: Vec<*const c_char> = strings.iter().map(|s| s.as_ptr()).collect();
+        values.push(ptr::null());
+
+        unsafe {
+            let dict = ffi::dict_new();
+            ffi::dict_insert_many(dict, keys.as_ptr(), values.as_ptr(), keys.len());
+            ffi::dict_insert_many(dict, ptr::null(), values.as_ptr(), keys.len());
+
+            for (key, expected) in [(1, "one"), (2, "two"), (3, "three")] {
+                let value = ffi::dict_get(dict, key);
+                assert!(!value.is_null());
+                assert_eq!(CStr::from_ptr(value).to_str().unwrap(), expected);
+            }
+            assert!(!ffi::dict_contains_key(dict, 4));
+
+            ffi::dict_free(dict);
+        }
+    }
+
+    #[test]
+    fn test_ffi_dict_get_copy() {
+        use std::ffi::{CStr, CString};
+
+        let value = CString::new("seventeen").unwrap();
+        unsafe {
+            let dict = ffi::dict_new();
+            ffi::dict_insert(dict, 17, value.as_ptr());
+
+            let mut small = [1 as c_char; 5];
+            let len = ffi::dict_get_copy(dict, 17, small.as_mut_ptr(), small.len());
+            assert_eq!(len, 9);
+            assert_eq!(CStr::from_ptr(small.as_ptr()).to_str().unwrap(), "seve");
+
+            let mut large = [1 as c_char; 32];
+            let len = ffi::dict_get_copy(dict, 17, large.as_mut_ptr(), large.len());
+            assert_eq!(len, 9);
+            assert_eq!(
+                CStr::from_ptr(large.as_ptr()).to_str().unwrap(),
+                "seventeen"
+            );
+
+            assert_eq!(ffi::dict_get_copy(dict, 17, ptr::null_mut(), 0), 9);
+            assert_eq!(
+                ffi::dict_get_copy(dict, 18, large.as_mut_ptr(), large.len()),
+                -1
+            );
+
+            ffi::dict_free(dict);
+        }
+    }
+
     #[test]
     fn test_dict_macro() {
         let dict = dict! {
@@ -1,7 +1,17 @@
+pub mod alter_command;
 pub mod command;
+pub mod count_command;
 pub mod create_command;
+pub mod dedup_command;
 pub mod delete_command;
+pub mod dry_run_command;
+pub mod dump_command;
+pub mod explain_command;
 pub mod insert_command;
+pub mod keys_command;
 pub mod read_from_command;
+pub mod rename_command;
 pub mod save_as_command;
 pub mod select_command;
+pub mod select_into_command;
+pub mod timed_command;
@@ -0,0 +1,165 @@
+use crate::{
+    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    database::{key::DatabaseKey, table::Table},
+};
+
+pub struct AlterRenameColumnCommand<'a, K: DatabaseKey> {
+    pub table: &'a mut Table<K>,
+    pub column_name: String,
+    pub new_column_name: String,
+}
+
+impl<K: DatabaseKey> Command for AlterRenameColumnCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        self.table
+            .rename_column(&self.column_name, &self.new_column_name)?;
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<AlterRenameColumnCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: AlterRenameColumnCommand<'a, K>) -> Self {
+        Self::AlterRenameColumnCommand(value)
+    }
+}
+
+pub struct AlterRenameKeyCommand<'a, K: DatabaseKey> {
+    pub table: &'a mut Table<K>,
+    pub new_key_name: String,
+}
+
+impl<K: DatabaseKey> Command for AlterRenameKeyCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        self.table.set_key_name(&self.new_key_name)?;
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<AlterRenameKeyCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: AlterRenameKeyCommand<'a, K>) -> Self {
+        Self::AlterRenameKeyCommand(value)
+    }
+}
+
+pub struct AlterRekeyCommand<'a, K: DatabaseKey> {
+    pub table: &'a mut Table<K>,
+    pub new_key_name: String,
+}
+
+impl<K: DatabaseKey> Command for AlterRekeyCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        self.table.rekey(&self.new_key_name)?;
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<AlterRekeyCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: AlterRekeyCommand<'a, K>) -> Self {
+        Self::AlterRekeyCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::table::{ColumnType, TableError, record::Value};
+
+    use super::*;
+
+    fn prepare_test_table() -> Table<i64> {
+        let mut table = Table::new_builder("Users".to_string(), "UserId".to_string())
+            .with_column("Age".to_string(), ColumnType::INT)
+            .build()
+            .unwrap();
+
+        table
+            .insert(
+                vec!["UserId".to_string(), "Age".to_string()],
+                vec![Value::INT(1), Value::INT(30)],
+            )
+            .unwrap();
+        table
+    }
+
+    #[test]
+    fn alter_rename_column_success_test() {
+        let mut table = prepare_test_table();
+
+        let command = AlterRenameColumnCommand {
+            table: &mut table,
+            column_name: "Age".to_string(),
+            new_column_name: "Years".to_string(),
+        };
+
+        assert!(matches!(command.execute(), Ok(CommandResult::Void)));
+        assert_eq!(
+            table.get(&1).unwrap().get_value("Years"),
+            Ok(&Value::INT(30))
+        );
+    }
+
+    #[test]
+    fn alter_rename_key_success_test() {
+        let mut table = prepare_test_table();
+
+        let command = AlterRenameKeyCommand {
+            table: &mut table,
+            new_key_name: "Id".to_string(),
+        };
+
+        assert!(matches!(command.execute(), Ok(CommandResult::Void)));
+        assert_eq!(table.get_key_name(), "Id");
+        assert_eq!(table.get(&1).unwrap().get_value("Id"), Ok(&Value::INT(1)));
+    }
+
+    #[test]
+    fn alter_rename_key_collision_test() {
+        let mut table = prepare_test_table();
+
+        let command = AlterRenameKeyCommand {
+            table: &mut table,
+            new_key_name: "Age".to_string(),
+        };
+
+        assert_eq!(
+            command.execute().unwrap_err(),
+            CommandError::TableError(TableError::ColumnAlreadyExistsError("Age".to_string()))
+        );
+        assert_eq!(table.get_key_name(), "UserId");
+    }
+
+    #[test]
+    fn alter_rekey_success_test() {
+        let mut table = prepare_test_table();
+
+        let command = AlterRekeyCommand {
+            table: &mut table,
+            new_key_name: "Age".to_string(),
+        };
+
+        assert!(matches!(command.execute(), Ok(CommandResult::Void)));
+        assert_eq!(table.get_key_name(), "Age");
+        assert_eq!(
+            table.get(&30).unwrap().get_value("UserId"),
+            Ok(&Value::INT(1))
+        );
+    }
+
+    #[test]
+    fn alter_rename_key_column_test() {
+        let mut table = prepare_test_table();
+
+        let command = AlterRenameColumnCommand {
+            table: &mut table,
+            column_name: "UserId".to_string(),
+            new_column_name: "Id".to_string(),
+        };
+
+        assert_eq!(
+            command.execute().unwrap_err(),
+            CommandError::TableError(TableError::KeyColumnUpdateError("UserId".to_string()))
+        );
+    }
+}
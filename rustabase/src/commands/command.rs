@@ -1,10 +1,24 @@
+use std::collections::HashMap;
+
 use thiserror::Error;
 
 use crate::{
     commands::{
-        create_command::CreateCommand, delete_command::DeleteCommand,
-        insert_command::InsertCommand, read_from_command::ReadFromCommand,
-        save_as_command::SaveAsCommand, select_command::SelectCommand,
+        alter_command::{AlterRekeyCommand, AlterRenameColumnCommand, AlterRenameKeyCommand},
+        count_command::CountCommand,
+        create_command::CreateCommand,
+        dedup_command::DedupCommand,
+        delete_command::{DeleteCommand, DeleteWhereCommand},
+        dry_run_command::DryRunCommand,
+        dump_command::DumpCommand,
+        explain_command::ExplainCommand,
+        insert_command::{InsertCommand, InsertRecordCommand},
+        keys_command::KeysCommand,
+        read_from_command::ReadFromCommand,
+        rename_command::RenameCommand,
+        save_as_command::SaveAsCommand,
+        select_command::SelectCommand,
+        select_into_command::SelectIntoCommand,
     },
     database::{
         DatabaseError,
@@ -42,6 +56,15 @@ pub enum CommandError {
         expected_type: String,
         got_type: String,
     },
+
+    #[error("Key column {0} must be selected")]
+    MissingKeyColumnError(String),
+
+    #[error("DRY_RUN supports only INSERT and DELETE commands")]
+    DryRunUnsupportedError,
+
+    #[error("Page size must be positive")]
+    InvalidPageSizeError,
 }
 
 #[derive(Debug)]
@@ -51,13 +74,40 @@ pub enum CommandResult {
     CommandList(Vec<String>),
 }
 
+impl CommandResult {
+    pub fn into_row_maps(self) -> Option<Vec<HashMap<String, Value>>> {
+        match self {
+            CommandResult::RecordValueList(columns, records) => Some(
+                records
+                    .into_iter()
+                    .map(|record| columns.iter().cloned().zip(record).collect())
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
 pub enum AnyCommand<'a, K: DatabaseKey> {
     CreateCommand(CreateCommand<'a, K>),
     DeleteCommand(DeleteCommand<'a, K>),
+    DeleteWhereCommand(DeleteWhereCommand<'a, K>),
     InsertCommand(InsertCommand<'a, K>),
+    InsertRecordCommand(InsertRecordCommand<'a, K>),
     SelectCommand(SelectCommand<'a, K>),
     SaveAsCommand(SaveAsCommand<'a>),
     ReadFromCommand(ReadFromCommand),
+    RenameCommand(RenameCommand<'a, K>),
+    ExplainCommand(ExplainCommand<'a, K>),
+    SelectIntoCommand(SelectIntoCommand<'a, K>),
+    KeysCommand(KeysCommand<'a, K>),
+    CountCommand(CountCommand<'a, K>),
+    AlterRenameColumnCommand(AlterRenameColumnCommand<'a, K>),
+    AlterRenameKeyCommand(AlterRenameKeyCommand<'a, K>),
+    AlterRekeyCommand(AlterRekeyCommand<'a, K>),
+    DryRunCommand(DryRunCommand<'a, K>),
+    DedupCommand(DedupCommand<'a, K>),
+    DumpCommand(DumpCommand<'a, K>),
 }
 
 pub trait Command {
@@ -69,10 +119,52 @@ impl<K: DatabaseKey> Command for AnyCommand<'_, K> {
         match self {
             AnyCommand::CreateCommand(create_command) => create_command.execute(),
             AnyCommand::DeleteCommand(delete_command) => delete_command.execute(),
+            AnyCommand::DeleteWhereCommand(delete_where_command) => delete_where_command.execute(),
             AnyCommand::InsertCommand(insert_command) => insert_command.execute(),
+            AnyCommand::InsertRecordCommand(insert_record_command) => {
+                insert_record_command.execute()
+            }
             AnyCommand::SelectCommand(select_command) => select_command.execute(),
             AnyCommand::SaveAsCommand(save_as_command) => save_as_command.execute(),
             AnyCommand::ReadFromCommand(read_from_command) => read_from_command.execute(),
+            AnyCommand::RenameCommand(rename_command) => rename_command.execute(),
+            AnyCommand::ExplainCommand(explain_command) => explain_command.execute(),
+            AnyCommand::SelectIntoCommand(select_into_command) => select_into_command.execute(),
+            AnyCommand::KeysCommand(keys_command) => keys_command.execute(),
+            AnyCommand::CountCommand(count_command) => count_command.execute(),
+            AnyCommand::AlterRenameColumnCommand(alter_command) => alter_command.execute(),
+            AnyCommand::AlterRenameKeyCommand(alter_command) => alter_command.execute(),
+            AnyCommand::AlterRekeyCommand(alter_command) => alter_command.execute(),
+            AnyCommand::DryRunCommand(dry_run_command) => dry_run_command.execute(),
+            AnyCommand::DedupCommand(dedup_command) => dedup_command.execute(),
+            AnyCommand::DumpCommand(dump_command) => dump_command.execute(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_row_maps_test() {
+        let result = CommandResult::RecordValueList(
+            vec!["Id".to_string(), "Name".to_string()],
+            vec![
+                vec![Value::INT(1), Value::STRING("Alice".to_string())],
+                vec![Value::INT(2), Value::STRING("Bob".to_string())],
+            ],
+        );
+
+        let rows = result.into_row_maps().unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["Id"], Value::INT(1));
+        assert_eq!(rows[0]["Name"], Value::STRING("Alice".to_string()));
+        assert_eq!(rows[1]["Id"], Value::INT(2));
+        assert_eq!(rows[1]["Name"], Value::STRING("Bob".to_string()));
+        assert_eq!(rows[1].len(), 2);
+
+        assert!(CommandResult::Void.into_row_maps().is_none());
+    }
+}
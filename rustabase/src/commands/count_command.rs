@@ -0,0 +1,105 @@
+use crate::{
+    commands::{
+        command::{AnyCommand, Command, CommandError, CommandResult},
+        select_command::{AnyWhereFilter, WhereFilter},
+    },
+    database::{
+        key::DatabaseKey,
+        table::{Table, record::Value},
+    },
+};
+
+pub struct CountCommand<'a, K: DatabaseKey> {
+    pub table: &'a Table<K>,
+    pub where_filter: AnyWhereFilter,
+}
+
+impl<K: DatabaseKey> Command for CountCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        for record in self.table.iter_records() {
+            self.where_filter.validate_filtering(record)?;
+        }
+
+        let count = self
+            .table
+            .iter_records()
+            .filter(|record| self.where_filter.filter_record(record))
+            .count();
+
+        Ok(CommandResult::RecordValueList(
+            vec!["Count".to_string()],
+            vec![vec![Value::INT(count as i64)]],
+        ))
+    }
+}
+
+impl<'a, K: DatabaseKey> From<CountCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: CountCommand<'a, K>) -> Self {
+        Self::CountCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::select_command::{AnyFilter, NoOpWhereFilter, Operator, ValueOperatorFilter},
+        database::table::ColumnType,
+    };
+
+    fn prepare_test_table() -> Table<i64> {
+        let mut table = Table::new_builder("Users".to_string(), "UserId".to_string())
+            .with_column("Age".to_string(), ColumnType::INT)
+            .build()
+            .unwrap();
+
+        for (id, age) in [(1, 16), (2, 24), (3, 30)] {
+            table
+                .insert(
+                    vec!["UserId".to_string(), "Age".to_string()],
+                    vec![Value::INT(id), Value::INT(age)],
+                )
+                .unwrap();
+        }
+        table
+    }
+
+    fn count_of(result: Result<CommandResult, CommandError>) -> Vec<Vec<Value>> {
+        match result {
+            Ok(CommandResult::RecordValueList(columns, rows)) => {
+                assert_eq!(columns, vec!["Count"]);
+                rows
+            }
+            other => panic!("Expected RecordValueList, received: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn count_command_with_filter_test() {
+        let table = prepare_test_table();
+
+        let command = CountCommand {
+            table: &table,
+            where_filter: ValueOperatorFilter {
+                column_name: "Age".into(),
+                op: Operator::Gt,
+                value: Value::INT(18),
+            }
+            .to_enum(),
+        };
+
+        assert_eq!(count_of(command.execute()), vec![vec![Value::INT(2)]]);
+    }
+
+    #[test]
+    fn count_command_without_filter_test() {
+        let table = prepare_test_table();
+
+        let command = CountCommand {
+            table: &table,
+            where_filter: NoOpWhereFilter {}.to_enum(),
+        };
+
+        assert_eq!(count_of(command.execute()), vec![vec![Value::INT(3)]]);
+    }
+}
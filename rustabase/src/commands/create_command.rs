@@ -1,5 +1,8 @@
 use crate::{
-    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    commands::{
+        command::{AnyCommand, Command, CommandError, CommandResult},
+        select_command::AnyWhereFilter,
+    },
     database::{Database, key::DatabaseKey, table::ColumnType},
 };
 
@@ -9,15 +12,17 @@ pub struct CreateCommand<'a, K: DatabaseKey> {
     pub key_name: String,
     pub fields: Vec<String>,
     pub types: Vec<ColumnType>,
+    pub checks: Vec<(String, AnyWhereFilter)>,
 }
 
 impl<K: DatabaseKey> Command for CreateCommand<'_, K> {
     fn execute(self) -> Result<CommandResult, CommandError> {
-        self.database.create_table(
-            self.table_name.clone(),
-            self.key_name.clone(),
-            self.fields.clone(),
-            self.types.clone(),
+        self.database.create_table_with_checks(
+            self.table_name,
+            self.key_name,
+            self.fields,
+            self.types,
+            self.checks,
         )?;
 
         Ok(CommandResult::Void)
@@ -49,6 +54,7 @@ mod tests {
             key_name: key_name.clone(),
             fields: fields.clone(),
             types: types.clone(),
+            checks: Vec::new(),
         };
 
         command.execute().unwrap();
@@ -89,6 +95,7 @@ mod tests {
             key_name: key_name.clone(),
             fields: fields.clone(),
             types: types.clone(),
+            checks: Vec::new(),
         };
 
         command1.execute().unwrap();
@@ -99,6 +106,7 @@ mod tests {
             key_name: key_name.clone(),
             fields: fields.clone(),
             types: types.clone(),
+            checks: Vec::new(),
         };
 
         let result = command2.execute();
@@ -128,6 +136,7 @@ mod tests {
             key_name: key_name.clone(),
             fields: fields.clone(),
             types: types.clone(),
+            checks: Vec::new(),
         };
 
         command1.execute().unwrap();
@@ -138,6 +147,7 @@ mod tests {
             key_name: key_name.clone(),
             fields: fields.clone(),
             types: types.clone(),
+            checks: Vec::new(),
         };
 
         let result = command2.execute();
@@ -167,6 +177,7 @@ mod tests {
             key_name,
             fields,
             types,
+            checks: Vec::new(),
         };
 
         let result = command.execute();
@@ -184,6 +195,58 @@ mod tests {
             ))
         );
     }
+    #[test]
+    fn create_table_with_field_named_as_key() {
+        let mut db = Database::<i64>::new();
+
+        let command = CreateCommand {
+            database: &mut db,
+            table_name: String::from("Users"),
+            key_name: String::from("UserId"),
+            fields: vec![String::from("Name"), String::from("UserId")],
+            types: vec![ColumnType::STRING, ColumnType::INT],
+            checks: Vec::new(),
+        };
+
+        let err = command.execute().unwrap_err();
+
+        assert_eq!(
+            err,
+            CommandError::DatabaseError(crate::database::DatabaseError::TableError(
+                crate::database::table::TableError::ColumnCollidesWithKeyError("UserId".into())
+            ))
+        );
+        assert!(db.get_table("Users").is_err());
+    }
+
+    #[test]
+    fn create_table_with_two_same_columns_string() {
+        let mut db = Database::<String>::new();
+
+        let command = CreateCommand {
+            database: &mut db,
+            table_name: String::from("Users"),
+            key_name: String::from("UserId"),
+            fields: vec![String::from("Name"), String::from("Name")],
+            types: vec![ColumnType::STRING, ColumnType::STRING],
+            checks: Vec::new(),
+        };
+
+        let err = command.execute().unwrap_err();
+
+        assert_eq!(
+            err,
+            CommandError::DatabaseError(crate::database::DatabaseError::TableError(
+                crate::database::table::TableError::ColumnDefinedTwiceError {
+                    column_name: "Name".into(),
+                    first_type: ColumnType::STRING,
+                    second_type: ColumnType::STRING
+                }
+            ))
+        );
+        assert!(db.get_table("Users").is_err());
+    }
+
     #[test]
     fn create_table_basic_string() {
         let mut db = Database::<String>::new();
@@ -199,6 +262,7 @@ mod tests {
             key_name: key_name.clone(),
             fields: fields.clone(),
             types: types.clone(),
+            checks: Vec::new(),
         };
 
         command.execute().unwrap();
@@ -0,0 +1,93 @@
+use crate::{
+    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    database::{
+        key::DatabaseKey,
+        table::{Table, record::Value},
+    },
+};
+
+pub struct DedupCommand<'a, K: DatabaseKey> {
+    pub table: &'a mut Table<K>,
+    pub columns: Vec<String>,
+}
+
+impl<K: DatabaseKey> Command for DedupCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        let deleted = self.table.dedup(&self.columns)?;
+
+        Ok(CommandResult::RecordValueList(
+            vec!["Deleted".to_string()],
+            vec![vec![Value::INT(deleted as i64)]],
+        ))
+    }
+}
+
+impl<'a, K: DatabaseKey> From<DedupCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: DedupCommand<'a, K>) -> Self {
+        Self::DedupCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::table::{ColumnType, TableError};
+
+    use super::*;
+
+    fn prepare_test_table() -> Table<i64> {
+        let mut table = Table::new_builder("Users".to_string(), "UserId".to_string())
+            .with_column("Name".to_string(), ColumnType::STRING)
+            .with_column("Age".to_string(), ColumnType::INT)
+            .build()
+            .unwrap();
+
+        for (id, name, age) in [(3, "Ann", 20), (1, "Ann", 20), (2, "Ann", 21)] {
+            table
+                .insert(
+                    vec!["UserId".to_string(), "Name".to_string(), "Age".to_string()],
+                    vec![
+                        Value::INT(id),
+                        Value::STRING(name.to_string()),
+                        Value::INT(age),
+                    ],
+                )
+                .unwrap();
+        }
+        table
+    }
+
+    #[test]
+    fn dedup_command_success_test() {
+        let mut table = prepare_test_table();
+
+        let command = DedupCommand {
+            table: &mut table,
+            columns: vec!["Name".to_string(), "Age".to_string()],
+        };
+
+        match command.execute() {
+            Ok(CommandResult::RecordValueList(columns, rows)) => {
+                assert_eq!(columns, vec!["Deleted".to_string()]);
+                assert_eq!(rows, vec![vec![Value::INT(1)]]);
+            }
+            _ => panic!("Expected RecordValueList"),
+        }
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn dedup_command_invalid_column_test() {
+        let mut table = prepare_test_table();
+
+        let command = DedupCommand {
+            table: &mut table,
+            columns: vec!["Email".to_string()],
+        };
+
+        assert_eq!(
+            command.execute().unwrap_err(),
+            CommandError::TableError(TableError::InvalidColumnNameError("Email".to_string()))
+        );
+        assert_eq!(table.keys().count(), 3);
+    }
+}
@@ -1,6 +1,12 @@
 use crate::{
-    commands::command::{AnyCommand, Command, CommandError, CommandResult},
-    database::{key::DatabaseKey, table::Table},
+    commands::{
+        command::{AnyCommand, Command, CommandError, CommandResult},
+        select_command::{AnyWhereFilter, WhereFilter},
+    },
+    database::{
+        key::DatabaseKey,
+        table::{Table, record::Value},
+    },
 };
 
 pub struct DeleteCommand<'a, K: DatabaseKey> {
@@ -8,6 +14,13 @@ pub struct DeleteCommand<'a, K: DatabaseKey> {
     pub key: K,
 }
 
+impl<K: DatabaseKey> DeleteCommand<'_, K> {
+    pub fn validate(&self) -> Result<(), CommandError> {
+        self.table.validate_delete(&self.key)?;
+        Ok(())
+    }
+}
+
 impl<K: DatabaseKey> Command for DeleteCommand<'_, K> {
     fn execute(self) -> Result<CommandResult, CommandError> {
         self.table.delete(self.key.clone())?;
@@ -21,12 +34,50 @@ impl<'a, K: DatabaseKey> From<DeleteCommand<'a, K>> for AnyCommand<'a, K> {
     }
 }
 
+pub struct DeleteWhereCommand<'a, K: DatabaseKey> {
+    pub table: &'a mut Table<K>,
+    pub where_filter: AnyWhereFilter,
+}
+
+impl<K: DatabaseKey> DeleteWhereCommand<'_, K> {
+    pub fn validate(&self) -> Result<(), CommandError> {
+        for record in self.table.iter_records() {
+            self.where_filter.validate_filtering(record)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: DatabaseKey> Command for DeleteWhereCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        self.validate()?;
+
+        let deleted = self
+            .table
+            .delete_where(|record| self.where_filter.filter_record(record));
+
+        Ok(CommandResult::RecordValueList(
+            vec!["Deleted".to_string()],
+            vec![vec![Value::INT(deleted as i64)]],
+        ))
+    }
+}
+
+impl<'a, K: DatabaseKey> From<DeleteWhereCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: DeleteWhereCommand<'a, K>) -> Self {
+        Self::DeleteWhereCommand(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        commands::command::CommandError,
-        database::table::{ColumnType, record::Value},
+        commands::{
+            command::CommandError,
+            select_command::{AnyFilter, Operator, ValueOperatorFilter},
+        },
+        database::table::ColumnType,
     };
 
     fn prepare_test_table() -> Table<i64> {
@@ -100,7 +151,7 @@ mod tests {
         assert_eq!(
             result.unwrap_err(),
             CommandError::TableError(crate::database::table::TableError::KeyNotFoundError(
-                missing_key.to_value()
+                missing_key.to_value().unwrap()
             ))
         );
         assert_eq!(table.filter(|_| true).len(), 1);
@@ -124,7 +175,7 @@ mod tests {
         assert_eq!(
             result.unwrap_err(),
             CommandError::TableError(crate::database::table::TableError::KeyNotFoundError(
-                missing_key.to_value()
+                missing_key.to_value().unwrap()
             ))
         );
         assert_eq!(table.filter(|_| true).len(), 1);
@@ -147,4 +198,53 @@ mod tests {
 
         assert_eq!(table.filter(|_| true).len(), 0);
     }
+
+    #[test]
+    fn delete_where_removes_matching_records_test() {
+        let mut table = prepare_test_table();
+        table
+            .insert(
+                vec!["ProductId".to_string(), "Name".to_string()],
+                vec![Value::INT(2), Value::STRING("Phone".to_string())],
+            )
+            .unwrap();
+
+        let command = DeleteWhereCommand {
+            table: &mut table,
+            where_filter: ValueOperatorFilter {
+                column_name: "Name".into(),
+                op: Operator::Eq,
+                value: Value::STRING("Phone".into()),
+            }
+            .to_enum(),
+        };
+
+        match command.execute().unwrap() {
+            CommandResult::RecordValueList(_, rows) => assert_eq!(rows, vec![vec![Value::INT(1)]]),
+            _ => panic!("Expected RecordValueList"),
+        }
+        assert!(table.get(&1).is_some());
+        assert!(table.get(&2).is_none());
+    }
+
+    #[test]
+    fn delete_where_invalid_filter_keeps_records_test() {
+        let mut table = prepare_test_table();
+
+        let command = DeleteWhereCommand {
+            table: &mut table,
+            where_filter: ValueOperatorFilter {
+                column_name: "Name".into(),
+                op: Operator::Eq,
+                value: Value::INT(3),
+            }
+            .to_enum(),
+        };
+
+        assert!(matches!(
+            command.execute(),
+            Err(CommandError::InvalidValueError { .. })
+        ));
+        assert_eq!(table.filter(|_| true).len(), 1);
+    }
 }
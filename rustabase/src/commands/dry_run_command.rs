@@ -0,0 +1,152 @@
+use crate::{
+    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    database::key::DatabaseKey,
+};
+
+pub struct DryRunCommand<'a, K: DatabaseKey> {
+    pub command: Box<AnyCommand<'a, K>>,
+}
+
+impl<K: DatabaseKey> Command for DryRunCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        match *self.command {
+            AnyCommand::InsertCommand(insert_command) => insert_command.validate()?,
+            AnyCommand::InsertRecordCommand(insert_record_command) => {
+                insert_record_command.validate()?
+            }
+            AnyCommand::DeleteCommand(delete_command) => delete_command.validate()?,
+            AnyCommand::DeleteWhereCommand(delete_where_command) => {
+                delete_where_command.validate()?
+            }
+            _ => return Err(CommandError::DryRunUnsupportedError),
+        }
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<DryRunCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: DryRunCommand<'a, K>) -> Self {
+        Self::DryRunCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::{delete_command::DeleteCommand, insert_command::InsertCommand},
+        database::table::{ColumnType, Table, TableError, record::Value},
+    };
+
+    use super::*;
+
+    fn prepare_test_table() -> Table<i64> {
+        let mut table = Table::new_builder("Orders".to_string(), "OrderId".to_string())
+            .with_column("Capacity".to_string(), ColumnType::INT)
+            .build()
+            .unwrap();
+
+        table
+            .insert(
+                vec!["OrderId".to_string(), "Capacity".to_string()],
+                vec![Value::INT(1), Value::INT(100)],
+            )
+            .unwrap();
+
+        table
+    }
+
+    fn dry_run_insert(
+        table: &mut Table<i64>,
+        values: Vec<Value>,
+    ) -> Result<CommandResult, CommandError> {
+        DryRunCommand {
+            command: Box::new(
+                InsertCommand {
+                    table,
+                    fields: vec!["OrderId".to_string(), "Capacity".to_string()],
+                    values,
+                }
+                .into(),
+            ),
+        }
+        .execute()
+    }
+
+    #[test]
+    fn dry_run_valid_insert_test() {
+        let mut table = prepare_test_table();
+
+        let result = dry_run_insert(&mut table, vec![Value::INT(2), Value::INT(50)]);
+
+        assert!(matches!(result, Ok(CommandResult::Void)));
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn dry_run_invalid_insert_test() {
+        let mut table = prepare_test_table();
+
+        let result = dry_run_insert(
+            &mut table,
+            vec![Value::INT(2), Value::STRING("Many".to_string())],
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::TableError(TableError::InsertInvalidColumnTypeError {
+                column_name: "Capacity".into(),
+                expected_type: ColumnType::INT,
+                got_type: ColumnType::STRING,
+            })
+        );
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn dry_run_duplicate_key_insert_test() {
+        let mut table = prepare_test_table();
+
+        let result = dry_run_insert(&mut table, vec![Value::INT(1), Value::INT(50)]);
+
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::TableError(TableError::PrimaryKeyConstraintViolation(Value::INT(1)))
+        );
+        assert_eq!(
+            table.get(&1).unwrap().get_value("Capacity"),
+            Ok(&Value::INT(100))
+        );
+    }
+
+    #[test]
+    fn dry_run_delete_test() {
+        let mut table = prepare_test_table();
+
+        let missing = DryRunCommand {
+            command: Box::new(
+                DeleteCommand {
+                    table: &mut table,
+                    key: 2,
+                }
+                .into(),
+            ),
+        };
+        assert_eq!(
+            missing.execute().unwrap_err(),
+            CommandError::TableError(TableError::KeyNotFoundError(Value::INT(2)))
+        );
+
+        let existing = DryRunCommand {
+            command: Box::new(
+                DeleteCommand {
+                    table: &mut table,
+                    key: 1,
+                }
+                .into(),
+            ),
+        };
+        assert!(existing.execute().is_ok());
+        assert!(table.get(&1).is_some());
+    }
+}
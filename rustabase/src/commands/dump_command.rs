@@ -0,0 +1,239 @@
+use std::{fs::File, io::Write};
+
+use crate::{
+    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    database::{
+        Database,
+        key::DatabaseKey,
+        table::{Table, record::Value},
+    },
+};
+
+pub struct DumpCommand<'a, K: DatabaseKey> {
+    pub database: &'a Database<K>,
+    pub file_name: String,
+}
+
+impl<K: DatabaseKey> DumpCommand<'_, K> {
+    pub fn statements(&self) -> Result<Vec<String>, CommandError> {
+        let mut statements = Vec::new();
+
+        for table in self.database.iter_tables() {
+            statements.push(create_statement(table));
+            statements.extend(insert_statements(table)?);
+        }
+
+        Ok(statements)
+    }
+}
+
+impl<K: DatabaseKey> Command for DumpCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        let statements = self.statements()?;
+
+        let mut file =
+            File::create(&self.file_name).map_err(|e| CommandError::IoError(e.to_string()))?;
+
+        for statement in statements {
+            writeln!(file, "{statement}").map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<DumpCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: DumpCommand<'a, K>) -> Self {
+        Self::DumpCommand(value)
+    }
+}
+
+fn field_names<K: DatabaseKey>(table: &Table<K>) -> Vec<&str> {
+    let mut fields: Vec<&str> = table
+        .get_columns()
+        .keys()
+        .map(String::as_str)
+        .filter(|name| *name != table.get_key_name())
+        .collect();
+    fields.sort_unstable();
+    fields
+}
+
+// checks are table-wide, so each one is attached to the next field in order; they are sorted so
+// the dump does not depend on the order in which they were added
+fn create_statement<K: DatabaseKey>(table: &Table<K>) -> String {
+    let mut descriptions: Vec<&str> = table
+        .get_checks()
+        .iter()
+        .map(|(description, _)| description.as_str())
+        .collect();
+    descriptions.sort_unstable();
+    let mut checks = descriptions.into_iter();
+    let fields: Vec<String> = field_names(table)
+        .into_iter()
+        .map(|name| {
+            let column_type = &table.get_columns()[name];
+            match checks.next() {
+                Some(description) => format!("{name}:{column_type:?} CHECK ({description})"),
+                None => format!("{name}:{column_type:?}"),
+            }
+        })
+        .collect();
+
+    let mut statement = format!("CREATE {} KEY {}", table.get_name(), table.get_key_name());
+    if !fields.is_empty() {
+        statement.push_str(&format!(" FIELDS {}", fields.join(", ")));
+    }
+    statement
+}
+
+fn insert_statements<K: DatabaseKey>(table: &Table<K>) -> Result<Vec<String>, CommandError> {
+    let mut columns = vec![table.get_key_name()];
+    columns.extend(field_names(table));
+
+    table
+        .iter_records()
+        .map(|record| {
+            let assignments = columns
+                .iter()
+                .map(|column| Ok(format!("{column}={}", literal(record.get_value(column)?))))
+                .collect::<Result<Vec<_>, CommandError>>()?;
+
+            Ok(format!(
+                "INSERT {} INTO {}",
+                assignments.join(", "),
+                table.get_name()
+            ))
+        })
+        .collect()
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::BOOL(b) => b.to_string(),
+        Value::STRING(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        Value::INT(i) => i.to_string(),
+        Value::FLOAT(f) => {
+            let text = f.to_string();
+            if text.contains('.') {
+                text
+            } else {
+                format!("{text}.0")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{database::table::ColumnType, parser::CommandParser};
+
+    use super::*;
+
+    #[test]
+    fn dump_command_statements_test() {
+        let mut db = Database::<i64>::new();
+        db.create_table(
+            "Items".to_string(),
+            "ItemId".to_string(),
+            vec![
+                "Price".to_string(),
+                "Name".to_string(),
+                "Active".to_string(),
+            ],
+            vec![ColumnType::FLOAT, ColumnType::STRING, ColumnType::BOOL],
+        )
+        .unwrap();
+        db.get_table("Items")
+            .unwrap()
+            .insert(
+                vec![
+                    "ItemId".to_string(),
+                    "Price".to_string(),
+                    "Name".to_string(),
+                    "Active".to_string(),
+                ],
+                vec![
+                    Value::INT(7),
+                    Value::FLOAT(3.0),
+                    Value::STRING("Lamp".to_string()),
+                    Value::BOOL(true),
+                ],
+            )
+            .unwrap();
+
+        let command = DumpCommand {
+            database: &db,
+            file_name: "unused.sql".to_string(),
+        };
+
+        assert_eq!(
+            command.statements().unwrap(),
+            vec![
+                "CREATE Items KEY ItemId FIELDS Active:BOOL, Name:STRING, Price:FLOAT".to_string(),
+                "INSERT ItemId=7, Active=true, Name=\"Lamp\", Price=3.0 INTO Items".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn dump_command_invalid_path_test() {
+        let db = Database::<i64>::new();
+        let command = DumpCommand {
+            database: &db,
+            file_name: "/invalid/path/dump.sql".to_string(),
+        };
+
+        assert!(matches!(command.execute(), Err(CommandError::IoError(_))));
+    }
+
+    #[test]
+    fn dump_command_sorts_checks_and_escapes_strings_test() {
+        let mut db = Database::<i64>::new();
+        db.create_table_with_checks(
+            "Notes".to_string(),
+            "NoteId".to_string(),
+            vec!["Text".to_string(), "Votes".to_string()],
+            vec![ColumnType::STRING, ColumnType::INT],
+            vec![
+                (
+                    "Votes >= 0".to_string(),
+                    CommandParser::parse_check("Votes >= 0").unwrap(),
+                ),
+                (
+                    "Text != \"\"".to_string(),
+                    CommandParser::parse_check("Text != \"\"").unwrap(),
+                ),
+            ],
+        )
+        .unwrap();
+        db.get_table("Notes")
+            .unwrap()
+            .insert(
+                vec![
+                    "NoteId".to_string(),
+                    "Text".to_string(),
+                    "Votes".to_string(),
+                ],
+                vec![
+                    Value::INT(1),
+                    Value::STRING(r#"say "hi" \ bye"#.to_string()),
+                    Value::INT(3),
+                ],
+            )
+            .unwrap();
+
+        let command = DumpCommand {
+            database: &db,
+            file_name: "unused.sql".to_string(),
+        };
+
+        assert_eq!(
+            command.statements().unwrap(),
+            vec![
+                r#"CREATE Notes KEY NoteId FIELDS Text:STRING CHECK (Text != ""), Votes:INT CHECK (Votes >= 0)"#.to_string(),
+                r#"INSERT NoteId=1, Text="say \"hi\" \\ bye", Votes=3 INTO Notes"#.to_string(),
+            ]
+        );
+    }
+}
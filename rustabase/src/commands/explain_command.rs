@@ -0,0 +1,169 @@
+use crate::{
+    commands::{
+        command::{AnyCommand, Command, CommandError, CommandResult},
+        select_command::{AnyWhereFilter, SelectCommand, SortDirection},
+    },
+    database::{key::DatabaseKey, table::record::Value},
+};
+
+pub struct ExplainCommand<'a, K: DatabaseKey> {
+    pub select: SelectCommand<'a, K>,
+}
+
+impl<K: DatabaseKey> Command for ExplainCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        let access = match self.select.where_filter {
+            AnyWhereFilter::KeyIn(_) => "key lookup",
+            _ => "full scan",
+        };
+
+        let order = match self.select.order_by.as_slice() {
+            [] => "none".to_string(),
+            order_by => order_by
+                .iter()
+                .map(|(column, direction)| match direction {
+                    SortDirection::Asc => format!("{column} ASC"),
+                    SortDirection::Desc => format!("{column} DESC"),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+
+        let steps = [
+            ("Table", self.select.table.get_name().to_string()),
+            ("Access", access.to_string()),
+            ("Filter", self.select.where_filter.describe()),
+            ("Order", order),
+            ("Columns", self.select.selected_columns.join(", ")),
+        ];
+
+        let rows = steps
+            .into_iter()
+            .map(|(step, detail)| vec![Value::STRING(step.into()), Value::STRING(detail)])
+            .collect();
+
+        Ok(CommandResult::RecordValueList(
+            vec!["Step".to_string(), "Detail".to_string()],
+            rows,
+        ))
+    }
+}
+
+impl<'a, K: DatabaseKey> From<ExplainCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: ExplainCommand<'a, K>) -> Self {
+        Self::ExplainCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::select_command::{AnyFilter, NoOpWhereFilter, Operator, ValueOperatorFilter},
+        database::{Database, table::ColumnType},
+    };
+
+    use super::*;
+
+    fn prepare_database() -> Database<i64> {
+        let mut db = Database::new();
+        db.create_table(
+            "Users".to_string(),
+            "UserId".to_string(),
+            vec!["Name".to_string(), "Age".to_string()],
+            vec![ColumnType::STRING, ColumnType::INT],
+        )
+        .unwrap();
+        db
+    }
+
+    fn explain_rows(command: ExplainCommand<'_, i64>) -> Vec<Vec<Value>> {
+        match command.execute().unwrap() {
+            CommandResult::RecordValueList(columns, rows) => {
+                assert_eq!(columns, vec!["Step", "Detail"]);
+                rows
+            }
+            _ => panic!("Expected RecordValueList"),
+        }
+    }
+
+    fn detail_of(rows: &[Vec<Value>], step: &str) -> Value {
+        rows.iter()
+            .find(|row| row[0] == Value::STRING(step.into()))
+            .map(|row| row[1].clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn explain_reports_full_scan_for_unindexed_filter() {
+        let mut db = prepare_database();
+        let table = db.get_table("Users").unwrap();
+
+        let filter = ValueOperatorFilter {
+            column_name: "Age".into(),
+            op: Operator::Gt,
+            value: Value::INT(18),
+        }
+        .to_enum();
+
+        let rows = explain_rows(ExplainCommand {
+            select: SelectCommand::new(table, vec!["Name".into()], filter),
+        });
+
+        assert_eq!(detail_of(&rows, "Table"), Value::STRING("Users".into()));
+        assert_eq!(
+            detail_of(&rows, "Access"),
+            Value::STRING("full scan".into())
+        );
+        assert_eq!(
+            detail_of(&rows, "Filter"),
+            Value::STRING("Age > INT 18".into())
+        );
+        assert_eq!(detail_of(&rows, "Columns"), Value::STRING("Name".into()));
+    }
+
+    #[test]
+    fn explain_reports_order_by() {
+        let mut db = prepare_database();
+        let table = db.get_table("Users").unwrap();
+
+        let rows = explain_rows(ExplainCommand {
+            select: SelectCommand::new(table, vec!["Name".into()], NoOpWhereFilter {}.to_enum())
+                .with_order_by(vec![
+                    ("Age".into(), SortDirection::Desc),
+                    ("Name".into(), SortDirection::Asc),
+                ]),
+        });
+
+        let steps: Vec<Value> = rows.iter().map(|row| row[0].clone()).collect();
+        assert_eq!(
+            steps,
+            ["Table", "Access", "Filter", "Order", "Columns"]
+                .map(|step| Value::STRING(step.into()))
+        );
+        assert_eq!(
+            detail_of(&rows, "Order"),
+            Value::STRING("Age DESC, Name ASC".into())
+        );
+    }
+
+    #[test]
+    fn explain_without_where_reports_no_filter() {
+        let mut db = prepare_database();
+        let table = db.get_table("Users").unwrap();
+
+        let rows = explain_rows(ExplainCommand {
+            select: SelectCommand::new(
+                table,
+                vec!["Name".into(), "Age".into()],
+                NoOpWhereFilter {}.to_enum(),
+            ),
+        });
+
+        assert_eq!(detail_of(&rows, "Filter"), Value::STRING("none".into()));
+        assert_eq!(detail_of(&rows, "Order"), Value::STRING("none".into()));
+        assert_eq!(
+            detail_of(&rows, "Columns"),
+            Value::STRING("Name, Age".into())
+        );
+    }
+}
@@ -2,7 +2,10 @@ use crate::{
     commands::command::{AnyCommand, Command, CommandError, CommandResult},
     database::{
         key::DatabaseKey,
-        table::{Table, record::Value},
+        table::{
+            Table,
+            record::{Record, Value},
+        },
     },
 };
 
@@ -12,6 +15,14 @@ pub struct InsertCommand<'a, K: DatabaseKey> {
     pub values: Vec<Value>,
 }
 
+impl<K: DatabaseKey> InsertCommand<'_, K> {
+    pub fn validate(&self) -> Result<(), CommandError> {
+        self.table
+            .validate_insert(self.fields.clone(), self.values.clone())?;
+        Ok(())
+    }
+}
+
 impl<K: DatabaseKey> Command for InsertCommand<'_, K> {
     fn execute(self) -> Result<CommandResult, CommandError> {
         self.table
@@ -26,6 +37,31 @@ impl<'a, K: DatabaseKey> From<InsertCommand<'a, K>> for AnyCommand<'a, K> {
     }
 }
 
+pub struct InsertRecordCommand<'a, K: DatabaseKey> {
+    pub table: &'a mut Table<K>,
+    pub record: Record,
+}
+
+impl<K: DatabaseKey> InsertRecordCommand<'_, K> {
+    pub fn validate(&self) -> Result<(), CommandError> {
+        self.table.validate_insert_record(&self.record)?;
+        Ok(())
+    }
+}
+
+impl<K: DatabaseKey> Command for InsertRecordCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        self.table.insert_record(self.record)?;
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<InsertRecordCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: InsertRecordCommand<'a, K>) -> Self {
+        Self::InsertRecordCommand(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::database::table::ColumnType;
@@ -242,6 +278,49 @@ mod tests {
         )
     }
 
+    #[test]
+    fn insert_record_command_test() {
+        let mut table = prepare_test_table();
+
+        let record = Record::new_builder()
+            .with_column("OrderId".to_string(), Value::INT(1))
+            .with_column("ClientName".to_string(), Value::STRING("Firma ABC".into()))
+            .with_column("Capacity".to_string(), Value::INT(100))
+            .build()
+            .unwrap();
+
+        let command: AnyCommand<_> = InsertRecordCommand {
+            table: &mut table,
+            record,
+        }
+        .into();
+
+        assert!(command.execute().is_ok());
+        assert_eq!(
+            table.get(&1).unwrap().get_value("Capacity").unwrap(),
+            &Value::INT(100)
+        );
+
+        let duplicate = Record::new_builder()
+            .with_column("OrderId".to_string(), Value::INT(1))
+            .with_column("ClientName".to_string(), Value::STRING("Firma XYZ".into()))
+            .with_column("Capacity".to_string(), Value::INT(5))
+            .build()
+            .unwrap();
+
+        let command = InsertRecordCommand {
+            table: &mut table,
+            record: duplicate,
+        };
+
+        assert_eq!(
+            command.validate().unwrap_err(),
+            CommandError::TableError(
+                crate::database::table::TableError::PrimaryKeyConstraintViolation(Value::INT(1))
+            )
+        );
+    }
+
     #[test]
     fn insert_command_success_test_string() {
         let mut table = prepare_test_table_string();
@@ -0,0 +1,68 @@
+use crate::{
+    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    database::{key::DatabaseKey, table::Table},
+};
+
+pub struct KeysCommand<'a, K: DatabaseKey> {
+    pub table: &'a Table<K>,
+}
+
+impl<K: DatabaseKey> Command for KeysCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        let keys = self
+            .table
+            .keys()
+            // stored keys were built with `from_value`, so they always convert back
+            .filter_map(|key| key.clone().to_value())
+            .map(|value| vec![value])
+            .collect();
+
+        Ok(CommandResult::RecordValueList(
+            vec![self.table.get_key_name().to_string()],
+            keys,
+        ))
+    }
+}
+
+impl<'a, K: DatabaseKey> From<KeysCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: KeysCommand<'a, K>) -> Self {
+        Self::KeysCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::table::{ColumnType, record::Value};
+
+    #[test]
+    fn keys_command_returns_sorted_keys_test() {
+        let mut table = Table::<String>::new_builder("Users".to_string(), "Login".to_string())
+            .with_column("Age".to_string(), ColumnType::INT)
+            .build()
+            .unwrap();
+        for login in ["carol", "alice", "bob"] {
+            table
+                .insert(
+                    vec!["Login".to_string(), "Age".to_string()],
+                    vec![Value::STRING(login.to_string()), Value::INT(30)],
+                )
+                .unwrap();
+        }
+
+        let result = KeysCommand { table: &table }.execute().unwrap();
+
+        let CommandResult::RecordValueList(columns, rows) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(columns, vec!["Login"]);
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::STRING("alice".to_string())],
+                vec![Value::STRING("bob".to_string())],
+                vec![Value::STRING("carol".to_string())],
+            ]
+        );
+    }
+}
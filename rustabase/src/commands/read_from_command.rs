@@ -2,7 +2,8 @@ use std::{fs::File, io::Read};
 
 use crate::{
     commands::command::{AnyCommand, Command, CommandError, CommandResult},
-    database::key::DatabaseKey,
+    database::{Database, key::DatabaseKey},
+    parser::CommandParser,
 };
 
 pub struct ReadFromCommand {
@@ -35,6 +36,65 @@ impl Command for ReadFromCommand {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayMode {
+    StopOnError,
+    ContinueOnError,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReplayFailure {
+    pub line: usize,
+    pub command: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReplayReport {
+    pub applied: usize,
+    pub failures: Vec<ReplayFailure>,
+}
+
+pub fn replay_commands<K: DatabaseKey>(
+    db: &mut Database<K>,
+    command_parser: &mut CommandParser,
+    commands: Vec<String>,
+    mode: ReplayMode,
+    mut on_result: impl FnMut(CommandResult),
+) -> ReplayReport {
+    let mut report = ReplayReport::default();
+
+    for (idx, command_str) in commands.into_iter().enumerate() {
+        let result = match command_parser.parse_command(db, &command_str) {
+            Err(e) => Err(e.to_string()),
+            Ok(command) => command.execute().map_err(|e| {
+                command_parser.remove_last_saved_line();
+                e.to_string()
+            }),
+        };
+
+        match result {
+            Ok(result) => {
+                report.applied += 1;
+                on_result(result);
+            }
+            Err(error) => {
+                report.failures.push(ReplayFailure {
+                    line: idx + 1,
+                    command: command_str,
+                    error,
+                });
+
+                if mode == ReplayMode::StopOnError {
+                    break;
+                }
+            }
+        }
+    }
+
+    report
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -85,4 +145,32 @@ mod tests {
             _ => panic!("Expected IO error"),
         }
     }
+
+    fn commands_with_bad_middle_line() -> Vec<String> {
+        vec![
+            "CREATE Users KEY UserId FIELDS Name:STRING".to_string(),
+            "INSERT UserId=1, Name=\"John\" INTO Users".to_string(),
+            "INSERT UserId=2, Name=3 INTO Users".to_string(),
+            "INSERT UserId=3, Name=\"Jane\" INTO Users".to_string(),
+        ]
+    }
+
+    #[test]
+    fn replay_stop_on_error_test() {
+        let mut db = Database::<i64>::new();
+        let mut parser = CommandParser::new();
+
+        let report = replay_commands(
+            &mut db,
+            &mut parser,
+            commands_with_bad_middle_line(),
+            ReplayMode::StopOnError,
+            |_| (),
+        );
+
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].line, 3);
+        assert!(db.get_table("Users").unwrap().get(&3).is_none());
+    }
 }
@@ -0,0 +1,78 @@
+use crate::{
+    commands::command::{AnyCommand, Command, CommandError, CommandResult},
+    database::{Database, key::DatabaseKey},
+};
+
+pub struct RenameCommand<'a, K: DatabaseKey> {
+    pub database: &'a mut Database<K>,
+    pub table_name: String,
+    pub new_table_name: String,
+}
+
+impl<K: DatabaseKey> Command for RenameCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        self.database
+            .rename_table(&self.table_name, &self.new_table_name)?;
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<RenameCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: RenameCommand<'a, K>) -> Self {
+        Self::RenameCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{DatabaseError, table::ColumnType};
+
+    use super::*;
+
+    fn prepare_database() -> Database<i64> {
+        let mut db = Database::new();
+        db.create_table(
+            "Users".to_string(),
+            "UserId".to_string(),
+            vec!["Name".to_string()],
+            vec![ColumnType::STRING],
+        )
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn rename_command_success_test() {
+        let mut db = prepare_database();
+
+        let command = RenameCommand {
+            database: &mut db,
+            table_name: "Users".to_string(),
+            new_table_name: "Accounts".to_string(),
+        };
+
+        let result = command.execute();
+
+        assert!(matches!(result, Ok(CommandResult::Void)));
+        assert_eq!(db.get_table_names(), vec!["Accounts"]);
+    }
+
+    #[test]
+    fn rename_command_not_found_test() {
+        let mut db = prepare_database();
+
+        let command = RenameCommand {
+            database: &mut db,
+            table_name: "Orders".to_string(),
+            new_table_name: "Accounts".to_string(),
+        };
+
+        let result = command.execute();
+
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::DatabaseError(DatabaseError::TableNotFoundError("Orders".to_string()))
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
 use crate::{
     commands::command::{AnyCommand, Command, CommandError, CommandResult},
     database::{
@@ -24,14 +26,114 @@ pub enum AnyWhereFilter {
     NoOp(NoOpWhereFilter),
     And(And),
     Or(Or),
+    Not(Not),
     ValueOperator(ValueOperatorFilter),
     ColumnOperator(ColumnOperatorFilter),
+    KeyIn(KeyInFilter),
 }
 
 impl AnyWhereFilter {
     pub fn to_box(self) -> Box<Self> {
         Box::new(self)
     }
+
+    pub fn eq(column_name: &str, value: Value) -> Self {
+        Self::compare(column_name, Operator::Eq, value)
+    }
+
+    pub fn compare(column_name: &str, op: Operator, value: Value) -> Self {
+        ValueOperatorFilter {
+            column_name: column_name.to_string(),
+            op,
+            value,
+        }
+        .to_enum()
+    }
+
+    pub fn and(self, other: AnyWhereFilter) -> Self {
+        match self {
+            AnyWhereFilter::And(mut and) => {
+                and.filters.push(other.to_box());
+                and.to_enum()
+            }
+            filter => And {
+                filters: vec![filter.to_box(), other.to_box()],
+            }
+            .to_enum(),
+        }
+    }
+
+    pub fn or(self, other: AnyWhereFilter) -> Self {
+        match self {
+            AnyWhereFilter::Or(mut or) => {
+                or.filters.push(other.to_box());
+                or.to_enum()
+            }
+            filter => Or {
+                filters: vec![filter.to_box(), other.to_box()],
+            }
+            .to_enum(),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Not {
+            filter: self.to_box(),
+        }
+        .to_enum()
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            AnyWhereFilter::NoOp(_) => "none".to_string(),
+            AnyWhereFilter::And(and) => Self::describe_group(&and.filters, "AND"),
+            AnyWhereFilter::Or(or) => Self::describe_group(&or.filters, "OR"),
+            AnyWhereFilter::Not(not) => format!("NOT {}", not.filter.describe()),
+            AnyWhereFilter::ValueOperator(filter) => {
+                format!("{} {} {}", filter.column_name, filter.op, filter.value)
+            }
+            AnyWhereFilter::ColumnOperator(filter) => format!(
+                "{} {} column {}",
+                filter.column_name1, filter.op, filter.column_name2
+            ),
+            AnyWhereFilter::KeyIn(filter) => {
+                let keys: Vec<String> = filter.keys.iter().map(|k| k.to_string()).collect();
+                format!("{} IN ({})", filter.key_column, keys.join(", "))
+            }
+        }
+    }
+
+    pub fn rename_column(&mut self, old: &str, new: &str) {
+        let rename = |column: &mut String| {
+            if column == old {
+                *column = new.to_string();
+            }
+        };
+
+        match self {
+            AnyWhereFilter::NoOp(_) => (),
+            AnyWhereFilter::And(And { filters }) | AnyWhereFilter::Or(Or { filters }) => filters
+                .iter_mut()
+                .for_each(|filter| filter.rename_column(old, new)),
+            AnyWhereFilter::Not(not) => not.filter.rename_column(old, new),
+            AnyWhereFilter::ValueOperator(filter) => rename(&mut filter.column_name),
+            AnyWhereFilter::ColumnOperator(filter) => {
+                rename(&mut filter.column_name1);
+                rename(&mut filter.column_name2);
+            }
+            AnyWhereFilter::KeyIn(filter) => rename(&mut filter.key_column),
+        }
+    }
+
+    fn describe_group(filters: &[Box<AnyWhereFilter>], separator: &str) -> String {
+        if let [single] = filters {
+            return single.describe();
+        }
+
+        let parts: Vec<String> = filters.iter().map(|f| f.describe()).collect();
+        format!("({})", parts.join(&format!(" {separator} ")))
+    }
 }
 
 impl WhereFilter for AnyWhereFilter {
@@ -40,12 +142,14 @@ impl WhereFilter for AnyWhereFilter {
             AnyWhereFilter::NoOp(no_op_where_filter) => no_op_where_filter.filter_record(record),
             AnyWhereFilter::And(and) => and.filter_record(record),
             AnyWhereFilter::Or(or) => or.filter_record(record),
+            AnyWhereFilter::Not(not) => not.filter_record(record),
             AnyWhereFilter::ValueOperator(value_operator_filter) => {
                 value_operator_filter.filter_record(record)
             }
             AnyWhereFilter::ColumnOperator(column_operator_filter) => {
                 column_operator_filter.filter_record(record)
             }
+            AnyWhereFilter::KeyIn(key_in_filter) => key_in_filter.filter_record(record),
         }
     }
 
@@ -56,20 +160,29 @@ impl WhereFilter for AnyWhereFilter {
             }
             AnyWhereFilter::And(and) => and.validate_filtering(record),
             AnyWhereFilter::Or(or) => or.validate_filtering(record),
+            AnyWhereFilter::Not(not) => not.validate_filtering(record),
             AnyWhereFilter::ValueOperator(value_operator_filter) => {
                 value_operator_filter.validate_filtering(record)
             }
             AnyWhereFilter::ColumnOperator(column_operator_filter) => {
                 column_operator_filter.validate_filtering(record)
             }
+            AnyWhereFilter::KeyIn(key_in_filter) => key_in_filter.validate_filtering(record),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
 pub struct SelectCommand<'a, K: DatabaseKey> {
     pub table: &'a Table<K>,
     pub selected_columns: Vec<String>,
     pub where_filter: AnyWhereFilter,
+    pub order_by: Vec<(String, SortDirection)>,
 }
 
 impl<K: DatabaseKey> Command for SelectCommand<'_, K> {
@@ -80,6 +193,8 @@ impl<K: DatabaseKey> Command for SelectCommand<'_, K> {
             return Err(err);
         }
 
+        self.validate_order_by()?;
+
         let results = self.select_records();
         let (successes, errors): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
         let errors = errors.into_iter().filter_map(Result::err);
@@ -107,9 +222,76 @@ impl<'a, K: DatabaseKey> SelectCommand<'a, K> {
             table,
             selected_columns,
             where_filter,
+            order_by: Vec::new(),
         }
     }
 
+    #[must_use]
+    pub fn with_order_by(mut self, order_by: Vec<(String, SortDirection)>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    pub fn execute_streaming(
+        self,
+        mut sink: impl FnMut(Vec<Value>) -> Result<(), CommandError>,
+    ) -> Result<(), CommandError> {
+        let where_errors = self.validate_where();
+
+        if let Some(err) = where_errors.into_iter().next() {
+            return Err(err);
+        }
+
+        self.validate_order_by()?;
+
+        for record in self.matching_records() {
+            sink(record.project(&self.selected_columns)?)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_pages(
+        self,
+        page_size: usize,
+    ) -> Result<impl Iterator<Item = Result<Vec<Vec<Value>>, CommandError>> + 'a, CommandError>
+    {
+        if page_size == 0 {
+            return Err(CommandError::InvalidPageSizeError);
+        }
+
+        if let Some(err) = self.validate_where().into_iter().next() {
+            return Err(err);
+        }
+
+        self.validate_order_by()?;
+
+        let columns = self.table.get_columns();
+        if let Some(missing) = self
+            .selected_columns
+            .iter()
+            .find(|column| !columns.contains_key(*column))
+        {
+            return Err(RecordError::InvalidColumnNameError(missing.clone()).into());
+        }
+
+        let mut records = self.matching_records().into_iter();
+        let selected_columns = self.selected_columns;
+
+        Ok(std::iter::from_fn(move || {
+            let page: Result<Vec<Vec<Value>>, CommandError> = records
+                .by_ref()
+                .take(page_size)
+                .map(|record| Ok(record.project(&selected_columns)?))
+                .collect();
+
+            match page {
+                Ok(page) if page.is_empty() => None,
+                page => Some(page),
+            }
+        }))
+    }
+
     fn validate_where(&self) -> Vec<CommandError> {
         let where_errors: Vec<CommandError> = self
             .table
@@ -121,14 +303,74 @@ impl<'a, K: DatabaseKey> SelectCommand<'a, K> {
         where_errors
     }
 
+    fn validate_order_by(&self) -> Result<(), CommandError> {
+        let columns = self.table.get_columns();
+
+        match self
+            .order_by
+            .iter()
+            .find(|(column, _)| !columns.contains_key(column))
+        {
+            Some((column, _)) => Err(RecordError::InvalidColumnNameError(column.clone()).into()),
+            None => Ok(()),
+        }
+    }
+
+    fn compare_records(&self, a: &Record, b: &Record) -> Ordering {
+        for (column, direction) in &self.order_by {
+            let ordering = match (a.get_value(column), b.get_value(column)) {
+                (Ok(a), Ok(b)) => a.total_cmp(b),
+                _ => Ordering::Equal,
+            };
+
+            let ordering = match direction {
+                SortDirection::Asc => ordering,
+                SortDirection::Desc => ordering.reverse(),
+            };
+
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    }
+
+    fn matching_records(&self) -> Vec<&'a Record> {
+        let mut records = self.filtered_records();
+
+        if !self.order_by.is_empty() {
+            records.sort_by(|a, b| self.compare_records(a, b));
+        }
+
+        records
+    }
+
+    fn filtered_records(&self) -> Vec<&'a Record> {
+        match &self.where_filter {
+            AnyWhereFilter::KeyIn(key_in_filter) => {
+                let mut keys: Vec<K> = key_in_filter
+                    .keys
+                    .iter()
+                    .cloned()
+                    .filter_map(K::from_value)
+                    .collect();
+                keys.sort();
+                keys.dedup();
+
+                keys.iter().filter_map(|key| self.table.get(key)).collect()
+            }
+            _ => self
+                .table
+                .filter(|record| self.where_filter.filter_record(record)),
+        }
+    }
+
     fn select_records(&self) -> Vec<Result<Vec<Value>, RecordError>> {
         let results: Vec<Result<Vec<Value>, RecordError>> = self
-            .table
-            .filter(|record| self.where_filter.filter_record(record))
+            .matching_records()
             .into_iter()
-            .map(|record| {
-                record.get_values(&self.selected_columns.iter().map(String::as_str).collect())
-            })
+            .map(|record| record.project(&self.selected_columns))
             .collect();
         results
     }
@@ -207,10 +449,84 @@ impl AnyFilter for Or {
     }
 }
 
+#[derive(Debug)]
+pub struct Not {
+    pub filter: Box<AnyWhereFilter>,
+}
+
+impl WhereFilter for Not {
+    fn filter_record(&self, record: &Record) -> bool {
+        !self.filter.filter_record(record)
+    }
+
+    fn validate_filtering(&self, record: &Record) -> Result<(), CommandError> {
+        self.filter.validate_filtering(record)
+    }
+}
+
+impl AnyFilter for Not {
+    fn to_enum(self) -> AnyWhereFilter {
+        AnyWhereFilter::Not(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+}
+
+impl Operator {
+    pub fn apply(&self, a: &Value, b: &Value) -> bool {
+        match self {
+            Operator::Gt => a.gt(b),
+            Operator::Ge => a.ge(b),
+            Operator::Eq => a.eq(b),
+            Operator::Ne => a.ne(b),
+            Operator::Lt => a.lt(b),
+            Operator::Le => a.le(b),
+        }
+    }
+}
+
+impl FromStr for Operator {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            ">" => Ok(Operator::Gt),
+            ">=" => Ok(Operator::Ge),
+            "=" => Ok(Operator::Eq),
+            "!=" => Ok(Operator::Ne),
+            "<" => Ok(Operator::Lt),
+            "<=" => Ok(Operator::Le),
+            _ => Err(CommandError::UnknownOperatorError(s.to_string())),
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::Eq => "=",
+            Operator::Ne => "!=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
 #[derive(Debug)]
 pub struct ValueOperatorFilter {
     pub column_name: String,
-    pub op: String,
+    pub op: Operator,
     pub value: Value,
 }
 
@@ -220,25 +536,17 @@ impl WhereFilter for ValueOperatorFilter {
             return false;
         };
 
-        if !val.is_the_same_type_as(&self.value) {
-            return false;
+        if val.is_the_same_type_as(&self.value) {
+            return self.op.apply(val, &self.value);
         }
 
-        match self.op.as_str() {
-            ">" => val.gt(&self.value),
-            ">=" => val.ge(&self.value),
-            "=" => val.eq(&self.value),
-            "!=" => val.ne(&self.value),
-            "<" => val.lt(&self.value),
-            "<=" => val.le(&self.value),
-            _ => false,
-        }
+        Value::promote_pair(val, &self.value).is_some_and(|(a, b)| self.op.apply(&a, &b))
     }
 
     fn validate_filtering(&self, record: &Record) -> Result<(), CommandError> {
         let val = record.get_value(&self.column_name)?;
 
-        if !val.is_the_same_type_as(&self.value) {
+        if Value::promote_pair(val, &self.value).is_none() {
             return Err(CommandError::InvalidValueError {
                 column_name: self.column_name.clone(),
                 expected_type: val.type_name(),
@@ -246,10 +554,6 @@ impl WhereFilter for ValueOperatorFilter {
             });
         }
 
-        if !matches!(self.op.as_str(), ">" | ">=" | "=" | "<" | "<=" | "!=") {
-            return Err(CommandError::UnknownOperatorError(self.op.clone()));
-        }
-
         Ok(())
     }
 }
@@ -263,7 +567,7 @@ impl AnyFilter for ValueOperatorFilter {
 #[derive(Debug)]
 pub struct ColumnOperatorFilter {
     pub column_name1: String,
-    pub op: String,
+    pub op: Operator,
     pub column_name2: String,
 }
 
@@ -275,7 +579,7 @@ impl WhereFilter for ColumnOperatorFilter {
 
         let value_filter = ValueOperatorFilter {
             column_name: self.column_name1.clone(),
-            op: self.op.clone(),
+            op: self.op,
             value: value.clone(),
         };
 
@@ -287,7 +591,7 @@ impl WhereFilter for ColumnOperatorFilter {
 
         let value_filter = ValueOperatorFilter {
             column_name: self.column_name1.clone(),
-            op: self.op.clone(),
+            op: self.op,
             value: value.clone(),
         };
 
@@ -303,6 +607,42 @@ impl AnyFilter for ColumnOperatorFilter {
     }
 }
 
+#[derive(Debug)]
+pub struct KeyInFilter {
+    pub key_column: String,
+    pub keys: Vec<Value>,
+}
+
+impl WhereFilter for KeyInFilter {
+    fn filter_record(&self, record: &Record) -> bool {
+        let Ok(val) = record.get_value(&self.key_column) else {
+            return false;
+        };
+
+        self.keys.contains(val)
+    }
+
+    fn validate_filtering(&self, record: &Record) -> Result<(), CommandError> {
+        let val = record.get_value(&self.key_column)?;
+
+        if let Some(key) = self.keys.iter().find(|key| !key.is_the_same_type_as(val)) {
+            return Err(CommandError::InvalidValueError {
+                column_name: self.key_column.clone(),
+                expected_type: val.type_name(),
+                got_type: key.type_name(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl AnyFilter for KeyInFilter {
+    fn to_enum(self) -> AnyWhereFilter {
+        AnyWhereFilter::KeyIn(self)
+    }
+}
+
 impl<'a, K: DatabaseKey> From<SelectCommand<'a, K>> for AnyCommand<'a, K> {
     fn from(value: SelectCommand<'a, K>) -> Self {
         Self::SelectCommand(value)
@@ -454,6 +794,72 @@ mod tests {
         table
     }
 
+    fn filtered_ids(table: &Table<i64>, filter: &AnyWhereFilter) -> Vec<Value> {
+        table
+            .filter(|record| filter.filter_record(record))
+            .into_iter()
+            .map(|record| record.get_value("UserId").unwrap().clone())
+            .collect()
+    }
+
+    #[test]
+    fn filter_combinators_match_manual_tree_test() {
+        let table = setup_test_table();
+
+        let fluent = AnyWhereFilter::eq("Age", Value::INT(16))
+            .and(AnyWhereFilter::eq("Married", Value::BOOL(false)));
+        let manual = And {
+            filters: vec![
+                ValueOperatorFilter {
+                    column_name: "Age".into(),
+                    op: Operator::Eq,
+                    value: Value::INT(16),
+                }
+                .to_enum()
+                .to_box(),
+                ValueOperatorFilter {
+                    column_name: "Married".into(),
+                    op: Operator::Eq,
+                    value: Value::BOOL(false),
+                }
+                .to_enum()
+                .to_box(),
+            ],
+        }
+        .to_enum();
+
+        assert_eq!(filtered_ids(&table, &fluent), vec![Value::INT(1)]);
+        assert_eq!(filtered_ids(&table, &fluent), filtered_ids(&table, &manual));
+        assert_eq!(fluent.describe(), manual.describe());
+    }
+
+    #[test]
+    fn filter_combinators_or_not_test() {
+        let table = setup_test_table();
+
+        let filter = AnyWhereFilter::eq("Firstname", Value::STRING("Jane".into()))
+            .or(AnyWhereFilter::eq("Married", Value::BOOL(true)))
+            .or(AnyWhereFilter::compare("Age", Operator::Lt, Value::INT(18)));
+        assert_eq!(
+            filter.describe(),
+            "(Firstname = STRING Jane OR Married = BOOL true OR Age < INT 18)"
+        );
+        assert_eq!(filtered_ids(&table, &filter).len(), 3);
+
+        let not_married = AnyWhereFilter::eq("Married", Value::BOOL(true)).not();
+        assert_eq!(
+            filtered_ids(&table, &not_married),
+            vec![Value::INT(1), Value::INT(3)]
+        );
+
+        let invalid = AnyWhereFilter::eq("Age", Value::STRING("old".into())).not();
+        assert!(
+            table
+                .iter_records()
+                .all(|record| invalid.validate_filtering(record).is_err())
+        );
+    }
+
     #[test]
     fn select_columns_test() {
         let table = setup_test_table();
@@ -491,13 +897,190 @@ mod tests {
         }
     }
 
+    #[test]
+    fn select_columns_in_requested_order_test() {
+        let table = setup_test_table();
+        let selected_columns = vec!["Married".into(), "UserId".into(), "Lastname".into()];
+        let select_command =
+            SelectCommand::new(&table, selected_columns, NoOpWhereFilter {}.to_enum());
+
+        let result = select_command.execute().unwrap();
+
+        let CommandResult::RecordValueList(column_names, result) = result else {
+            panic!("Expected RecordValueList");
+        };
+
+        assert_eq!(column_names, vec!["Married", "UserId", "Lastname"]);
+        assert_eq!(
+            result[0],
+            vec![
+                Value::BOOL(false),
+                Value::INT(1),
+                Value::STRING("Doe".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn select_streaming_counts_rows_test() {
+        let table = setup_test_table();
+        let filter = ValueOperatorFilter {
+            column_name: "Age".into(),
+            op: Operator::Gt,
+            value: Value::INT(18),
+        };
+        let select_command = SelectCommand::new(&table, vec!["Firstname".into()], filter.to_enum());
+        let mut rows = Vec::new();
+
+        select_command
+            .execute_streaming(|row| {
+                rows.push(row);
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::STRING("Chris".into())],
+                vec![Value::STRING("Jane".into())]
+            ]
+        );
+    }
+
+    #[test]
+    fn select_streaming_stops_on_sink_error_test() {
+        let table = setup_test_table();
+        let select_command =
+            SelectCommand::new(&table, vec!["UserId".into()], NoOpWhereFilter {}.to_enum());
+        let mut rows_seen = 0;
+
+        let result = select_command.execute_streaming(|_row| {
+            rows_seen += 1;
+            Err(CommandError::IoError("sink closed".into()))
+        });
+
+        assert_eq!(result, Err(CommandError::IoError("sink closed".into())));
+        assert_eq!(rows_seen, 1);
+    }
+
+    #[test]
+    fn select_order_by_multiple_columns_test() {
+        let table = setup_test_table();
+        let select_command = SelectCommand::new(
+            &table,
+            vec!["Firstname".into()],
+            NoOpWhereFilter {}.to_enum(),
+        )
+        .with_order_by(vec![
+            ("Married".into(), SortDirection::Desc),
+            ("Age".into(), SortDirection::Asc),
+        ]);
+
+        let CommandResult::RecordValueList(_, rows) = select_command.execute().unwrap() else {
+            panic!("Expected RecordValueList");
+        };
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::STRING("Chris".into())],
+                vec![Value::STRING("John".into())],
+                vec![Value::STRING("Jane".into())],
+            ]
+        );
+    }
+
+    #[test]
+    fn select_order_by_unknown_column_test() {
+        let table = setup_test_table();
+        let select_command =
+            SelectCommand::new(&table, vec!["Age".into()], NoOpWhereFilter {}.to_enum())
+                .with_order_by(vec![("Height".into(), SortDirection::Asc)]);
+
+        assert_eq!(
+            select_command.execute().unwrap_err(),
+            CommandError::RecordError(RecordError::InvalidColumnNameError("Height".into()))
+        );
+    }
+
+    #[test]
+    fn select_into_pages_splits_rows_test() {
+        let mut table: Table<i64> = Table::new_builder("Numbers".to_string(), "Id".to_string())
+            .build()
+            .unwrap();
+        for id in 1..=10 {
+            table
+                .insert(vec!["Id".into()], vec![Value::INT(id)])
+                .unwrap();
+        }
+
+        let pages: Vec<Vec<Vec<Value>>> =
+            SelectCommand::new(&table, vec!["Id".into()], NoOpWhereFilter {}.to_enum())
+                .into_pages(3)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+        assert_eq!(
+            pages.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![3, 3, 3, 1]
+        );
+        assert_eq!(pages[1][0], vec![Value::INT(4)]);
+        assert_eq!(pages[3], vec![vec![Value::INT(10)]]);
+
+        let CommandResult::RecordValueList(_, all_rows) =
+            SelectCommand::new(&table, vec!["Id".into()], NoOpWhereFilter {}.to_enum())
+                .execute()
+                .unwrap()
+        else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(pages.concat(), all_rows);
+    }
+
+    #[test]
+    fn select_into_pages_validates_up_front_test() {
+        let table = setup_test_table();
+
+        let empty = SelectCommand::new(
+            &table,
+            vec!["Age".into()],
+            ValueOperatorFilter {
+                column_name: "Age".into(),
+                op: Operator::Gt,
+                value: Value::INT(100),
+            }
+            .to_enum(),
+        )
+        .into_pages(2)
+        .unwrap();
+        assert_eq!(empty.count(), 0);
+
+        let missing_column =
+            SelectCommand::new(&table, vec!["Surname".into()], NoOpWhereFilter {}.to_enum())
+                .into_pages(2);
+        assert!(matches!(
+            missing_column,
+            Err(CommandError::RecordError(RecordError::InvalidColumnNameError(name))) if name == "Surname"
+        ));
+
+        let zero_page_size =
+            SelectCommand::new(&table, vec!["Age".into()], NoOpWhereFilter {}.to_enum())
+                .into_pages(0);
+        assert!(matches!(
+            zero_page_size,
+            Err(CommandError::InvalidPageSizeError)
+        ));
+    }
+
     #[test]
     fn select_with_simple_filter_test_string() {
         let table = setup_test_table_string();
 
         let filter = ValueOperatorFilter {
             column_name: "Age".into(),
-            op: ">".into(),
+            op: Operator::Gt,
             value: Value::INT(18),
         }
         .to_enum();
@@ -533,7 +1116,7 @@ mod tests {
 
         let filter = ValueOperatorFilter {
             column_name: "Age".into(),
-            op: ">".into(),
+            op: Operator::Gt,
             value: Value::INT(18),
         }
         .to_enum();
@@ -569,13 +1152,13 @@ mod tests {
 
         let age_filter = AnyWhereFilter::ValueOperator(ValueOperatorFilter {
             column_name: "Age".into(),
-            op: ">".into(),
+            op: Operator::Gt,
             value: Value::INT(18),
         });
 
         let married_filter = AnyWhereFilter::ValueOperator(ValueOperatorFilter {
             column_name: "Married".into(),
-            op: "=".into(),
+            op: Operator::Eq,
             value: Value::BOOL(false),
         });
 
@@ -603,7 +1186,7 @@ mod tests {
 
         let age_filter = ValueOperatorFilter {
             column_name: "Age".into(),
-            op: "<".into(),
+            op: Operator::Lt,
             value: Value::INT(18),
         }
         .to_enum()
@@ -611,7 +1194,7 @@ mod tests {
 
         let married_filter = ValueOperatorFilter {
             column_name: "Married".into(),
-            op: "=".into(),
+            op: Operator::Eq,
             value: Value::BOOL(true),
         }
         .to_enum()
@@ -652,7 +1235,7 @@ mod tests {
 
         let filter = ColumnOperatorFilter {
             column_name1: "Age".into(),
-            op: "=".into(),
+            op: Operator::Eq,
             column_name2: "Age".into(),
         }
         .to_enum();
@@ -697,7 +1280,7 @@ mod tests {
 
         let filter = ValueOperatorFilter {
             column_name: "Age".into(),
-            op: ">".into(),
+            op: Operator::Gt,
             value: Value::INT(100),
         }
         .to_enum();
@@ -721,7 +1304,7 @@ mod tests {
 
         let filter = ValueOperatorFilter {
             column_name: "GhostColumn".into(),
-            op: "=".into(),
+            op: Operator::Eq,
             value: Value::INT(1),
         }
         .to_enum();
@@ -739,13 +1322,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn select_filter_promotes_int_column_to_float_test() {
+        let table = setup_test_table();
+
+        let filter = ValueOperatorFilter {
+            column_name: "Age".into(),
+            op: Operator::Gt,
+            value: Value::FLOAT(19.5),
+        }
+        .to_enum();
+
+        let select_command = SelectCommand::new(&table, vec!["Age".into()], filter);
+
+        match select_command.execute() {
+            Ok(CommandResult::RecordValueList(_, rows)) => {
+                assert_eq!(rows, vec![vec![Value::INT(24)], vec![Value::INT(20)]]);
+            }
+            other => panic!("Expected RecordValueList, received: {:?}", other),
+        }
+    }
+
     #[test]
     fn select_filter_validation_type_mismatch_test() {
         let table = setup_test_table();
 
         let filter = ValueOperatorFilter {
             column_name: "Age".into(),
-            op: ">".into(),
+            op: Operator::Gt,
             value: Value::STRING("Eighteen".into()),
         }
         .to_enum();
@@ -804,4 +1408,120 @@ mod tests {
             assert_eq!(record[1], Value::STRING(name.into()));
         }
     }
+
+    #[test]
+    fn select_with_key_in_filter_test() {
+        let table = setup_test_table();
+        let selected_columns = vec!["UserId".into(), "Firstname".into()];
+
+        let filter = KeyInFilter {
+            key_column: "UserId".into(),
+            keys: vec![Value::INT(2), Value::INT(1), Value::INT(99), Value::INT(2)],
+        };
+
+        let command = SelectCommand::new(&table, selected_columns, filter.to_enum());
+
+        match command.execute().unwrap() {
+            CommandResult::RecordValueList(_, rows) => {
+                assert_eq!(rows.len(), 2);
+                assert_eq!(rows[0][0], Value::INT(1));
+                assert_eq!(rows[1][0], Value::INT(2));
+            }
+            _ => panic!("Expected RecordValueList"),
+        }
+    }
+
+    #[test]
+    fn select_with_key_in_filter_type_mismatch_test() {
+        let table = setup_test_table();
+
+        let filter = KeyInFilter {
+            key_column: "UserId".into(),
+            keys: vec![Value::STRING("1".into())],
+        };
+
+        let command = SelectCommand::new(&table, vec!["Firstname".into()], filter.to_enum());
+
+        assert!(matches!(
+            command.execute(),
+            Err(CommandError::InvalidValueError { .. })
+        ));
+    }
+
+    #[test]
+    fn operator_parse_and_apply_test() {
+        let cases = [
+            (">", Operator::Gt, [false, false, true]),
+            (">=", Operator::Ge, [false, true, true]),
+            ("=", Operator::Eq, [false, true, false]),
+            ("!=", Operator::Ne, [true, false, true]),
+            ("<", Operator::Lt, [true, false, false]),
+            ("<=", Operator::Le, [true, true, false]),
+        ];
+
+        for (symbol, expected, results) in cases {
+            let op: Operator = symbol.parse().unwrap();
+
+            assert_eq!(op, expected);
+            assert_eq!(op.to_string(), symbol);
+
+            for (b, result) in [3, 2, 1].into_iter().zip(results) {
+                assert_eq!(op.apply(&Value::INT(2), &Value::INT(b)), result);
+            }
+        }
+    }
+
+    #[test]
+    fn operator_invalid_string_test() {
+        for symbol in ["==", "<>", "LIKE", ""] {
+            assert_eq!(
+                symbol.parse::<Operator>(),
+                Err(CommandError::UnknownOperatorError(symbol.to_string()))
+            );
+        }
+    }
+
+    fn select_scores(table: &Table<i64>, op: Operator, value: Value) -> Vec<Vec<Value>> {
+        let filter = ValueOperatorFilter {
+            column_name: "Score".into(),
+            op,
+            value,
+        };
+        let command = SelectCommand::new(table, vec!["Id".into()], filter.to_enum());
+
+        match command.execute().unwrap() {
+            CommandResult::RecordValueList(_, rows) => rows,
+            _ => panic!("Expected RecordValueList"),
+        }
+    }
+
+    #[test]
+    fn select_where_compares_mixed_numbers_and_nan_test() {
+        let mut table = Table::new_builder("Scores".into(), "Id".into())
+            .with_column("Score".into(), ColumnType::FLOAT)
+            .build()
+            .unwrap();
+        for (id, score) in [(1, 2.0), (2, f64::NAN), (3, 7.5)] {
+            table
+                .insert(
+                    vec!["Id".into(), "Score".into()],
+                    vec![Value::INT(id), Value::FLOAT(score)],
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            select_scores(&table, Operator::Eq, Value::INT(2)),
+            vec![vec![Value::INT(1)]]
+        );
+        assert_eq!(
+            select_scores(&table, Operator::Gt, Value::INT(5)),
+            vec![vec![Value::INT(3)]]
+        );
+        assert_eq!(
+            select_scores(&table, Operator::Le, Value::FLOAT(7.5)),
+            vec![vec![Value::INT(1)], vec![Value::INT(3)]]
+        );
+        assert!(select_scores(&table, Operator::Eq, Value::FLOAT(f64::NAN)).is_empty());
+    }
 }
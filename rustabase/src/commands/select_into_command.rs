@@ -0,0 +1,173 @@
+use crate::{
+    commands::{
+        command::{AnyCommand, Command, CommandError, CommandResult},
+        select_command::{AnyWhereFilter, SelectCommand},
+    },
+    database::{Database, key::DatabaseKey, table::ColumnType},
+};
+
+pub struct SelectIntoCommand<'a, K: DatabaseKey> {
+    pub database: &'a mut Database<K>,
+    pub table_name: String,
+    pub new_table_name: String,
+    pub selected_columns: Vec<String>,
+    pub where_filter: AnyWhereFilter,
+}
+
+impl<K: DatabaseKey> Command for SelectIntoCommand<'_, K> {
+    fn execute(self) -> Result<CommandResult, CommandError> {
+        let table = self.database.get_table(&self.table_name)?;
+        let key_name = table.get_key_name().to_string();
+
+        if !self.selected_columns.contains(&key_name) {
+            return Err(CommandError::MissingKeyColumnError(key_name));
+        }
+
+        let schema = table.get_columns().clone();
+
+        let result = SelectCommand::new(table, self.selected_columns.clone(), self.where_filter)
+            .execute()?;
+
+        let CommandResult::RecordValueList(columns, rows) = result else {
+            return Ok(CommandResult::Void);
+        };
+
+        let mut fields = Vec::new();
+        let mut types = Vec::new();
+
+        for (idx, column) in columns.iter().enumerate() {
+            if *column == key_name {
+                continue;
+            }
+
+            let column_type = match rows.first() {
+                Some(row) => ColumnType::from_value(&row[idx]),
+                None => schema[column],
+            };
+
+            fields.push(column.clone());
+            types.push(column_type);
+        }
+
+        self.database
+            .create_table(self.new_table_name.clone(), key_name, fields, types)?;
+
+        let new_table = self.database.get_table(&self.new_table_name)?;
+
+        for row in rows {
+            new_table.insert(columns.clone(), row)?;
+        }
+
+        Ok(CommandResult::Void)
+    }
+}
+
+impl<'a, K: DatabaseKey> From<SelectIntoCommand<'a, K>> for AnyCommand<'a, K> {
+    fn from(value: SelectIntoCommand<'a, K>) -> Self {
+        Self::SelectIntoCommand(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::select_command::{AnyFilter, NoOpWhereFilter, Operator, ValueOperatorFilter},
+        database::table::record::Value,
+    };
+
+    use super::*;
+
+    fn prepare_database() -> Database<i64> {
+        let mut db = Database::new();
+        db.create_table(
+            "Users".to_string(),
+            "UserId".to_string(),
+            vec!["Name".to_string(), "Age".to_string()],
+            vec![ColumnType::STRING, ColumnType::INT],
+        )
+        .unwrap();
+
+        let table = db.get_table("Users").unwrap();
+        for (id, name, age) in [(1, "Alice", 30), (2, "Bob", 15), (3, "Carol", 22)] {
+            table
+                .insert(
+                    vec!["UserId".into(), "Name".into(), "Age".into()],
+                    vec![Value::INT(id), Value::STRING(name.into()), Value::INT(age)],
+                )
+                .unwrap();
+        }
+
+        db
+    }
+
+    #[test]
+    fn select_into_creates_filtered_table() {
+        let mut db = prepare_database();
+
+        let command = SelectIntoCommand {
+            database: &mut db,
+            table_name: "Users".into(),
+            new_table_name: "Adults".into(),
+            selected_columns: vec!["UserId".into(), "Name".into()],
+            where_filter: ValueOperatorFilter {
+                column_name: "Age".into(),
+                op: Operator::Gt,
+                value: Value::INT(18),
+            }
+            .to_enum(),
+        };
+
+        assert!(matches!(command.execute(), Ok(CommandResult::Void)));
+
+        let adults = db.get_table("Adults").unwrap();
+        assert_eq!(adults.get_key_name(), "UserId");
+        assert_eq!(adults.get_columns().len(), 2);
+        assert_eq!(adults.get_columns()["Name"], ColumnType::STRING);
+        assert!(adults.get(&1).is_some());
+        assert!(adults.get(&2).is_none());
+        assert!(adults.get(&3).is_some());
+    }
+
+    #[test]
+    fn select_into_with_empty_result_uses_source_schema() {
+        let mut db = prepare_database();
+
+        let command = SelectIntoCommand {
+            database: &mut db,
+            table_name: "Users".into(),
+            new_table_name: "Nobody".into(),
+            selected_columns: vec!["UserId".into(), "Age".into()],
+            where_filter: ValueOperatorFilter {
+                column_name: "Age".into(),
+                op: Operator::Gt,
+                value: Value::INT(100),
+            }
+            .to_enum(),
+        };
+
+        command.execute().unwrap();
+
+        let nobody = db.get_table("Nobody").unwrap();
+        assert_eq!(nobody.get_columns()["Age"], ColumnType::INT);
+        assert_eq!(nobody.iter_records().count(), 0);
+    }
+
+    #[test]
+    fn select_into_requires_key_column() {
+        let mut db = prepare_database();
+
+        let command = SelectIntoCommand {
+            database: &mut db,
+            table_name: "Users".into(),
+            new_table_name: "Names".into(),
+            selected_columns: vec!["Name".into()],
+            where_filter: NoOpWhereFilter {}.to_enum(),
+        };
+
+        assert_eq!(
+            command.execute().unwrap_err(),
+            CommandError::MissingKeyColumnError("UserId".into())
+        );
+        assert!(db.get_table("Names").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use crate::commands::command::{Command, CommandError, CommandResult};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandMetrics {
+    pub elapsed: Duration,
+    pub records: usize,
+}
+
+pub struct TimedCommand<C: Command> {
+    pub command: C,
+}
+
+impl<C: Command> TimedCommand<C> {
+    pub fn new(command: C) -> Self {
+        Self { command }
+    }
+
+    // `records` counts the rows (or replayed commands) in the result.
+    pub fn execute_timed(self) -> (Result<CommandResult, CommandError>, CommandMetrics) {
+        let start = Instant::now();
+        let result = self.command.execute();
+        let elapsed = start.elapsed();
+
+        let records = match &result {
+            Ok(CommandResult::RecordValueList(_, records)) => records.len(),
+            Ok(CommandResult::CommandList(items)) => items.len(),
+            Ok(CommandResult::Void) | Err(_) => 0,
+        };
+
+        (result, CommandMetrics { elapsed, records })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::database::table::record::Value;
+
+    use super::*;
+
+    struct SlowCommand {
+        result: Result<CommandResult, CommandError>,
+    }
+
+    impl Command for SlowCommand {
+        fn execute(self) -> Result<CommandResult, CommandError> {
+            thread::sleep(Duration::from_millis(2));
+            self.result
+        }
+    }
+
+    #[test]
+    fn timed_command_forwards_result_test() {
+        let command = SlowCommand {
+            result: Ok(CommandResult::RecordValueList(
+                vec!["Name".to_string()],
+                vec![
+                    vec![Value::STRING("Alice".to_string())],
+                    vec![Value::STRING("Bob".to_string())],
+                ],
+            )),
+        };
+
+        let (result, metrics) = TimedCommand::new(command).execute_timed();
+
+        assert!(metrics.elapsed >= Duration::from_millis(2));
+        assert_eq!(metrics.records, 2);
+        let Ok(CommandResult::RecordValueList(columns, records)) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(columns, vec!["Name".to_string()]);
+        assert_eq!(
+            records,
+            vec![
+                vec![Value::STRING("Alice".to_string())],
+                vec![Value::STRING("Bob".to_string())],
+            ]
+        );
+    }
+
+    #[test]
+    fn timed_command_forwards_error_test() {
+        let command = SlowCommand {
+            result: Err(CommandError::DryRunUnsupportedError),
+        };
+
+        let (result, metrics) = TimedCommand::new(command).execute_timed();
+
+        assert!(metrics.elapsed > Duration::ZERO);
+        assert_eq!(metrics.records, 0);
+        assert_eq!(result.unwrap_err(), CommandError::DryRunUnsupportedError);
+    }
+}
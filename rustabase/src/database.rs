@@ -1,8 +1,11 @@
 use thiserror::Error;
 
-use crate::database::{
-    key::DatabaseKey,
-    table::{ColumnType, Table, TableError},
+use crate::{
+    commands::select_command::AnyWhereFilter,
+    database::{
+        key::DatabaseKey,
+        table::{ColumnType, Table, TableError},
+    },
 };
 
 pub mod key;
@@ -11,6 +14,11 @@ pub mod table;
 pub enum AnyDatabase {
     StringDatabase(Database<String>),
     I64Database(Database<i64>),
+    U64Database(Database<u64>),
+}
+
+pub trait DatabaseHandler {
+    fn handle<K: DatabaseKey>(&mut self, db: &mut Database<K>) -> bool;
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -29,6 +37,20 @@ pub struct Database<K: DatabaseKey> {
     tables: Vec<Table<K>>,
 }
 
+impl AnyDatabase {
+    pub fn dispatch<H: DatabaseHandler>(&mut self, handler: &mut H) -> bool {
+        match self {
+            AnyDatabase::StringDatabase(database) => handler.handle(database),
+            AnyDatabase::I64Database(database) => handler.handle(database),
+            AnyDatabase::U64Database(database) => handler.handle(database),
+        }
+    }
+
+    pub fn run_repl<H: DatabaseHandler>(mut self, handler: &mut H) {
+        while self.dispatch(handler) {}
+    }
+}
+
 impl<K: DatabaseKey> Default for Database<K> {
     fn default() -> Self {
         Self::new()
@@ -45,6 +67,17 @@ impl<K: DatabaseKey> Database<K> {
         key_name: String,
         fields: Vec<String>,
         types: Vec<ColumnType>,
+    ) -> Result<(), DatabaseError> {
+        self.create_table_with_checks(table_name, key_name, fields, types, Vec::new())
+    }
+
+    pub fn create_table_with_checks(
+        &mut self,
+        table_name: String,
+        key_name: String,
+        fields: Vec<String>,
+        types: Vec<ColumnType>,
+        checks: Vec<(String, AnyWhereFilter)>,
     ) -> Result<(), DatabaseError> {
         if self
             .tables
@@ -62,6 +95,10 @@ impl<K: DatabaseKey> Database<K> {
             new_table = new_table.with_column(field, t);
         }
 
+        for (description, check) in checks {
+            new_table = new_table.with_check(description, check);
+        }
+
         let new_table = new_table.build()?;
 
         self.tables.push(new_table);
@@ -79,13 +116,32 @@ impl<K: DatabaseKey> Database<K> {
         }
     }
 
+    pub fn iter_tables(&self) -> impl Iterator<Item = &Table<K>> {
+        self.tables.iter()
+    }
+
     pub fn get_table_names(&self) -> Vec<&str> {
         self.tables.iter().map(Table::get_name).collect()
     }
+
+    pub fn rename_table(&mut self, old: &str, new: &str) -> Result<(), DatabaseError> {
+        if self.tables.iter().any(|t| t.get_name() == new) {
+            return Err(DatabaseError::TableAlreadyExistsError(new.into()));
+        }
+
+        let table = self.get_table(old)?;
+        table.rename(new.into());
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::BufRead;
+
+    use crate::{commands::command::Command, parser::CommandParser};
+
     use super::*;
 
     fn prepare_database() -> Database<i64> {
@@ -104,6 +160,69 @@ mod tests {
         db
     }
 
+    struct ScriptedHandler<R: BufRead> {
+        parser: CommandParser,
+        input: R,
+        handled_key_types: Vec<ColumnType>,
+        table_names: Vec<String>,
+    }
+
+    impl<R: BufRead> DatabaseHandler for ScriptedHandler<R> {
+        fn handle<K: DatabaseKey>(&mut self, db: &mut Database<K>) -> bool {
+            let mut line = String::new();
+            if self.input.read_line(&mut line).unwrap() == 0 {
+                self.table_names = db.get_table_names().into_iter().map(String::from).collect();
+                return false;
+            }
+
+            self.handled_key_types.push(K::to_column_type());
+            self.parser
+                .parse_command(db, &line)
+                .unwrap()
+                .execute()
+                .unwrap();
+            true
+        }
+    }
+
+    fn prepare_scripted_handler(script: &str) -> ScriptedHandler<&[u8]> {
+        ScriptedHandler {
+            parser: CommandParser::new(),
+            input: script.as_bytes(),
+            handled_key_types: Vec::new(),
+            table_names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn run_repl_string_database_test() {
+        let db = AnyDatabase::StringDatabase(Database::new());
+        let mut handler = prepare_scripted_handler(
+            "CREATE Users KEY UserId FIELDS Name:STRING\nINSERT UserId=\"u1\", Name=\"Bob\" INTO Users\n",
+        );
+
+        db.run_repl(&mut handler);
+
+        assert_eq!(
+            handler.handled_key_types,
+            vec![ColumnType::STRING, ColumnType::STRING]
+        );
+        assert_eq!(handler.table_names, vec!["Users"]);
+    }
+
+    #[test]
+    fn run_repl_i64_database_test() {
+        let db = AnyDatabase::I64Database(Database::new());
+        let mut handler = prepare_scripted_handler(
+            "CREATE Users KEY UserId\nCREATE Orders KEY OrderId\nINSERT UserId=1 INTO Users\n",
+        );
+
+        db.run_repl(&mut handler);
+
+        assert_eq!(handler.handled_key_types, vec![ColumnType::INT; 3]);
+        assert_eq!(handler.table_names, vec!["Users", "Orders"]);
+    }
+
     #[test]
     fn database_creation_test() {
         let db = Database::<i64>::new();
@@ -165,4 +284,52 @@ mod tests {
             DatabaseError::TableNotFoundError(missing_name)
         );
     }
+
+    #[test]
+    fn rename_table_success_test() {
+        let mut db = prepare_populated_database();
+
+        let result = db.rename_table("Users", "Accounts");
+
+        assert!(result.is_ok());
+        assert_eq!(db.get_table_names(), vec!["Accounts"]);
+        assert!(db.get_table("Accounts").is_ok());
+        assert_eq!(
+            db.get_table("Users").unwrap_err(),
+            DatabaseError::TableNotFoundError("Users".to_string())
+        );
+    }
+
+    #[test]
+    fn rename_table_not_found_test() {
+        let mut db = prepare_populated_database();
+
+        let result = db.rename_table("Orders", "Accounts");
+
+        assert_eq!(
+            result.unwrap_err(),
+            DatabaseError::TableNotFoundError("Orders".to_string())
+        );
+        assert_eq!(db.get_table_names(), vec!["Users"]);
+    }
+
+    #[test]
+    fn rename_table_already_exists_test() {
+        let mut db = prepare_populated_database();
+        db.create_table(
+            "Accounts".to_string(),
+            "AccountId".to_string(),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+
+        let result = db.rename_table("Users", "Accounts");
+
+        assert_eq!(
+            result.unwrap_err(),
+            DatabaseError::TableAlreadyExistsError("Accounts".to_string())
+        );
+        assert_eq!(db.get_table_names(), vec!["Users", "Accounts"]);
+    }
 }
@@ -3,11 +3,13 @@ use crate::database::table::{ColumnType, record::Value};
 pub trait DatabaseKey: Ord + Clone {
     fn is_equal_to(&self, other: &Self) -> bool;
 
-    fn to_value(self) -> Value;
+    fn to_value(self) -> Option<Value>;
 
     fn to_column_type() -> ColumnType;
 
     fn from_value(value: Value) -> Option<Self>;
+
+    fn parse_key(s: &str) -> Option<Self>;
 }
 
 impl DatabaseKey for i64 {
@@ -15,8 +17,8 @@ impl DatabaseKey for i64 {
         self.eq(other)
     }
 
-    fn to_value(self) -> Value {
-        Value::INT(self)
+    fn to_value(self) -> Option<Value> {
+        Some(Value::INT(self))
     }
 
     fn from_value(value: Value) -> Option<Self> {
@@ -26,6 +28,36 @@ impl DatabaseKey for i64 {
         }
     }
 
+    fn parse_key(s: &str) -> Option<Self> {
+        s.trim().parse().ok()
+    }
+
+    fn to_column_type() -> ColumnType {
+        ColumnType::INT
+    }
+}
+
+// u64 keys are stored as INT values, so only 0..=i64::MAX is accepted.
+impl DatabaseKey for u64 {
+    fn is_equal_to(&self, other: &Self) -> bool {
+        self.eq(other)
+    }
+
+    fn to_value(self) -> Option<Value> {
+        i64::try_from(self).ok().map(Value::INT)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::INT(i) => u64::try_from(i).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_key(s: &str) -> Option<Self> {
+        i64::parse_key(s).and_then(|i| u64::try_from(i).ok())
+    }
+
     fn to_column_type() -> ColumnType {
         ColumnType::INT
     }
@@ -36,8 +68,8 @@ impl DatabaseKey for String {
         self.eq(other)
     }
 
-    fn to_value(self) -> Value {
-        Value::STRING(self)
+    fn to_value(self) -> Option<Value> {
+        Some(Value::STRING(self))
     }
 
     fn from_value(value: Value) -> Option<Self> {
@@ -47,7 +79,47 @@ impl DatabaseKey for String {
         }
     }
 
+    fn parse_key(s: &str) -> Option<Self> {
+        Some(s.to_string())
+    }
+
     fn to_column_type() -> ColumnType {
         ColumnType::STRING
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_i64_key_test() {
+        assert_eq!(i64::parse_key("42"), Some(42));
+        assert_eq!(i64::parse_key("-7"), Some(-7));
+        assert_eq!(i64::parse_key(" 13 "), Some(13));
+        assert_eq!(i64::parse_key("4.2"), None);
+        assert_eq!(i64::parse_key("abc"), None);
+        assert_eq!(i64::parse_key(""), None);
+        assert_eq!(i64::parse_key("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn parse_u64_key_test() {
+        assert_eq!(u64::parse_key("42"), Some(42));
+        assert_eq!(u64::parse_key(" 0 "), Some(0));
+        assert_eq!(u64::parse_key("-7"), None);
+        assert_eq!(u64::parse_key("18446744073709551615"), None);
+        assert_eq!(u64::from_value(Value::INT(-1)), None);
+        assert_eq!(u64::from_value(Value::INT(i64::MAX)), Some(i64::MAX as u64));
+        assert_eq!(u64::from_value(Value::STRING("1".into())), None);
+        assert_eq!(7u64.to_value(), Some(Value::INT(7)));
+        assert_eq!((i64::MAX as u64 + 1).to_value(), None);
+    }
+
+    #[test]
+    fn parse_string_key_test() {
+        assert_eq!(String::parse_key("user-1"), Some("user-1".to_string()));
+        assert_eq!(String::parse_key(" spaced "), Some(" spaced ".to_string()));
+        assert_eq!(String::parse_key(""), Some(String::new()));
+    }
+}
@@ -1,15 +1,23 @@
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+};
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::database::{
-    key::DatabaseKey,
-    table::record::{Record, RecordBuilder, RecordError, Value},
+use crate::{
+    commands::select_command::{AnyWhereFilter, WhereFilter},
+    database::{
+        key::DatabaseKey,
+        table::record::{Record, RecordBuilder, RecordError, Value},
+    },
 };
 
 pub mod record;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ColumnType {
     BOOL,
     STRING,
@@ -26,12 +34,24 @@ pub enum TableError {
         second_type: ColumnType,
     },
 
+    #[error("Column: {0} has the same name as the table key")]
+    ColumnCollidesWithKeyError(String),
+
     #[error("Table does not contain column: {0}")]
     InvalidColumnNameError(String),
 
+    #[error("Table does not contain column: {column_name}, did you mean {suggestion}?")]
+    UnknownColumnWithSuggestionError {
+        column_name: String,
+        suggestion: String,
+    },
+
     #[error("Record by key: {0} not found")]
     KeyNotFoundError(Value),
 
+    #[error("Key is out of range for a {0:?} key column")]
+    KeyOutOfRangeError(ColumnType),
+
     #[error("Record with key {0} already in the database")]
     PrimaryKeyConstraintViolation(Value),
 
@@ -44,12 +64,34 @@ pub enum TableError {
         got_type: ColumnType,
     },
 
+    #[error("Column: {0} already exists")]
+    ColumnAlreadyExistsError(String),
+
+    #[error("Column: {column_name} of type {got_type:?} cannot be used as a {expected_type:?} key")]
+    KeyTypeMismatchError {
+        column_name: String,
+        expected_type: ColumnType,
+        got_type: ColumnType,
+    },
+
+    #[error("Value {0} cannot be used as a key")]
+    InvalidKeyValueError(Value),
+
+    #[error("Key column: {0} cannot be updated")]
+    KeyColumnUpdateError(String),
+
     #[error("Missing columns on insert: {0:?}")]
     InsertMissingColumnsError(Vec<String>),
 
     #[error("The length of column_names and column_values does not match")]
     InsertNotMatchingArgsLengthError,
 
+    #[error("Check constraint violated: {0}")]
+    CheckConstraintViolation(String),
+
+    #[error("Invalid check constraint {check}: {reason}")]
+    InvalidCheckError { check: String, reason: String },
+
     #[error("Record error occured: {0}")]
     RecordError(#[from] RecordError),
 }
@@ -60,6 +102,16 @@ pub struct Table<K: DatabaseKey> {
     records: BTreeMap<K, Record>,
     columns: HashMap<String, ColumnType>,
     key_name: String,
+    checks: Vec<(String, AnyWhereFilter)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableSchema {
+    pub name: String,
+    pub key_name: String,
+    pub columns: HashMap<String, ColumnType>,
+    #[serde(default)]
+    pub checks: Vec<String>,
 }
 
 pub struct TableBuilder<K: DatabaseKey> {
@@ -78,6 +130,16 @@ impl ColumnType {
         )
     }
 
+    // stand-in value used to type-check CHECK predicates before any record exists
+    fn sample_value(&self) -> Value {
+        match self {
+            ColumnType::BOOL => Value::BOOL(false),
+            ColumnType::STRING => Value::STRING(String::new()),
+            ColumnType::INT => Value::INT(0),
+            ColumnType::FLOAT => Value::FLOAT(0.0),
+        }
+    }
+
     pub fn from_value(value: &Value) -> Self {
         match value {
             Value::BOOL(_) => ColumnType::BOOL,
@@ -97,10 +159,18 @@ impl<K: DatabaseKey> Table<K> {
         &self.columns
     }
 
+    pub fn get_checks(&self) -> &[(String, AnyWhereFilter)] {
+        &self.checks
+    }
+
     pub fn get_key_name(&self) -> &str {
         &self.key_name
     }
 
+    pub fn rename(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
     pub fn new_builder(name: String, key_name: String) -> TableBuilder<K> {
         TableBuilder {
             table: Self {
@@ -108,26 +178,107 @@ impl<K: DatabaseKey> Table<K> {
                 records: BTreeMap::new(),
                 columns: HashMap::new(),
                 key_name,
+                checks: Vec::new(),
             },
             errors: Vec::new(),
         }
     }
 
+    pub fn quick(
+        name: &str,
+        key_name: &str,
+        columns: &[(&str, ColumnType)],
+    ) -> Result<Table<K>, TableError> {
+        columns
+            .iter()
+            .fold(
+                Self::new_builder(name.to_string(), key_name.to_string()),
+                |builder, (column_name, column_type)| {
+                    builder.with_column(column_name.to_string(), *column_type)
+                },
+            )
+            .build()
+    }
+
     pub fn insert(
         &mut self,
         column_names: Vec<String>,
         column_values: Vec<Value>,
     ) -> Result<(), TableError> {
+        let (key, new_record) = self.prepare_insert(column_names, column_values)?;
+        self.records.insert(key, new_record);
+
+        Ok(())
+    }
+
+    pub fn validate_insert(
+        &self,
+        column_names: Vec<String>,
+        column_values: Vec<Value>,
+    ) -> Result<(), TableError> {
+        self.prepare_insert(column_names, column_values)?;
+
+        Ok(())
+    }
+
+    pub fn insert_record(&mut self, record: Record) -> Result<(), TableError> {
+        let (column_names, column_values) = Self::split_record(&record);
+        self.insert(column_names, column_values)
+    }
+
+    pub fn validate_insert_record(&self, record: &Record) -> Result<(), TableError> {
+        let (column_names, column_values) = Self::split_record(record);
+        self.validate_insert(column_names, column_values)
+    }
+
+    fn split_record(record: &Record) -> (Vec<String>, Vec<Value>) {
+        record
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .unzip()
+    }
+
+    fn prepare_insert(
+        &self,
+        column_names: Vec<String>,
+        column_values: Vec<Value>,
+    ) -> Result<(K, Record), TableError> {
         if column_names.len() != column_values.len() {
             return Err(TableError::InsertNotMatchingArgsLengthError);
         }
 
+        self.validate_known_columns(&column_names)?;
         self.validate_columns(&column_names)?;
         let key_value = self.extract_key_value(&column_names, &column_values)?;
         let new_record = self.build_record(column_names, column_values)?;
-        self.insert_with_key(new_record, &key_value)?;
 
-        Ok(())
+        self.keyed_record(new_record, &key_value)
+    }
+
+    fn validate_known_columns(&self, column_names: &[String]) -> Result<(), TableError> {
+        let Some(unknown) = column_names
+            .iter()
+            .find(|name| !self.columns.contains_key(*name))
+        else {
+            return Ok(());
+        };
+
+        match self.suggest_column(unknown) {
+            Some(suggestion) => Err(TableError::UnknownColumnWithSuggestionError {
+                column_name: unknown.clone(),
+                suggestion,
+            }),
+            None => Err(TableError::InvalidColumnNameError(unknown.clone())),
+        }
+    }
+
+    fn suggest_column(&self, column_name: &str) -> Option<String> {
+        self.columns
+            .keys()
+            .map(|candidate| (edit_distance(column_name, candidate), candidate))
+            .filter(|(distance, candidate)| *distance <= 2 && *distance < candidate.len())
+            .min()
+            .map(|(_, candidate)| candidate.clone())
     }
 
     fn validate_columns(&self, column_names: &[String]) -> Result<(), TableError> {
@@ -173,13 +324,13 @@ impl<K: DatabaseKey> Table<K> {
                 return Err(TableError::InvalidColumnNameError(name));
             };
 
-            if !t.is_type_of(&value) {
+            let Some(value) = value.coerce_to(t) else {
                 return Err(TableError::InsertInvalidColumnTypeError {
                     column_name: name,
                     expected_type: *t,
                     got_type: ColumnType::from_value(&value),
                 });
-            }
+            };
 
             new_record = new_record.with_column(name, value);
         }
@@ -187,11 +338,11 @@ impl<K: DatabaseKey> Table<K> {
         Ok(new_record)
     }
 
-    fn insert_with_key(
-        &mut self,
+    fn keyed_record(
+        &self,
         new_record: RecordBuilder,
         key_value: &Value,
-    ) -> Result<(), TableError> {
+    ) -> Result<(K, Record), TableError> {
         let Some(key) = K::from_value(key_value.clone()) else {
             return Err(TableError::InsertInvalidColumnTypeError {
                 column_name: self.key_name.clone(),
@@ -202,20 +353,296 @@ impl<K: DatabaseKey> Table<K> {
 
         let new_record = new_record.build()?;
 
+        Self::validate_checks(&self.checks, &new_record)?;
+
         if self.records.contains_key(&key) {
-            return Err(TableError::PrimaryKeyConstraintViolation(key.to_value()));
+            return Err(TableError::PrimaryKeyConstraintViolation(key_value.clone()));
         }
 
-        self.records.insert(key, new_record);
-
-        Ok(())
+        Ok((key, new_record))
     }
 
     pub fn delete(&mut self, key: K) -> Result<(), TableError> {
         match self.records.remove(&key) {
             Some(_) => Ok(()),
-            None => Err(TableError::KeyNotFoundError(key.to_value())),
+            None => Err(Self::key_not_found(&key)),
+        }
+    }
+
+    fn key_not_found(key: &K) -> TableError {
+        match key.clone().to_value() {
+            Some(value) => TableError::KeyNotFoundError(value),
+            None => TableError::KeyOutOfRangeError(K::to_column_type()),
+        }
+    }
+
+    pub fn validate_delete(&self, key: &K) -> Result<(), TableError> {
+        match self.records.contains_key(key) {
+            true => Ok(()),
+            false => Err(Self::key_not_found(key)),
+        }
+    }
+
+    pub fn update(&mut self, key: &K, column: &str, value: Value) -> Result<(), TableError> {
+        let Some(column_type) = self.columns.get(column) else {
+            return Err(TableError::InvalidColumnNameError(column.to_string()));
+        };
+
+        if column == self.key_name {
+            return Err(TableError::KeyColumnUpdateError(column.to_string()));
+        }
+
+        let Some(value) = value.coerce_to(column_type) else {
+            return Err(TableError::InsertInvalidColumnTypeError {
+                column_name: column.to_string(),
+                expected_type: *column_type,
+                got_type: ColumnType::from_value(&value),
+            });
+        };
+
+        let Some(record) = self.records.get(key) else {
+            return Err(Self::key_not_found(key));
+        };
+        let old_value = record.get_value(column)?.clone();
+
+        let Some(record) = self.records.get_mut(key) else {
+            return Err(Self::key_not_found(key));
+        };
+        record.set_value(column, value)?;
+
+        if let Err(err) = Self::validate_checks(&self.checks, record) {
+            record.set_value(column, old_value)?;
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    pub fn rename_column(&mut self, old: &str, new: &str) -> Result<(), TableError> {
+        if old == self.key_name {
+            return Err(TableError::KeyColumnUpdateError(old.to_string()));
+        }
+
+        let Some(column_type) = self.columns.get(old).copied() else {
+            return Err(TableError::InvalidColumnNameError(old.to_string()));
+        };
+
+        if self.columns.contains_key(new) {
+            return Err(TableError::ColumnAlreadyExistsError(new.to_string()));
+        }
+
+        for record in self.records.values_mut() {
+            record.rename_column(old, new.to_string())?;
+        }
+
+        self.columns.remove(old);
+        self.columns.insert(new.to_string(), column_type);
+        self.rename_column_in_checks(old, new);
+
+        Ok(())
+    }
+
+    pub fn set_key_name(&mut self, new: &str) -> Result<(), TableError> {
+        if new == self.key_name {
+            return Ok(());
+        }
+
+        if self.columns.contains_key(new) {
+            return Err(TableError::ColumnAlreadyExistsError(new.to_string()));
+        }
+
+        for record in self.records.values_mut() {
+            record.rename_column(&self.key_name, new.to_string())?;
+        }
+
+        if let Some(column_type) = self.columns.remove(&self.key_name) {
+            self.columns.insert(new.to_string(), column_type);
+        }
+        let old = std::mem::replace(&mut self.key_name, new.to_string());
+        self.rename_column_in_checks(&old, new);
+
+        Ok(())
+    }
+
+    pub fn rekey(&mut self, new_key: &str) -> Result<(), TableError> {
+        let Some(column_type) = self.columns.get(new_key).copied() else {
+            return Err(TableError::InvalidColumnNameError(new_key.to_string()));
+        };
+
+        if column_type != K::to_column_type() {
+            return Err(TableError::KeyTypeMismatchError {
+                column_name: new_key.to_string(),
+                expected_type: K::to_column_type(),
+                got_type: column_type,
+            });
+        }
+
+        let mut new_keys: Vec<K> = Vec::with_capacity(self.records.len());
+        let mut seen_keys = BTreeSet::new();
+
+        for record in self.records.values() {
+            let value = record.get_value(new_key)?.clone();
+            let Some(key) = K::from_value(value.clone()) else {
+                return Err(TableError::InvalidKeyValueError(value));
+            };
+
+            if !seen_keys.insert(key.clone()) {
+                return Err(TableError::PrimaryKeyConstraintViolation(value));
+            }
+
+            new_keys.push(key);
+        }
+
+        let records = std::mem::take(&mut self.records);
+        self.records = new_keys.into_iter().zip(records.into_values()).collect();
+        self.key_name = new_key.to_string();
+
+        Ok(())
+    }
+
+    fn rename_column_in_checks(&mut self, old: &str, new: &str) {
+        for (description, check) in &mut self.checks {
+            *description = rename_in_check_source(description, old, new);
+            check.rename_column(old, new);
+        }
+    }
+
+    fn validate_checks(
+        checks: &[(String, AnyWhereFilter)],
+        record: &Record,
+    ) -> Result<(), TableError> {
+        match checks
+            .iter()
+            .find(|(_, check)| !check.filter_record(record))
+        {
+            Some((description, _)) => {
+                Err(TableError::CheckConstraintViolation(description.clone()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn dedup(&mut self, columns: &[String]) -> Result<usize, TableError> {
+        if let Some(unknown) = columns
+            .iter()
+            .find(|column| !self.columns.contains_key(*column))
+        {
+            return Err(TableError::InvalidColumnNameError(unknown.clone()));
+        }
+
+        let mut representatives: BTreeSet<GroupKey> = BTreeSet::new();
+        let mut duplicate_keys: Vec<K> = Vec::new();
+
+        // Records are kept in key order, so the first one of each group has the smallest key.
+        for (key, record) in &self.records {
+            let group = columns
+                .iter()
+                .map(|column| record.get_value(column))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !representatives.insert(GroupKey(group)) {
+                duplicate_keys.push(key.clone());
+            }
+        }
+
+        for key in &duplicate_keys {
+            self.records.remove(key);
+        }
+
+        Ok(duplicate_keys.len())
+    }
+
+    pub fn delete_where(&mut self, filter: impl Fn(&Record) -> bool) -> usize {
+        let keys: Vec<K> = self
+            .records
+            .iter()
+            .filter(|(_, record)| filter(record))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in &keys {
+            self.records.remove(key);
+        }
+
+        keys.len()
+    }
+
+    pub fn schema(&self) -> TableSchema {
+        TableSchema {
+            name: self.name.clone(),
+            key_name: self.key_name.clone(),
+            columns: self.columns.clone(),
+            checks: self
+                .checks
+                .iter()
+                .map(|(description, _)| description.clone())
+                .collect(),
+        }
+    }
+
+    // CHECK sources are turned back into filters by `parse_check` (normally
+    // `CommandParser::parse_check`), so the storage layer does not depend on the parser
+    pub fn from_schema<E: Display>(
+        schema: TableSchema,
+        parse_check: impl Fn(&str) -> Result<AnyWhereFilter, E>,
+    ) -> Result<Table<K>, TableError> {
+        let mut builder = Table::new_builder(schema.name, schema.key_name.clone());
+
+        for (column_name, column_type) in schema.columns {
+            if column_name != schema.key_name {
+                builder = builder.with_column(column_name, column_type);
+            }
+        }
+
+        for check in schema.checks {
+            let filter = match parse_check(&check) {
+                Ok(filter) => filter,
+                Err(err) => {
+                    return Err(TableError::InvalidCheckError {
+                        check,
+                        reason: err.to_string(),
+                    });
+                }
+            };
+            builder = builder.with_check(check, filter);
+        }
+
+        builder.build()
+    }
+
+    // rejects checks that reference unknown columns or compare a column with a value of the
+    // wrong type, which would otherwise make every insert fail
+    fn validate_check_columns(&self) -> Result<(), TableError> {
+        let sample = self
+            .columns
+            .iter()
+            .fold(Record::new_builder(), |record, (name, column_type)| {
+                record.with_column(name.clone(), column_type.sample_value())
+            })
+            .build()?;
+
+        for (description, check) in &self.checks {
+            if let Err(err) = check.validate_filtering(&sample) {
+                return Err(TableError::InvalidCheckError {
+                    check: description.clone(),
+                    reason: err.to_string(),
+                });
+            }
         }
+
+        Ok(())
+    }
+
+    pub fn get(&self, key: &K) -> Option<&Record> {
+        self.records.get(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.records.keys()
+    }
+
+    pub fn iter_records(&self) -> impl Iterator<Item = &Record> {
+        self.records.values()
     }
 
     pub fn filter(&self, filter: impl Fn(&Record) -> bool) -> Vec<&Record> {
@@ -226,6 +653,99 @@ impl<K: DatabaseKey> Table<K> {
     }
 }
 
+// Rewrites column references in the source text of a CHECK, leaving string literals
+// and table qualifiers (identifiers followed by '.') untouched.
+fn rename_in_check_source(source: &str, old: &str, new: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.char_indices().peekable();
+    let mut in_string = false;
+
+    while let Some((start, c)) = chars.next() {
+        if in_string && c == '\\' {
+            result.push(c);
+            if let Some((_, escaped)) = chars.next() {
+                result.push(escaped);
+            }
+            continue;
+        }
+
+        if c == '"' {
+            in_string = !in_string;
+        }
+
+        if in_string || !c.is_ascii_alphanumeric() {
+            result.push(c);
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        while let Some((idx, next)) = chars.peek().copied() {
+            if !next.is_ascii_alphanumeric() {
+                break;
+            }
+            end = idx + next.len_utf8();
+            chars.next();
+        }
+
+        let word = &source[start..end];
+        let is_qualifier = chars.peek().is_some_and(|(_, next)| *next == '.');
+
+        if word == old && !is_qualifier {
+            result.push_str(new);
+        } else {
+            result.push_str(word);
+        }
+    }
+
+    result
+}
+
+// Values of the dedup columns, ordered with Value::total_cmp so groups fit in a BTreeSet.
+struct GroupKey<'a>(Vec<&'a Value>);
+
+impl Ord for GroupKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| a.total_cmp(b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for GroupKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for GroupKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GroupKey<'_> {}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 impl<K: DatabaseKey> TableBuilder<K> {
     #[must_use]
     pub fn with_column(mut self, column_name: String, column_type: ColumnType) -> Self {
@@ -243,21 +763,36 @@ impl<K: DatabaseKey> TableBuilder<K> {
         self
     }
 
+    #[must_use]
+    pub fn with_check(mut self, description: String, check: AnyWhereFilter) -> Self {
+        self.table.checks.push((description, check));
+
+        self
+    }
+
     pub fn build(mut self) -> Result<Table<K>, TableError> {
         let key_name = self.table.key_name.clone();
 
-        self = self.with_column(key_name, K::to_column_type());
+        if self.table.columns.contains_key(&key_name) {
+            self.errors
+                .push(TableError::ColumnCollidesWithKeyError(key_name));
+        } else {
+            self = self.with_column(key_name, K::to_column_type());
+        }
 
         if let Some(err) = self.errors.into_iter().next() {
             return Err(err);
         }
 
+        self.table.validate_check_columns()?;
+
         Ok(self.table)
     }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::CommandParser;
 
     fn prepare_test_table() -> Table<i64> {
         let table: Table<i64> = Table::new_builder("Orders".to_string(), "OrderId".to_string())
@@ -284,6 +819,46 @@ mod tests {
         assert_eq!(*table.columns.get("Capacity").unwrap(), ColumnType::INT);
     }
 
+    #[test]
+    fn table_quick_creation_test() {
+        let quick_table = Table::<i64>::quick(
+            "Orders",
+            "OrderId",
+            &[
+                ("ClientName", ColumnType::STRING),
+                ("Capacity", ColumnType::INT),
+            ],
+        )
+        .unwrap();
+        let built_table = prepare_test_table();
+
+        assert_eq!(quick_table.get_name(), built_table.get_name());
+        assert_eq!(quick_table.get_key_name(), built_table.get_key_name());
+        assert_eq!(quick_table.get_columns(), built_table.get_columns());
+        assert_eq!(quick_table.records.len(), 0);
+    }
+
+    #[test]
+    fn table_quick_creation_fail_test() {
+        let table = Table::<String>::quick(
+            "Orders",
+            "OrderId",
+            &[
+                ("ClientName", ColumnType::STRING),
+                ("ClientName", ColumnType::BOOL),
+            ],
+        );
+
+        assert_eq!(
+            table.unwrap_err(),
+            TableError::ColumnDefinedTwiceError {
+                column_name: "ClientName".to_string(),
+                first_type: ColumnType::STRING,
+                second_type: ColumnType::BOOL
+            }
+        );
+    }
+
     #[test]
     fn table_creation_fail_test() {
         let table = Table::<i64>::new_builder("Orders".to_string(), "OrderId".to_string())
@@ -303,12 +878,25 @@ mod tests {
     }
 
     #[test]
-    fn table_insert_fail_test() {
-        let mut table = prepare_test_table();
+    fn table_creation_key_collision_test() {
+        let table = Table::<i64>::new_builder("Orders".to_string(), "OrderId".to_string())
+            .with_column("OrderId".to_string(), ColumnType::INT)
+            .with_column("ClientName".to_string(), ColumnType::STRING)
+            .build();
 
-        let missing_col_result = table.insert(
-            vec!["OrderId".to_string(), "ClientName".to_string()],
-            vec![Value::INT(1), Value::STRING("Test".to_string())],
+        assert_eq!(
+            table.unwrap_err(),
+            TableError::ColumnCollidesWithKeyError("OrderId".to_string())
+        );
+    }
+
+    #[test]
+    fn table_insert_fail_test() {
+        let mut table = prepare_test_table();
+
+        let missing_col_result = table.insert(
+            vec!["OrderId".to_string(), "ClientName".to_string()],
+            vec![Value::INT(1), Value::STRING("Test".to_string())],
         );
         assert!(missing_col_result.is_err());
         assert_eq!(
@@ -446,6 +1034,167 @@ mod tests {
         assert!(!table.records.contains_key(&key_2));
     }
 
+    fn prepare_test_table_with_record() -> Table<i64> {
+        let mut table = prepare_test_table();
+        table
+            .insert(
+                vec![
+                    "OrderId".to_string(),
+                    "ClientName".to_string(),
+                    "Capacity".to_string(),
+                ],
+                vec![
+                    Value::INT(1),
+                    Value::STRING("ABC Corp".to_string()),
+                    Value::INT(100),
+                ],
+            )
+            .unwrap();
+        table
+    }
+
+    #[test]
+    fn table_update_test() {
+        let mut table = prepare_test_table_with_record();
+
+        let update_result = table.update(&1, "Capacity", Value::INT(250));
+
+        assert!(update_result.is_ok());
+        let record = table.records.get(&1).unwrap();
+        assert_eq!(*record.get_value("Capacity").unwrap(), Value::INT(250));
+        assert_eq!(
+            *record.get_value("ClientName").unwrap(),
+            Value::STRING("ABC Corp".to_string())
+        );
+    }
+
+    #[test]
+    fn table_update_type_mismatch_test() {
+        let mut table = prepare_test_table_with_record();
+
+        let update_result = table.update(&1, "Capacity", Value::STRING("Full".to_string()));
+
+        assert_eq!(
+            update_result.unwrap_err(),
+            TableError::InsertInvalidColumnTypeError {
+                column_name: "Capacity".to_string(),
+                expected_type: ColumnType::INT,
+                got_type: ColumnType::STRING
+            }
+        );
+        assert_eq!(
+            *table
+                .records
+                .get(&1)
+                .unwrap()
+                .get_value("Capacity")
+                .unwrap(),
+            Value::INT(100)
+        );
+    }
+
+    #[test]
+    fn table_update_coerces_like_insert_test() {
+        let mut table: Table<i64> =
+            Table::new_builder("Products".to_string(), "ProductId".to_string())
+                .with_column("Price".to_string(), ColumnType::FLOAT)
+                .build()
+                .unwrap();
+        table
+            .insert(
+                vec!["ProductId".to_string(), "Price".to_string()],
+                vec![Value::INT(1), Value::INT(5)],
+            )
+            .unwrap();
+
+        assert!(table.update(&1, "Price", Value::INT(7)).is_ok());
+        assert_eq!(
+            *table.records.get(&1).unwrap().get_value("Price").unwrap(),
+            Value::FLOAT(7.0)
+        );
+    }
+
+    #[test]
+    fn table_update_fail_test() {
+        let mut table = prepare_test_table_with_record();
+
+        assert_eq!(
+            table.update(&2, "Capacity", Value::INT(1)).unwrap_err(),
+            TableError::KeyNotFoundError(Value::INT(2))
+        );
+        assert_eq!(
+            table.update(&1, "Missing", Value::INT(1)).unwrap_err(),
+            TableError::InvalidColumnNameError("Missing".to_string())
+        );
+        assert_eq!(
+            table.update(&1, "OrderId", Value::INT(5)).unwrap_err(),
+            TableError::KeyColumnUpdateError("OrderId".to_string())
+        );
+    }
+
+    fn prepare_test_table_with_check() -> Table<i64> {
+        use crate::commands::select_command::{AnyFilter, Operator, ValueOperatorFilter};
+
+        let mut table = Table::new_builder("Orders".to_string(), "OrderId".to_string())
+            .with_column("Capacity".to_string(), ColumnType::INT)
+            .with_check(
+                "Capacity >= 0".to_string(),
+                ValueOperatorFilter {
+                    column_name: "Capacity".into(),
+                    op: Operator::Ge,
+                    value: Value::INT(0),
+                }
+                .to_enum(),
+            )
+            .build()
+            .unwrap();
+        table
+            .insert(
+                vec!["OrderId".to_string(), "Capacity".to_string()],
+                vec![Value::INT(1), Value::INT(10)],
+            )
+            .unwrap();
+        table
+    }
+
+    #[test]
+    fn table_check_constraint_insert_test() {
+        let mut table = prepare_test_table_with_check();
+
+        let result = table.insert(
+            vec!["OrderId".to_string(), "Capacity".to_string()],
+            vec![Value::INT(2), Value::INT(-5)],
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            TableError::CheckConstraintViolation("Capacity >= 0".to_string())
+        );
+        assert_eq!(table.records.len(), 1);
+    }
+
+    #[test]
+    fn table_check_constraint_update_test() {
+        let mut table = prepare_test_table_with_check();
+
+        let result = table.update(&1, "Capacity", Value::INT(-1));
+
+        assert_eq!(
+            result.unwrap_err(),
+            TableError::CheckConstraintViolation("Capacity >= 0".to_string())
+        );
+        assert_eq!(
+            *table
+                .records
+                .get(&1)
+                .unwrap()
+                .get_value("Capacity")
+                .unwrap(),
+            Value::INT(10)
+        );
+        assert!(table.update(&1, "Capacity", Value::INT(0)).is_ok());
+    }
+
     #[test]
     fn table_insert_test() {
         {
@@ -495,6 +1244,404 @@ mod tests {
         }
     }
 
+    #[test]
+    fn table_insert_record_test() {
+        let mut table = prepare_test_table();
+
+        let record = Record::new_builder()
+            .with_column("OrderId".to_string(), Value::INT(1))
+            .with_column("ClientName".to_string(), Value::STRING("ABC Corp".into()))
+            .with_column("Capacity".to_string(), Value::INT(100))
+            .build()
+            .unwrap();
+
+        assert!(table.validate_insert_record(&record).is_ok());
+        assert!(table.insert_record(record).is_ok());
+
+        let record = table.get(&1).unwrap();
+        assert_eq!(
+            record.get_value("ClientName").unwrap(),
+            &Value::STRING("ABC Corp".into())
+        );
+        assert_eq!(record.get_value("Capacity").unwrap(), &Value::INT(100));
+    }
+
+    #[test]
+    fn table_insert_record_missing_column_test() {
+        let mut table = prepare_test_table();
+
+        let record = Record::new_builder()
+            .with_column("OrderId".to_string(), Value::INT(1))
+            .with_column("ClientName".to_string(), Value::STRING("ABC Corp".into()))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            table.insert_record(record),
+            Err(TableError::InsertMissingColumnsError(vec![
+                "Capacity".to_string()
+            ]))
+        );
+
+        let record = Record::new_builder()
+            .with_column("ClientName".to_string(), Value::STRING("ABC Corp".into()))
+            .with_column("Capacity".to_string(), Value::INT(100))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            table.insert_record(record),
+            Err(TableError::InsertMissingColumnsError(vec![
+                "OrderId".to_string()
+            ]))
+        );
+        assert_eq!(table.records.len(), 0);
+    }
+
+    #[test]
+    fn table_insert_record_type_mismatch_test() {
+        let mut table = prepare_test_table();
+
+        let record = Record::new_builder()
+            .with_column("OrderId".to_string(), Value::INT(1))
+            .with_column("ClientName".to_string(), Value::STRING("ABC Corp".into()))
+            .with_column("Capacity".to_string(), Value::BOOL(true))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            table.validate_insert_record(&record),
+            Err(TableError::InsertInvalidColumnTypeError {
+                column_name: "Capacity".to_string(),
+                expected_type: ColumnType::INT,
+                got_type: ColumnType::BOOL,
+            })
+        );
+        assert!(table.insert_record(record).is_err());
+        assert_eq!(table.records.len(), 0);
+    }
+
+    #[test]
+    fn table_u64_out_of_range_key_test() {
+        let mut table: Table<u64> = Table::new_builder("Orders".to_string(), "OrderId".to_string())
+            .with_column("Capacity".to_string(), ColumnType::INT)
+            .build()
+            .unwrap();
+        let key = 1u64 << 63;
+        let out_of_range = Err(TableError::KeyOutOfRangeError(ColumnType::INT));
+
+        assert_eq!(table.validate_delete(&key), out_of_range);
+        assert_eq!(table.delete(key), out_of_range);
+        assert_eq!(table.update(&key, "Capacity", Value::INT(1)), out_of_range);
+        assert_eq!(
+            table.delete(7),
+            Err(TableError::KeyNotFoundError(Value::INT(7)))
+        );
+    }
+
+    #[test]
+    fn table_rename_column_test() {
+        let mut table = prepare_test_table_with_record();
+
+        table.rename_column("Capacity", "Size").unwrap();
+
+        assert_eq!(table.columns.get("Size"), Some(&ColumnType::INT));
+        assert!(!table.columns.contains_key("Capacity"));
+        for record in table.iter_records() {
+            assert!(record.get_value("Size").is_ok());
+            assert!(record.get_value("Capacity").is_err());
+        }
+    }
+
+    #[test]
+    fn table_rename_column_updates_checks_test() {
+        let mut table = prepare_test_table_with_check();
+
+        table.rename_column("Capacity", "Size").unwrap();
+
+        assert_eq!(table.get_checks()[0].0, "Size >= 0");
+        assert!(
+            table
+                .insert(
+                    vec!["OrderId".to_string(), "Size".to_string()],
+                    vec![Value::INT(2), Value::INT(7)],
+                )
+                .is_ok()
+        );
+        assert_eq!(
+            table.insert(
+                vec!["OrderId".to_string(), "Size".to_string()],
+                vec![Value::INT(3), Value::INT(-1)],
+            ),
+            Err(TableError::CheckConstraintViolation(
+                "Size >= 0".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn rename_in_check_source_test() {
+        assert_eq!(
+            rename_in_check_source("Age >= 0 AND (Age < 120)", "Age", "Years"),
+            "Years >= 0 AND (Years < 120)"
+        );
+        assert_eq!(
+            rename_in_check_source("Name != \"Age\" OR Age2 > Age", "Age", "Years"),
+            "Name != \"Age\" OR Age2 > Years"
+        );
+        assert_eq!(
+            rename_in_check_source(r#"Name != "\" Age" AND Age > 0"#, "Age", "Years"),
+            r#"Name != "\" Age" AND Years > 0"#
+        );
+        assert_eq!(
+            rename_in_check_source("Age.Age > 1.5", "Age", "Years"),
+            "Age.Years > 1.5"
+        );
+    }
+
+    #[test]
+    fn table_rename_column_fail_test() {
+        let mut table = prepare_test_table_with_record();
+
+        assert_eq!(
+            table.rename_column("OrderId", "Id"),
+            Err(TableError::KeyColumnUpdateError("OrderId".to_string()))
+        );
+        assert_eq!(
+            table.rename_column("Missing", "Size"),
+            Err(TableError::InvalidColumnNameError("Missing".to_string()))
+        );
+        assert_eq!(
+            table.rename_column("Capacity", "ClientName"),
+            Err(TableError::ColumnAlreadyExistsError(
+                "ClientName".to_string()
+            ))
+        );
+        assert_eq!(
+            table.rename_column("Capacity", "OrderId"),
+            Err(TableError::ColumnAlreadyExistsError("OrderId".to_string()))
+        );
+    }
+
+    #[test]
+    fn edit_distance_test() {
+        assert_eq!(edit_distance("Name", "Name"), 0);
+        assert_eq!(edit_distance("Nmae", "Name"), 2);
+        assert_eq!(edit_distance("Capacty", "Capacity"), 1);
+        assert_eq!(edit_distance("", "Id"), 2);
+    }
+
+    #[test]
+    fn table_insert_suggests_closest_column_test() {
+        let mut table = prepare_test_table();
+
+        let result = table.insert(
+            vec![
+                "OrderId".to_string(),
+                "ClientNmae".to_string(),
+                "Capacity".to_string(),
+            ],
+            vec![
+                Value::INT(1),
+                Value::STRING("Test".to_string()),
+                Value::INT(100),
+            ],
+        );
+
+        let err = result.unwrap_err();
+        assert_eq!(
+            err,
+            TableError::UnknownColumnWithSuggestionError {
+                column_name: "ClientNmae".to_string(),
+                suggestion: "ClientName".to_string(),
+            }
+        );
+        assert!(err.to_string().contains("did you mean ClientName?"));
+        assert_eq!(table.records.len(), 0);
+    }
+
+    #[test]
+    fn table_set_key_name_test() {
+        let mut table = prepare_test_table_with_record();
+
+        table.set_key_name("Id").unwrap();
+
+        assert_eq!(table.get_key_name(), "Id");
+        assert!(!table.get_columns().contains_key("OrderId"));
+        assert_eq!(table.get(&1).unwrap().get_value("Id"), Ok(&Value::INT(1)));
+
+        table
+            .insert(
+                vec![
+                    "Id".to_string(),
+                    "ClientName".to_string(),
+                    "Capacity".to_string(),
+                ],
+                vec![
+                    Value::INT(2),
+                    Value::STRING("XYZ Ltd".to_string()),
+                    Value::INT(50),
+                ],
+            )
+            .unwrap();
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+
+        assert_eq!(
+            table.set_key_name("Capacity"),
+            Err(TableError::ColumnAlreadyExistsError("Capacity".to_string()))
+        );
+        assert_eq!(table.get_key_name(), "Id");
+    }
+
+    #[test]
+    fn table_set_key_name_updates_checks_test() {
+        use crate::commands::select_command::Operator;
+
+        let mut table: Table<i64> = Table::new_builder("Orders".to_string(), "OrderId".to_string())
+            .with_column("Capacity".to_string(), ColumnType::INT)
+            .with_check(
+                "OrderId > 0".to_string(),
+                AnyWhereFilter::compare("OrderId", Operator::Gt, Value::INT(0)),
+            )
+            .build()
+            .unwrap();
+
+        table.set_key_name("Id").unwrap();
+
+        assert_eq!(table.get_checks()[0].0, "Id > 0");
+        assert!(
+            table
+                .insert(
+                    vec!["Id".to_string(), "Capacity".to_string()],
+                    vec![Value::INT(1), Value::INT(10)],
+                )
+                .is_ok()
+        );
+        assert_eq!(
+            table.insert(
+                vec!["Id".to_string(), "Capacity".to_string()],
+                vec![Value::INT(-1), Value::INT(10)],
+            ),
+            Err(TableError::CheckConstraintViolation("Id > 0".to_string()))
+        );
+    }
+
+    #[test]
+    fn table_rekey_test() {
+        let mut table = prepare_test_table_with_record();
+        table
+            .insert(
+                vec![
+                    "OrderId".to_string(),
+                    "ClientName".to_string(),
+                    "Capacity".to_string(),
+                ],
+                vec![
+                    Value::INT(2),
+                    Value::STRING("XYZ Ltd".to_string()),
+                    Value::INT(50),
+                ],
+            )
+            .unwrap();
+
+        table.rekey("Capacity").unwrap();
+
+        assert_eq!(table.get_key_name(), "Capacity");
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![50, 100]);
+        assert_eq!(
+            table.get(&100).unwrap().get_value("OrderId"),
+            Ok(&Value::INT(1))
+        );
+        assert_eq!(
+            table.insert(
+                vec![
+                    "OrderId".to_string(),
+                    "ClientName".to_string(),
+                    "Capacity".to_string(),
+                ],
+                vec![
+                    Value::INT(3),
+                    Value::STRING("Dup".to_string()),
+                    Value::INT(50),
+                ],
+            ),
+            Err(TableError::PrimaryKeyConstraintViolation(Value::INT(50)))
+        );
+    }
+
+    #[test]
+    fn table_dedup_test() {
+        let mut table = prepare_test_table_with_record();
+        for (id, name, capacity) in [(2, "ABC Corp", 100), (3, "ABC Corp", 50), (4, "XYZ", 100)] {
+            table
+                .insert(
+                    vec![
+                        "OrderId".to_string(),
+                        "ClientName".to_string(),
+                        "Capacity".to_string(),
+                    ],
+                    vec![
+                        Value::INT(id),
+                        Value::STRING(name.to_string()),
+                        Value::INT(capacity),
+                    ],
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            table.dedup(&["ClientName".to_string(), "Capacity".to_string()]),
+            Ok(1)
+        );
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![1, 3, 4]);
+
+        assert_eq!(table.dedup(&["Capacity".to_string()]), Ok(1));
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+
+        assert_eq!(
+            table.dedup(&["Missing".to_string()]),
+            Err(TableError::InvalidColumnNameError("Missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn table_rekey_fail_test() {
+        let mut table = prepare_test_table_with_record();
+        table
+            .insert(
+                vec![
+                    "OrderId".to_string(),
+                    "ClientName".to_string(),
+                    "Capacity".to_string(),
+                ],
+                vec![
+                    Value::INT(2),
+                    Value::STRING("XYZ Ltd".to_string()),
+                    Value::INT(100),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            table.rekey("Capacity"),
+            Err(TableError::PrimaryKeyConstraintViolation(Value::INT(100)))
+        );
+        assert_eq!(
+            table.rekey("ClientName"),
+            Err(TableError::KeyTypeMismatchError {
+                column_name: "ClientName".to_string(),
+                expected_type: ColumnType::INT,
+                got_type: ColumnType::STRING,
+            })
+        );
+        assert_eq!(
+            table.rekey("Missing"),
+            Err(TableError::InvalidColumnNameError("Missing".to_string()))
+        );
+        assert_eq!(table.get_key_name(), "OrderId");
+        assert_eq!(table.records.len(), 2);
+    }
+
     #[test]
     fn table_filter_test() {
         let mut table = prepare_test_table();
@@ -583,4 +1730,114 @@ mod tests {
         let results_all = table.filter(|_| true);
         assert_eq!(results_all.len(), 3);
     }
+
+    #[test]
+    fn table_schema_serde_round_trip_test() {
+        let table = prepare_test_table();
+
+        let json = serde_json::to_string(&table.schema()).unwrap();
+        let schema: TableSchema = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(schema, table.schema());
+
+        let restored = Table::<i64>::from_schema(schema, CommandParser::parse_check).unwrap();
+
+        assert_eq!(restored.name, table.name);
+        assert_eq!(restored.key_name, table.key_name);
+        assert_eq!(restored.columns, table.columns);
+    }
+
+    #[test]
+    fn table_schema_round_trip_keeps_checks_test() {
+        let table = prepare_test_table_with_check();
+
+        let json = serde_json::to_string(&table.schema()).unwrap();
+        let schema: TableSchema = serde_json::from_str(&json).unwrap();
+        assert_eq!(schema.checks, vec!["Capacity >= 0".to_string()]);
+
+        let mut restored = Table::<i64>::from_schema(schema, CommandParser::parse_check).unwrap();
+
+        assert_eq!(restored.schema(), table.schema());
+        assert_eq!(
+            restored.insert(
+                vec!["OrderId".to_string(), "Capacity".to_string()],
+                vec![Value::INT(1), Value::INT(-5)],
+            ),
+            Err(TableError::CheckConstraintViolation(
+                "Capacity >= 0".to_string()
+            ))
+        );
+        assert!(
+            restored
+                .insert(
+                    vec!["OrderId".to_string(), "Capacity".to_string()],
+                    vec![Value::INT(1), Value::INT(5)],
+                )
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn table_from_schema_rejects_invalid_check_test() {
+        let schema = TableSchema {
+            name: "Orders".to_string(),
+            key_name: "OrderId".to_string(),
+            columns: HashMap::from([("Capacity".to_string(), ColumnType::INT)]),
+            checks: vec!["Capacity >=".to_string()],
+        };
+
+        assert!(matches!(
+            Table::<i64>::from_schema(schema, CommandParser::parse_check),
+            Err(TableError::InvalidCheckError { .. })
+        ));
+    }
+
+    #[test]
+    fn table_build_rejects_invalid_check_test() {
+        use crate::commands::select_command::{AnyFilter, Operator, ValueOperatorFilter};
+
+        for (description, column_name, value) in [
+            ("Foo >= 0", "Foo", Value::INT(0)),
+            (
+                "Capacity >= \"x\"",
+                "Capacity",
+                Value::STRING("x".to_string()),
+            ),
+        ] {
+            let result = Table::<i64>::new_builder("Orders".to_string(), "OrderId".to_string())
+                .with_column("Capacity".to_string(), ColumnType::INT)
+                .with_check(
+                    description.to_string(),
+                    ValueOperatorFilter {
+                        column_name: column_name.into(),
+                        op: Operator::Ge,
+                        value,
+                    }
+                    .to_enum(),
+                )
+                .build();
+
+            assert!(matches!(
+                result,
+                Err(TableError::InvalidCheckError { check, .. }) if check == description
+            ));
+        }
+    }
+
+    #[test]
+    fn column_type_serde_test() {
+        for column_type in [
+            ColumnType::BOOL,
+            ColumnType::STRING,
+            ColumnType::INT,
+            ColumnType::FLOAT,
+        ] {
+            let json = serde_json::to_string(&column_type).unwrap();
+
+            assert_eq!(
+                serde_json::from_str::<ColumnType>(&json).unwrap(),
+                column_type
+            );
+        }
+    }
 }
@@ -1,8 +1,11 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+use crate::database::table::ColumnType;
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum Value {
     BOOL(bool),
     STRING(String),
@@ -10,6 +13,31 @@ pub enum Value {
     FLOAT(f64),
 }
 
+fn cmp_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+// exact, so large INTs that share an f64 representation still compare correctly
+fn cmp_int_float(a: i64, b: f64) -> Ordering {
+    const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+
+    if b.is_nan() || b >= TWO_POW_63 {
+        return Ordering::Less;
+    }
+    if b < -TWO_POW_63 {
+        return Ordering::Greater;
+    }
+
+    let whole = b.trunc();
+    a.cmp(&(whole as i64))
+        .then_with(|| cmp_floats(0.0, b - whole))
+}
+
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -32,9 +60,17 @@ pub enum RecordError {
         first_value: Value,
         second_value: Value,
     },
+    #[error("Column: {column_name} holds {got_type}, expected {expected_type}")]
+    TypeMismatchError {
+        column_name: String,
+        expected_type: String,
+        got_type: String,
+    },
+    #[error("Multiple record errors occured: {0:?}")]
+    MultipleErrors(Vec<RecordError>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Record {
     values_map: HashMap<String, Value>,
 }
@@ -55,6 +91,31 @@ impl Value {
         )
     }
 
+    // Total order used by ORDER BY: BOOL < numbers < STRING. INT and FLOAT interleave by numeric
+    // value (INT first on a tie), NaN sorts after every number and equals itself. WHERE filters
+    // keep the SQL semantics of PartialEq/PartialOrd instead.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::BOOL(a), Value::BOOL(b)) => a.cmp(b),
+            (Value::STRING(a), Value::STRING(b)) => a.cmp(b),
+            (Value::INT(a), Value::INT(b)) => a.cmp(b),
+            (Value::FLOAT(a), Value::FLOAT(b)) => cmp_floats(*a, *b),
+            (Value::INT(a), Value::FLOAT(b)) => cmp_int_float(*a, *b).then(Ordering::Less),
+            (Value::FLOAT(a), Value::INT(b)) => {
+                cmp_int_float(*b, *a).reverse().then(Ordering::Greater)
+            }
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+
+    fn type_rank(&self) -> u8 {
+        match self {
+            Value::BOOL(_) => 0,
+            Value::INT(_) | Value::FLOAT(_) => 1,
+            Value::STRING(_) => 2,
+        }
+    }
+
     pub fn type_name(&self) -> String {
         match self {
             Value::BOOL(_) => String::from("BOOL"),
@@ -63,6 +124,25 @@ impl Value {
             Value::FLOAT(_) => String::from("FLOAT"),
         }
     }
+
+    pub fn coerce_to(&self, target: &ColumnType) -> Option<Value> {
+        match (self, target) {
+            (value, target) if target.is_type_of(value) => Some(value.clone()),
+            (Value::INT(i), ColumnType::FLOAT) => Some(Value::FLOAT(*i as f64)),
+            (Value::STRING(s), ColumnType::BOOL) => s.trim().parse().ok().map(Value::BOOL),
+            (Value::STRING(s), ColumnType::INT) => s.trim().parse().ok().map(Value::INT),
+            (Value::STRING(s), ColumnType::FLOAT) => s.trim().parse().ok().map(Value::FLOAT),
+            _ => None,
+        }
+    }
+
+    pub fn promote_pair(a: &Value, b: &Value) -> Option<(Value, Value)> {
+        if let Some(b) = b.coerce_to(&ColumnType::from_value(a)) {
+            return Some((a.clone(), b));
+        }
+        let a = a.coerce_to(&ColumnType::from_value(b))?;
+        Some((a, b.clone()))
+    }
 }
 
 impl Record {
@@ -104,6 +184,77 @@ impl Record {
 
         Ok(successes)
     }
+
+    pub fn get_int(&self, column_name: &str) -> Result<i64, RecordError> {
+        match self.get_value(column_name)? {
+            Value::INT(i) => Ok(*i),
+            other => Err(Self::type_mismatch(column_name, "INT", other)),
+        }
+    }
+
+    pub fn get_string(&self, column_name: &str) -> Result<&str, RecordError> {
+        match self.get_value(column_name)? {
+            Value::STRING(s) => Ok(s),
+            other => Err(Self::type_mismatch(column_name, "STRING", other)),
+        }
+    }
+
+    pub fn get_float(&self, column_name: &str) -> Result<f64, RecordError> {
+        match self.get_value(column_name)? {
+            Value::FLOAT(f) => Ok(*f),
+            other => Err(Self::type_mismatch(column_name, "FLOAT", other)),
+        }
+    }
+
+    pub fn get_bool(&self, column_name: &str) -> Result<bool, RecordError> {
+        match self.get_value(column_name)? {
+            Value::BOOL(b) => Ok(*b),
+            other => Err(Self::type_mismatch(column_name, "BOOL", other)),
+        }
+    }
+
+    fn type_mismatch(column_name: &str, expected_type: &str, got: &Value) -> RecordError {
+        RecordError::TypeMismatchError {
+            column_name: column_name.to_string(),
+            expected_type: expected_type.to_string(),
+            got_type: got.type_name(),
+        }
+    }
+
+    pub fn set_value(&mut self, column_name: &str, value: Value) -> Result<(), RecordError> {
+        match self.values_map.get_mut(column_name) {
+            Some(stored_value) => {
+                *stored_value = value;
+                Ok(())
+            }
+            None => Err(RecordError::InvalidColumnNameError(column_name.to_string())),
+        }
+    }
+
+    pub fn rename_column(
+        &mut self,
+        column_name: &str,
+        new_name: String,
+    ) -> Result<(), RecordError> {
+        match self.values_map.remove(column_name) {
+            Some(value) => {
+                self.values_map.insert(new_name, value);
+                Ok(())
+            }
+            None => Err(RecordError::InvalidColumnNameError(column_name.to_string())),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.values_map.iter()
+    }
+
+    pub fn project(&self, column_names: &[String]) -> Result<Vec<Value>, RecordError> {
+        column_names
+            .iter()
+            .map(|name| self.get_value(name).cloned())
+            .collect()
+    }
 }
 
 impl RecordBuilder {
@@ -122,12 +273,12 @@ impl RecordBuilder {
         self
     }
 
-    pub fn build(self) -> Result<Record, RecordError> {
-        if let Some(err) = self.errors.into_iter().next() {
-            return Err(err);
+    pub fn build(mut self) -> Result<Record, RecordError> {
+        match self.errors.len() {
+            0 => Ok(self.record),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(RecordError::MultipleErrors(self.errors)),
         }
-
-        Ok(self.record)
     }
 }
 
@@ -135,6 +286,74 @@ impl RecordBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn value_total_order_test() {
+        let mut values = vec![
+            Value::STRING("b".to_string()),
+            Value::FLOAT(f64::NAN),
+            Value::INT(2),
+            Value::FLOAT(1.5),
+            Value::BOOL(true),
+            Value::FLOAT(2.0),
+            Value::STRING("a".to_string()),
+            Value::INT(-3),
+            Value::FLOAT(f64::NEG_INFINITY),
+            Value::BOOL(false),
+            Value::INT(i64::MAX),
+            Value::FLOAT(9_223_372_036_854_775_808.0),
+        ];
+        values.sort_by(Value::total_cmp);
+
+        assert_eq!(
+            format!("{values:?}"),
+            format!(
+                "{:?}",
+                [
+                    Value::BOOL(false),
+                    Value::BOOL(true),
+                    Value::FLOAT(f64::NEG_INFINITY),
+                    Value::INT(-3),
+                    Value::FLOAT(1.5),
+                    Value::INT(2),
+                    Value::FLOAT(2.0),
+                    Value::INT(i64::MAX),
+                    Value::FLOAT(9_223_372_036_854_775_808.0),
+                    Value::FLOAT(f64::NAN),
+                    Value::STRING("a".to_string()),
+                    Value::STRING("b".to_string()),
+                ]
+            )
+        );
+
+        let nan = Value::FLOAT(f64::NAN);
+        assert_eq!(nan.total_cmp(&nan), Ordering::Equal);
+        assert_eq!(
+            Value::FLOAT(-0.0).total_cmp(&Value::FLOAT(0.0)),
+            Ordering::Equal
+        );
+        assert_eq!(Value::INT(2).total_cmp(&Value::FLOAT(2.0)), Ordering::Less);
+        assert_eq!(
+            Value::INT(9_007_199_254_740_993).total_cmp(&Value::FLOAT(9_007_199_254_740_992.0)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::INT(-1).total_cmp(&Value::FLOAT(-1.5)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::INT(-2).total_cmp(&Value::FLOAT(-1.5)),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn value_comparison_keeps_sql_semantics_test() {
+        let nan = Value::FLOAT(f64::NAN);
+
+        assert_ne!(nan, nan);
+        assert_eq!(nan.partial_cmp(&Value::FLOAT(5.0)), None);
+    }
+
     #[test]
     fn value_type_name_tests() {
         let v1 = Value::BOOL(false);
@@ -148,6 +367,48 @@ mod tests {
         assert_eq!(v4.type_name(), String::from("STRING"));
     }
 
+    #[test]
+    fn value_coerce_int_to_float_test() {
+        assert_eq!(
+            Value::INT(3).coerce_to(&ColumnType::FLOAT),
+            Some(Value::FLOAT(3.0))
+        );
+        assert_eq!(
+            Value::promote_pair(&Value::FLOAT(2.5), &Value::INT(2)),
+            Some((Value::FLOAT(2.5), Value::FLOAT(2.0)))
+        );
+    }
+
+    #[test]
+    fn value_coerce_from_string_test() {
+        assert_eq!(
+            Value::STRING("true".into()).coerce_to(&ColumnType::BOOL),
+            Some(Value::BOOL(true))
+        );
+        assert_eq!(
+            Value::STRING(" 42 ".into()).coerce_to(&ColumnType::INT),
+            Some(Value::INT(42))
+        );
+        assert_eq!(
+            Value::STRING("x".into()).coerce_to(&ColumnType::STRING),
+            Some(Value::STRING("x".into()))
+        );
+    }
+
+    #[test]
+    fn value_coerce_impossible_test() {
+        assert_eq!(
+            Value::STRING("yes".into()).coerce_to(&ColumnType::BOOL),
+            None
+        );
+        assert_eq!(Value::FLOAT(1.5).coerce_to(&ColumnType::INT), None);
+        assert_eq!(Value::INT(1).coerce_to(&ColumnType::STRING), None);
+        assert_eq!(
+            Value::promote_pair(&Value::BOOL(true), &Value::INT(1)),
+            None
+        );
+    }
+
     #[test]
     fn value_type_true_comparison_test() {
         let v1 = Value::BOOL(false);
@@ -243,6 +504,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn record_building_aggregates_errors_test() {
+        let record = Record::new_builder()
+            .with_column("Name".into(), Value::STRING(String::from("John")))
+            .with_column("Age".into(), Value::INT(24))
+            .with_column("Name".into(), Value::STRING(String::from("Jack")))
+            .with_column("Age".into(), Value::INT(30))
+            .build();
+
+        assert_eq!(
+            record.unwrap_err(),
+            RecordError::MultipleErrors(vec![
+                RecordError::ColumnDefinedTwiceError {
+                    column_name: "Name".to_string(),
+                    first_value: Value::STRING(String::from("John")),
+                    second_value: Value::STRING(String::from("Jack"))
+                },
+                RecordError::ColumnDefinedTwiceError {
+                    column_name: "Age".to_string(),
+                    first_value: Value::INT(24),
+                    second_value: Value::INT(30)
+                },
+            ])
+        );
+    }
+
     #[test]
     fn record_get_value_success_test() {
         let record = Record::new_builder()
@@ -311,4 +598,167 @@ mod tests {
 
         assert!(error_name == "Missing1" || error_name == "Missing2");
     }
+
+    #[test]
+    fn record_project_preserves_requested_order_test() {
+        let record = Record::new_builder()
+            .with_column("A".into(), Value::INT(1))
+            .with_column("B".into(), Value::STRING("Two".to_string()))
+            .with_column("C".into(), Value::FLOAT(3.0f64))
+            .with_column("D".into(), Value::BOOL(true))
+            .build()
+            .unwrap();
+
+        let column_names = vec![
+            "D".to_string(),
+            "A".to_string(),
+            "C".to_string(),
+            "B".to_string(),
+        ];
+
+        let result = record.project(&column_names).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                Value::BOOL(true),
+                Value::INT(1),
+                Value::FLOAT(3.0f64),
+                Value::STRING("Two".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn record_project_repeated_column_test() {
+        let record = Record::new_builder()
+            .with_column("A".into(), Value::INT(1))
+            .with_column("B".into(), Value::INT(2))
+            .build()
+            .unwrap();
+
+        let column_names = vec!["B".to_string(), "A".to_string(), "B".to_string()];
+
+        let result = record.project(&column_names).unwrap();
+
+        assert_eq!(result, vec![Value::INT(2), Value::INT(1), Value::INT(2)]);
+    }
+
+    #[test]
+    fn record_rename_column_test() {
+        let mut record = Record::new_builder()
+            .with_column("Age".into(), Value::INT(30))
+            .build()
+            .unwrap();
+
+        record.rename_column("Age", "Years".into()).unwrap();
+
+        assert_eq!(record.get_value("Years"), Ok(&Value::INT(30)));
+        assert!(record.get_value("Age").is_err());
+        assert_eq!(
+            record.rename_column("Age", "Other".into()),
+            Err(RecordError::InvalidColumnNameError("Age".into()))
+        );
+    }
+
+    #[test]
+    fn record_project_failure_test() {
+        let record = Record::new_builder()
+            .with_column("A".into(), Value::INT(1))
+            .build()
+            .unwrap();
+
+        let result = record.project(&["A".to_string(), "Missing".to_string()]);
+
+        assert_eq!(
+            result.unwrap_err(),
+            RecordError::InvalidColumnNameError("Missing".to_string())
+        );
+    }
+
+    fn mixed_record() -> Record {
+        Record::new_builder()
+            .with_column("Id".into(), Value::INT(7))
+            .with_column("Name".into(), Value::STRING("Alice".into()))
+            .with_column("Score".into(), Value::FLOAT(9.5))
+            .with_column("Active".into(), Value::BOOL(true))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn typed_getters_test() {
+        let record = mixed_record();
+
+        assert_eq!(record.get_int("Id"), Ok(7));
+        assert_eq!(record.get_string("Name"), Ok("Alice"));
+        assert_eq!(record.get_float("Score"), Ok(9.5));
+        assert_eq!(record.get_bool("Active"), Ok(true));
+    }
+
+    #[test]
+    fn typed_getters_mismatch_test() {
+        let record = mixed_record();
+
+        assert_eq!(
+            record.get_int("Name"),
+            Err(RecordError::TypeMismatchError {
+                column_name: "Name".into(),
+                expected_type: "INT".into(),
+                got_type: "STRING".into(),
+            })
+        );
+        assert!(matches!(
+            record.get_string("Id"),
+            Err(RecordError::TypeMismatchError { .. })
+        ));
+        assert!(matches!(
+            record.get_float("Active"),
+            Err(RecordError::TypeMismatchError { .. })
+        ));
+        assert!(matches!(
+            record.get_bool("Score"),
+            Err(RecordError::TypeMismatchError { .. })
+        ));
+        assert_eq!(
+            record.get_bool("Missing"),
+            Err(RecordError::InvalidColumnNameError("Missing".into()))
+        );
+    }
+
+    #[test]
+    fn value_serde_round_trip_test() {
+        let values = [
+            Value::BOOL(true),
+            Value::STRING("Zażółć \"gęślą\" jaźń".into()),
+            Value::INT(i64::MIN),
+            Value::FLOAT(0.1 + 0.2),
+            Value::FLOAT(-1.5e300),
+        ];
+
+        for value in values {
+            let json = serde_json::to_string(&value).unwrap();
+            let decoded: Value = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(decoded, value);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&Value::INT(5)).unwrap(),
+            r#"{"INT":5}"#
+        );
+    }
+
+    #[test]
+    fn record_serde_round_trip_test() {
+        let record = mixed_record();
+
+        let json = serde_json::to_string(&record).unwrap();
+        let decoded: Record = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.get_int("Id"), Ok(7));
+        assert_eq!(decoded.get_string("Name"), Ok("Alice"));
+        assert_eq!(decoded.get_float("Score"), Ok(9.5));
+        assert_eq!(decoded.get_bool("Active"), Ok(true));
+    }
 }
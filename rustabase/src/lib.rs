@@ -9,8 +9,9 @@ pub mod parser;
 
 #[cfg(test)]
 mod integration_tests {
-    use crate::commands::command::{Command, CommandResult};
+    use crate::commands::command::{Command, CommandError, CommandResult};
     use crate::database::Database;
+    use crate::database::table::TableError;
     use crate::database::table::record::Value;
     use crate::parser::CommandParser;
 
@@ -172,6 +173,162 @@ mod integration_tests {
         }
     }
 
+    #[test]
+    fn full_pipeline_check_constraint_i64() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let create_cmd = "CREATE Users KEY UserId FIELDS Name:STRING, Age:INT CHECK(Age >= 0)";
+        parser
+            .parse_command(&mut db, create_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let insert_cmd = "INSERT UserId=1, Name=\"Alice\", Age=30 INTO Users";
+        let result = parser.parse_command(&mut db, insert_cmd).unwrap().execute();
+        assert!(result.is_ok());
+
+        let invalid_insert_cmd = "INSERT UserId=2, Name=\"Bob\", Age=-1 INTO Users";
+        let result = parser
+            .parse_command(&mut db, invalid_insert_cmd)
+            .unwrap()
+            .execute();
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::TableError(TableError::CheckConstraintViolation("Age >= 0".into()))
+        );
+
+        let table = db.get_table("Users").unwrap();
+        assert_eq!(
+            table.update(&1, "Age", Value::INT(-3)),
+            Err(TableError::CheckConstraintViolation("Age >= 0".into()))
+        );
+        assert!(table.update(&1, "Age", Value::INT(31)).is_ok());
+
+        let select_cmd = "SELECT UserId, Age FROM Users";
+        let result = parser
+            .parse_command(&mut db, select_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+        if let CommandResult::RecordValueList(_, records) = result {
+            assert_eq!(records, vec![vec![Value::INT(1), Value::INT(31)]]);
+        } else {
+            panic!("Expected RecordValueList");
+        }
+    }
+
+    #[test]
+    fn full_pipeline_check_constraint_with_and_string() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<String>::new();
+
+        let create_cmd =
+            "CREATE Users KEY UserId FIELDS Age:INT CHECK(Age >= 18 AND Age < 130), Name:STRING";
+        parser
+            .parse_command(&mut db, create_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let insert_cmd = "INSERT UserId=\"u1\", Name=\"Alice\", Age=30 INTO Users";
+        let result = parser.parse_command(&mut db, insert_cmd).unwrap().execute();
+        assert!(result.is_ok());
+
+        let invalid_insert_cmd = "INSERT UserId=\"u2\", Name=\"Tim\", Age=12 INTO Users";
+        let result = parser
+            .parse_command(&mut db, invalid_insert_cmd)
+            .unwrap()
+            .execute();
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::TableError(TableError::CheckConstraintViolation(
+                "Age >= 18 AND Age < 130".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn full_pipeline_rename_table_i64() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let create_cmd = "CREATE Users KEY UserId FIELDS Name:STRING";
+        parser
+            .parse_command(&mut db, create_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let insert_cmd = "INSERT UserId=1, Name=\"Alice\" INTO Users";
+        parser
+            .parse_command(&mut db, insert_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let rename_cmd = "RENAME Users TO Accounts";
+        let result = parser
+            .parse_command(&mut db, rename_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+        assert!(matches!(result, CommandResult::Void));
+
+        let select_cmd = "SELECT Name FROM Accounts";
+        let result = parser
+            .parse_command(&mut db, select_cmd)
+            .unwrap()
+            .execute()
+            .unwrap();
+        if let CommandResult::RecordValueList(columns, records) = result {
+            assert_eq!(columns, vec!["Name"]);
+            assert_eq!(records, vec![vec![Value::STRING("Alice".into())]]);
+        } else {
+            panic!("Expected RecordValueList");
+        }
+
+        let old_select_cmd = "SELECT Name FROM Users";
+        let result = parser.parse_command(&mut db, old_select_cmd);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn full_pipeline_rename_table_errors_string() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<String>::new();
+
+        let create_users = "CREATE Users KEY UserId FIELDS Name:STRING";
+        parser
+            .parse_command(&mut db, create_users)
+            .unwrap()
+            .execute()
+            .unwrap();
+        let create_accounts = "CREATE Accounts KEY AccountId";
+        parser
+            .parse_command(&mut db, create_accounts)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let rename_existing = "RENAME Users TO Accounts";
+        let result = parser
+            .parse_command(&mut db, rename_existing)
+            .unwrap()
+            .execute();
+        assert!(result.is_err());
+
+        let rename_missing = "RENAME Orders TO Purchases";
+        let result = parser
+            .parse_command(&mut db, rename_missing)
+            .unwrap()
+            .execute();
+        assert!(result.is_err());
+
+        assert_eq!(db.get_table_names(), vec!["Users", "Accounts"]);
+    }
+
     #[test]
     fn full_pipeline_save_as_read_from_success() {
         let mut parser = CommandParser::new();
@@ -244,4 +401,1068 @@ mod integration_tests {
         let result = parser.parse_command(&mut db, invalid_cmd);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn explain_select_describes_plan_without_executing() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        parser
+            .parse_command(
+                &mut db,
+                "CREATE Users KEY UserId FIELDS Name:STRING, Age:INT",
+            )
+            .unwrap()
+            .execute()
+            .unwrap();
+        parser
+            .parse_command(
+                &mut db,
+                "INSERT UserId=1, Name=\"Alice\", Age=30 INTO Users",
+            )
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let result = parser
+            .parse_command(&mut db, "EXPLAIN SELECT Name FROM Users WHERE Age > 18")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(columns, rows) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(columns, vec!["Step", "Detail"]);
+
+        let details: Vec<Value> = rows.into_iter().map(|row| row[1].clone()).collect();
+        assert!(details.contains(&Value::STRING("Users".into())));
+        assert!(details.contains(&Value::STRING("full scan".into())));
+        assert!(!details.contains(&Value::STRING("Alice".into())));
+    }
+
+    #[test]
+    fn select_where_key_in_returns_only_listed_keys() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let setup = [
+            "CREATE Users KEY UserId FIELDS Name:STRING",
+            "INSERT UserId=1, Name=\"Alice\" INTO Users",
+            "INSERT UserId=2, Name=\"Bob\" INTO Users",
+            "INSERT UserId=3, Name=\"Carol\" INTO Users",
+        ];
+        for command in setup {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let result = parser
+            .parse_command(
+                &mut db,
+                "SELECT UserId, Name FROM Users WHERE KEY IN (3, 1, 42)",
+            )
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(columns, records) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(columns, vec!["UserId", "Name"]);
+        assert_eq!(
+            records,
+            vec![
+                vec![Value::INT(1), Value::STRING("Alice".into())],
+                vec![Value::INT(3), Value::STRING("Carol".into())],
+            ]
+        );
+    }
+
+    #[test]
+    fn select_where_key_in_with_string_keys() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<String>::new();
+
+        let setup = [
+            "CREATE Users KEY UserId FIELDS Name:STRING",
+            "INSERT UserId=\"a\", Name=\"Alice\" INTO Users",
+            "INSERT UserId=\"b\", Name=\"Bob\" INTO Users",
+        ];
+        for command in setup {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let result = parser
+            .parse_command(
+                &mut db,
+                "SELECT Name FROM Users WHERE KEY IN (\"b\", \"missing\")",
+            )
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(_, records) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(records, vec![vec![Value::STRING("Bob".into())]]);
+    }
+
+    #[test]
+    fn select_into_materializes_filtered_rows() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let setup = [
+            "CREATE Users KEY UserId FIELDS Name:STRING, Age:INT",
+            "INSERT UserId=1, Name=\"Alice\", Age=30 INTO Users",
+            "INSERT UserId=2, Name=\"Bob\", Age=15 INTO Users",
+            "INSERT UserId=3, Name=\"Carol\", Age=22 INTO Users",
+            "SELECT UserId, Name, Age INTO Adults FROM Users WHERE Age > 18",
+        ];
+        for command in setup {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let result = parser
+            .parse_command(&mut db, "SELECT Name, Age FROM Adults")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(_, records) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(
+            records,
+            vec![
+                vec![Value::STRING("Alice".into()), Value::INT(30)],
+                vec![Value::STRING("Carol".into()), Value::INT(22)],
+            ]
+        );
+    }
+
+    #[test]
+    fn select_into_without_key_column_fails() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        parser
+            .parse_command(&mut db, "CREATE Users KEY UserId FIELDS Name:STRING")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let result = parser
+            .parse_command(&mut db, "SELECT Name INTO Names FROM Users")
+            .unwrap()
+            .execute();
+
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::MissingKeyColumnError("UserId".into())
+        );
+        assert_eq!(db.get_table_names(), vec!["Users"]);
+    }
+
+    fn prepare_people(parser: &mut CommandParser, db: &mut Database<i64>) {
+        let setup = [
+            "CREATE People KEY PersonId FIELDS Name:STRING, Age:INT",
+            "INSERT PersonId=1, Name=\"Alice\", Age=30 INTO People",
+            "INSERT PersonId=2, Name=\"Bob\", Age=15 INTO People",
+            "INSERT PersonId=3, Name=\"Carol\", Age=12 INTO People",
+            "INSERT PersonId=4, Name=\"Dave\", Age=45 INTO People",
+        ];
+        for command in setup {
+            parser
+                .parse_command(db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+    }
+
+    fn remaining_names(parser: &mut CommandParser, db: &mut Database<i64>) -> Vec<Vec<Value>> {
+        let result = parser
+            .parse_command(db, "SELECT Name FROM People")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(_, records) = result else {
+            panic!("Expected RecordValueList");
+        };
+        records
+    }
+
+    #[test]
+    fn delete_where_removes_all_matching_rows() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let result = parser
+            .parse_command(&mut db, "DELETE FROM People WHERE Age < 18")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(columns, records) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(columns, vec!["Deleted"]);
+        assert_eq!(records, vec![vec![Value::INT(2)]]);
+        assert_eq!(
+            remaining_names(&mut parser, &mut db),
+            vec![
+                vec![Value::STRING("Alice".into())],
+                vec![Value::STRING("Dave".into())],
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_where_matching_nothing_removes_zero_rows() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let result = parser
+            .parse_command(&mut db, "DELETE FROM People WHERE Age > 100")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let CommandResult::RecordValueList(_, records) = result else {
+            panic!("Expected RecordValueList");
+        };
+        assert_eq!(records, vec![vec![Value::INT(0)]]);
+        assert_eq!(remaining_names(&mut parser, &mut db).len(), 4);
+    }
+
+    #[test]
+    fn delete_by_single_key_still_works() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let result = parser
+            .parse_command(&mut db, "DELETE 2 FROM People")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        assert!(matches!(result, CommandResult::Void));
+        assert_eq!(remaining_names(&mut parser, &mut db).len(), 3);
+    }
+
+    #[test]
+    fn tolerant_replay_skips_failing_lines() {
+        use crate::commands::read_from_command::{ReplayMode, replay_commands};
+
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let commands = vec![
+            "CREATE Users KEY UserId FIELDS Name:STRING".to_string(),
+            "INSERT UserId=1, Name=\"Alice\" INTO Users".to_string(),
+            "INSERT UserId=1, Name=\"Duplicate\" INTO Users".to_string(),
+            "THIS IS NOT A COMMAND".to_string(),
+            "INSERT UserId=2, Name=\"Bob\" INTO Users".to_string(),
+            "SELECT Name FROM Users".to_string(),
+        ];
+
+        let mut selected = Vec::new();
+        let report = replay_commands(
+            &mut db,
+            &mut parser,
+            commands,
+            ReplayMode::ContinueOnError,
+            |result| {
+                if let CommandResult::RecordValueList(_, records) = result {
+                    selected = records;
+                }
+            },
+        );
+
+        assert_eq!(report.applied, 4);
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|failure| failure.line)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(report.failures[1].command, "THIS IS NOT A COMMAND");
+        assert_eq!(
+            selected,
+            vec![
+                vec![Value::STRING("Alice".into())],
+                vec![Value::STRING("Bob".into())],
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_lists_primary_keys_in_sorted_order() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let commands = [
+            "CREATE Users KEY UserId FIELDS Name:STRING",
+            "INSERT UserId=30, Name=\"Carol\" INTO Users",
+            "INSERT UserId=10, Name=\"Alice\" INTO Users",
+            "INSERT UserId=20, Name=\"Bob\" INTO Users",
+        ];
+        for command in commands {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let result = parser
+            .parse_command(&mut db, "KEYS FROM Users")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        if let CommandResult::RecordValueList(columns, records) = result {
+            assert_eq!(columns, vec!["UserId"]);
+            assert_eq!(
+                records,
+                vec![
+                    vec![Value::INT(10)],
+                    vec![Value::INT(20)],
+                    vec![Value::INT(30)],
+                ]
+            );
+        } else {
+            panic!("Expected RecordValueList");
+        }
+    }
+
+    #[test]
+    fn keys_on_empty_table_returns_no_rows() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        parser
+            .parse_command(&mut db, "CREATE Users KEY UserId FIELDS Name:STRING")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let result = parser
+            .parse_command(&mut db, "KEYS FROM Users")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        if let CommandResult::RecordValueList(columns, records) = result {
+            assert_eq!(columns, vec!["UserId"]);
+            assert!(records.is_empty());
+        } else {
+            panic!("Expected RecordValueList");
+        }
+    }
+
+    fn run_query(
+        parser: &mut CommandParser,
+        db: &mut Database<i64>,
+        command: &str,
+    ) -> Vec<Vec<Value>> {
+        match parser
+            .parse_command(db, command)
+            .unwrap()
+            .execute()
+            .unwrap()
+        {
+            CommandResult::RecordValueList(_, records) => records,
+            _ => panic!("Expected RecordValueList"),
+        }
+    }
+
+    #[test]
+    fn count_matches_number_of_selected_rows() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        let commands = [
+            "CREATE Users KEY UserId FIELDS Name:STRING, Age:INT",
+            "INSERT UserId=1, Name=\"Alice\", Age=30 INTO Users",
+            "INSERT UserId=2, Name=\"Bob\", Age=15 INTO Users",
+            "INSERT UserId=3, Name=\"Carol\", Age=42 INTO Users",
+        ];
+        for command in commands {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let count_all = run_query(&mut parser, &mut db, "COUNT FROM Users");
+        let select_all = run_query(&mut parser, &mut db, "SELECT Name FROM Users");
+        assert_eq!(count_all, vec![vec![Value::INT(3)]]);
+        assert_eq!(select_all.len(), 3);
+
+        let count_adults = run_query(&mut parser, &mut db, "COUNT FROM Users WHERE Age > 18");
+        let select_adults = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name FROM Users WHERE Age > 18",
+        );
+        assert_eq!(count_adults, vec![vec![Value::INT(2)]]);
+        assert_eq!(select_adults.len(), 2);
+    }
+
+    #[test]
+    fn count_with_invalid_filter_type_fails() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        parser
+            .parse_command(&mut db, "CREATE Users KEY UserId FIELDS Age:INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+        parser
+            .parse_command(&mut db, "INSERT UserId=1, Age=30 INTO Users")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let result = parser
+            .parse_command(&mut db, "COUNT FROM Users WHERE Age > true")
+            .unwrap()
+            .execute();
+
+        assert!(matches!(
+            result,
+            Err(CommandError::InvalidValueError { .. })
+        ));
+    }
+
+    #[test]
+    fn alter_rename_column_keeps_data_under_new_name() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        parser
+            .parse_command(&mut db, "ALTER People RENAME COLUMN Age TO Years")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let adults = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name, Years FROM People WHERE Years > 18",
+        );
+        assert_eq!(
+            adults,
+            vec![
+                vec![Value::STRING("Alice".into()), Value::INT(30)],
+                vec![Value::STRING("Dave".into()), Value::INT(45)],
+            ]
+        );
+
+        let old_name = parser
+            .parse_command(&mut db, "SELECT Age FROM People")
+            .unwrap()
+            .execute();
+        assert!(old_name.is_err());
+    }
+
+    #[test]
+    fn alter_rename_column_errors() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let cases = [
+            (
+                "ALTER People RENAME COLUMN PersonId TO Id",
+                TableError::KeyColumnUpdateError("PersonId".into()),
+            ),
+            (
+                "ALTER People RENAME COLUMN Missing TO Other",
+                TableError::InvalidColumnNameError("Missing".into()),
+            ),
+            (
+                "ALTER People RENAME COLUMN Age TO Name",
+                TableError::ColumnAlreadyExistsError("Name".into()),
+            ),
+        ];
+
+        for (command, expected) in cases {
+            let result = parser.parse_command(&mut db, command).unwrap().execute();
+            assert_eq!(result.unwrap_err(), CommandError::TableError(expected));
+        }
+    }
+
+    #[test]
+    fn u64_keyed_database_insert_select_delete() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<u64>::new();
+
+        let commands = [
+            "CREATE Users KEY UserId FIELDS Name:STRING",
+            "INSERT UserId=1, Name=\"Alice\" INTO Users",
+            "INSERT UserId=9223372036854775807, Name=\"Bob\" INTO Users",
+            "DELETE 1 FROM Users",
+        ];
+        for command in commands {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let rows = match parser
+            .parse_command(&mut db, "SELECT UserId, Name FROM Users")
+            .unwrap()
+            .execute()
+            .unwrap()
+        {
+            CommandResult::RecordValueList(_, rows) => rows,
+            _ => panic!("Expected RecordValueList"),
+        };
+        assert_eq!(
+            rows,
+            vec![vec![Value::INT(i64::MAX), Value::STRING("Bob".into())]]
+        );
+        assert_eq!(
+            db.get_table("Users").unwrap().keys().collect::<Vec<_>>(),
+            vec![&(i64::MAX as u64)]
+        );
+    }
+
+    #[test]
+    fn u64_keyed_database_rejects_negative_keys() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<u64>::new();
+
+        parser
+            .parse_command(&mut db, "CREATE Users KEY UserId FIELDS Name:STRING")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let insert = parser
+            .parse_command(&mut db, "INSERT UserId=-1, Name=\"Eve\" INTO Users")
+            .unwrap()
+            .execute();
+        assert!(matches!(
+            insert,
+            Err(CommandError::TableError(
+                TableError::InsertInvalidColumnTypeError { .. }
+            ))
+        ));
+
+        assert!(
+            parser
+                .parse_command(&mut db, "DELETE -1 FROM Users")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn insert_with_misspelled_column_suggests_existing_one() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        parser
+            .parse_command(
+                &mut db,
+                "CREATE Users KEY UserId FIELDS Name:STRING, Age:INT",
+            )
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let err = parser
+            .parse_command(
+                &mut db,
+                "INSERT UserId=1, Nmae=\"Alice\", Age=30 INTO Users",
+            )
+            .unwrap()
+            .execute()
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            CommandError::TableError(TableError::UnknownColumnWithSuggestionError {
+                column_name: "Nmae".into(),
+                suggestion: "Name".into(),
+            })
+        );
+        assert!(err.to_string().contains("did you mean Name?"));
+    }
+
+    fn prepare_ordered_people(parser: &mut CommandParser, db: &mut Database<i64>) {
+        let setup = [
+            "CREATE People KEY PersonId FIELDS Name:STRING, Age:INT",
+            "INSERT PersonId=1, Name=\"Bob\", Age=30 INTO People",
+            "INSERT PersonId=2, Name=\"Alice\", Age=30 INTO People",
+            "INSERT PersonId=3, Name=\"Carol\", Age=25 INTO People",
+            "INSERT PersonId=4, Name=\"Dave\", Age=25 INTO People",
+        ];
+        for command in setup {
+            parser
+                .parse_command(db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+    }
+
+    fn names(rows: Vec<Vec<Value>>) -> Vec<String> {
+        rows.into_iter()
+            .map(|row| match &row[0] {
+                Value::STRING(name) => name.clone(),
+                other => panic!("Expected STRING, got {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn order_by_secondary_key_resolves_ties() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_ordered_people(&mut parser, &mut db);
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name FROM People ORDER BY Age DESC, Name ASC",
+        );
+        assert_eq!(names(rows), vec!["Alice", "Bob", "Carol", "Dave"]);
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name FROM People ORDER BY Age DESC, Name DESC",
+        );
+        assert_eq!(names(rows), vec!["Bob", "Alice", "Dave", "Carol"]);
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name FROM People ORDER BY Age, Name DESC",
+        );
+        assert_eq!(names(rows), vec!["Dave", "Carol", "Bob", "Alice"]);
+    }
+
+    #[test]
+    fn order_by_combines_with_where() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_ordered_people(&mut parser, &mut db);
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name, Age FROM People WHERE Age < 30 ORDER BY Name DESC",
+        );
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::STRING("Dave".into()), Value::INT(25)],
+                vec![Value::STRING("Carol".into()), Value::INT(25)],
+            ]
+        );
+    }
+
+    #[test]
+    fn alter_set_key_rekeys_table() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<String>::new();
+
+        let setup = [
+            "CREATE Users KEY Login FIELDS Email:STRING, Team:STRING, Age:INT",
+            "INSERT Login=\"al\", Email=\"alice@x.io\", Team=\"red\", Age=30 INTO Users",
+            "INSERT Login=\"bo\", Email=\"bob@x.io\", Team=\"red\", Age=25 INTO Users",
+            "ALTER Users SET KEY Email",
+        ];
+        for command in setup {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let duplicate = parser
+            .parse_command(&mut db, "ALTER Users SET KEY Team")
+            .unwrap()
+            .execute();
+        assert!(duplicate.is_err());
+
+        parser
+            .parse_command(&mut db, "DELETE \"bob@x.io\" FROM Users")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let table = db.get_table("Users").unwrap();
+        assert_eq!(table.get_key_name(), "Email");
+        assert_eq!(table.keys().collect::<Vec<_>>(), vec!["alice@x.io"]);
+    }
+
+    #[test]
+    fn dry_run_does_not_modify_table() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let valid = parser
+            .parse_command(
+                &mut db,
+                "DRY_RUN INSERT PersonId=5, Name=\"Eve\", Age=22 INTO People",
+            )
+            .unwrap()
+            .execute();
+        assert!(matches!(valid, Ok(CommandResult::Void)));
+
+        let invalid = parser
+            .parse_command(
+                &mut db,
+                "DRY_RUN INSERT PersonId=6, Name=\"Frank\", Age=\"old\" INTO People",
+            )
+            .unwrap()
+            .execute();
+        assert!(invalid.is_err());
+
+        let delete = parser
+            .parse_command(&mut db, "DRY_RUN DELETE FROM People WHERE Age < 18")
+            .unwrap()
+            .execute();
+        assert!(delete.is_ok());
+
+        let rows = run_query(&mut parser, &mut db, "SELECT PersonId FROM People");
+        assert_eq!(rows.len(), 4);
+    }
+
+    #[test]
+    fn alter_rename_key_keeps_records() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        parser
+            .parse_command(&mut db, "ALTER People RENAME KEY TO Id")
+            .unwrap()
+            .execute()
+            .unwrap();
+        parser
+            .parse_command(&mut db, "INSERT Id=5, Name=\"Eve\", Age=22 INTO People")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let rows = run_query(&mut parser, &mut db, "SELECT Id FROM People WHERE Age > 20");
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::INT(1)],
+                vec![Value::INT(4)],
+                vec![Value::INT(5)]
+            ]
+        );
+
+        let collision = parser
+            .parse_command(&mut db, "ALTER People RENAME KEY TO Name")
+            .unwrap()
+            .execute();
+        assert_eq!(
+            collision.unwrap_err(),
+            CommandError::TableError(TableError::ColumnAlreadyExistsError("Name".into()))
+        );
+    }
+
+    #[test]
+    fn select_with_table_alias_matches_unqualified() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let aliased = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT p.Name, p.Age FROM People p WHERE p.Age > 14 ORDER BY p.Age DESC",
+        );
+        let plain = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name, Age FROM People WHERE Age > 14 ORDER BY Age DESC",
+        );
+
+        assert_eq!(aliased, plain);
+        assert_eq!(names(aliased), vec!["Dave", "Alice", "Bob"]);
+
+        let without_where = run_query(&mut parser, &mut db, "SELECT p.Name FROM People p");
+        assert_eq!(without_where.len(), 4);
+    }
+
+    #[test]
+    fn dedup_keeps_lowest_keyed_representative() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let duplicates = [
+            "INSERT PersonId=7, Name=\"Alice\", Age=30 INTO People",
+            "INSERT PersonId=5, Name=\"Alice\", Age=30 INTO People",
+            "INSERT PersonId=6, Name=\"Bob\", Age=15 INTO People",
+            "INSERT PersonId=8, Name=\"Bob\", Age=16 INTO People",
+        ];
+        for command in duplicates {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let deleted = run_query(&mut parser, &mut db, "DEDUP People ON (Name, Age)");
+        assert_eq!(deleted, vec![vec![Value::INT(3)]]);
+
+        let rows = run_query(&mut parser, &mut db, "SELECT PersonId, Name FROM People");
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::INT(1), Value::STRING("Alice".into())],
+                vec![Value::INT(2), Value::STRING("Bob".into())],
+                vec![Value::INT(3), Value::STRING("Carol".into())],
+                vec![Value::INT(4), Value::STRING("Dave".into())],
+                vec![Value::INT(8), Value::STRING("Bob".into())],
+            ]
+        );
+
+        let deleted = run_query(&mut parser, &mut db, "DEDUP People ON (Name)");
+        assert_eq!(deleted, vec![vec![Value::INT(1)]]);
+        assert_eq!(
+            names(run_query(&mut parser, &mut db, "SELECT Name FROM People")),
+            vec!["Alice", "Bob", "Carol", "Dave"]
+        );
+    }
+
+    #[test]
+    fn dump_reconstructs_current_state() {
+        use crate::commands::read_from_command::{ReplayMode, replay_commands};
+
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let edits = [
+            "CREATE Scores KEY ScoreId FIELDS Points:FLOAT CHECK (Points >= 0.0), Passed:BOOL",
+            "INSERT ScoreId=1, Points=2.0, Passed=true INTO Scores",
+            "INSERT ScoreId=2, Points=7.5, Passed=false INTO Scores",
+            "DELETE 2 FROM People",
+            "DELETE FROM People WHERE Age > 40",
+            "INSERT PersonId=9, Name=\"Eve\", Age=22 INTO People",
+            r#"INSERT PersonId=10, Name="Say \"hi\" to C:\\", Age=33 INTO People"#,
+            "ALTER People RENAME COLUMN Age TO Years",
+            "RENAME People TO Persons",
+        ];
+        for command in edits {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let dump_file = "test_dump_current_state.sql";
+        parser
+            .parse_command(&mut db, &format!("DUMP TO {dump_file}"))
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let mut fresh_parser = CommandParser::new();
+        let mut fresh_db = Database::<i64>::new();
+        let CommandResult::CommandList(commands) = fresh_parser
+            .parse_command(&mut fresh_db, &format!("READ_FROM {dump_file}"))
+            .unwrap()
+            .execute()
+            .unwrap()
+        else {
+            panic!("Expected CommandList");
+        };
+        std::fs::remove_file(dump_file).unwrap();
+
+        let report = replay_commands(
+            &mut fresh_db,
+            &mut fresh_parser,
+            commands,
+            ReplayMode::StopOnError,
+            |_| (),
+        );
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+        assert_eq!(report.applied, 8);
+
+        for query in [
+            "SELECT PersonId, Name, Years FROM Persons",
+            "SELECT ScoreId, Points, Passed FROM Scores",
+        ] {
+            assert_eq!(
+                run_query(&mut fresh_parser, &mut fresh_db, query),
+                run_query(&mut parser, &mut db, query)
+            );
+        }
+        assert_eq!(fresh_db.get_table("Scores").unwrap().get_checks().len(), 1);
+    }
+
+    #[test]
+    fn rename_column_keeps_check_constraint_working() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        for command in [
+            "CREATE T KEY id FIELDS age: INT CHECK(age >= 0)",
+            "ALTER T RENAME COLUMN age TO years",
+            "INSERT id = 1, years = 7 INTO T",
+        ] {
+            parser
+                .parse_command(&mut db, command)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let result = parser
+            .parse_command(&mut db, "INSERT id = 2, years = -1 INTO T")
+            .unwrap()
+            .execute();
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::TableError(TableError::CheckConstraintViolation(
+                "years >= 0".to_string()
+            ))
+        );
+
+        let rows = run_query(&mut parser, &mut db, "SELECT years FROM T");
+        assert_eq!(rows, vec![vec![Value::INT(7)]]);
+    }
+
+    #[test]
+    fn explain_describes_order_by() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        parser
+            .parse_command(&mut db, "CREATE T KEY id FIELDS age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "EXPLAIN SELECT age FROM T WHERE age > 1 ORDER BY age DESC",
+        );
+
+        assert!(rows.contains(&vec![
+            Value::STRING("Order".into()),
+            Value::STRING("age DESC".into())
+        ]));
+    }
+
+    #[test]
+    fn where_matches_int_column_against_float_literal() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        prepare_people(&mut parser, &mut db);
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name FROM People WHERE Age = 30.0",
+        );
+        assert_eq!(names(rows), vec!["Alice"]);
+
+        let rows = run_query(
+            &mut parser,
+            &mut db,
+            "SELECT Name FROM People WHERE Age > 14.5 AND Age < 31.0",
+        );
+        assert_eq!(names(rows), vec!["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn create_rejects_check_on_unknown_column_or_wrong_type() {
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+
+        for command in [
+            "CREATE Users KEY Id FIELDS Age:INT CHECK(Foo >= 0)",
+            "CREATE Users KEY Id FIELDS Age:INT CHECK(Age >= \"x\")",
+        ] {
+            let result = parser.parse_command(&mut db, command).unwrap().execute();
+            assert!(result.is_err(), "{command} should be rejected");
+        }
+        assert!(db.get_table("Users").is_err());
+
+        parser
+            .parse_command(
+                &mut db,
+                "CREATE Users KEY Id FIELDS Age:INT CHECK(Age >= 0)",
+            )
+            .unwrap()
+            .execute()
+            .unwrap();
+    }
+
+    #[test]
+    fn failing_dry_run_keeps_earlier_lines_in_save_as_history() {
+        use crate::commands::read_from_command::{ReplayMode, replay_commands};
+
+        let mut parser = CommandParser::new();
+        let mut db = Database::<i64>::new();
+        let save_file = "test_failing_dry_run_history.txt";
+
+        let commands = vec![
+            "CREATE Users KEY UserId FIELDS Name:STRING".to_string(),
+            "INSERT UserId=1, Name=\"Alice\" INTO Users".to_string(),
+            "DRY_RUN INSERT UserId=1, Name=\"Again\" INTO Users".to_string(),
+            format!("SAVE_AS {save_file}"),
+        ];
+        let report = replay_commands(
+            &mut db,
+            &mut parser,
+            commands,
+            ReplayMode::ContinueOnError,
+            |_| (),
+        );
+        assert_eq!(report.applied, 3);
+        assert_eq!(report.failures.len(), 1);
+
+        let saved = std::fs::read_to_string(save_file).unwrap();
+        std::fs::remove_file(save_file).unwrap();
+        assert_eq!(
+            saved.lines().collect::<Vec<_>>(),
+            vec![
+                "CREATE Users KEY UserId FIELDS Name:STRING",
+                "INSERT UserId=1, Name=\"Alice\" INTO Users",
+            ]
+        );
+    }
 }
@@ -3,12 +3,16 @@
 #![allow(clippy::missing_panics_doc)]
 #![allow(clippy::cast_precision_loss)]
 #![allow(clippy::cast_sign_loss)]
-use std::io::stdin;
+use std::io::{BufRead, stdin};
 
 use clap::Parser;
 use rustabase::{
-    commands::command::{Command, CommandResult},
-    database::{AnyDatabase, Database, key::DatabaseKey, table::record},
+    commands::{
+        command::CommandResult,
+        read_from_command::{ReplayMode, replay_commands},
+        timed_command::TimedCommand,
+    },
+    database::{AnyDatabase, Database, DatabaseHandler, key::DatabaseKey, table::record},
     parser::CommandParser,
 };
 use thiserror::Error;
@@ -17,6 +21,7 @@ use thiserror::Error;
 enum KeyType {
     String,
     I64,
+    U64,
     Unknown,
 }
 
@@ -36,6 +41,10 @@ impl From<String> for KeyType {
             return KeyType::I64;
         }
 
+        if value == "U64" {
+            return KeyType::U64;
+        }
+
         KeyType::Unknown
     }
 }
@@ -45,10 +54,67 @@ impl From<String> for KeyType {
 struct Args {
     #[arg(short, long)]
     key_type: KeyType,
+
+    #[arg(long)]
+    continue_on_error: bool,
+
+    #[arg(long)]
+    float_precision: Option<usize>,
+
+    #[arg(long)]
+    markdown: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    Plain,
+    Markdown,
+}
+
+#[derive(Clone, Copy)]
+struct ReplSettings {
+    replay_mode: ReplayMode,
+    float_precision: Option<usize>,
+    output_format: OutputFormat,
+    timer: bool,
+}
+
+struct ReplHandler<R: BufRead> {
+    command_parser: CommandParser,
+    input: R,
+    settings: ReplSettings,
+}
+
+impl<R: BufRead> DatabaseHandler for ReplHandler<R> {
+    fn handle<K: DatabaseKey>(&mut self, db: &mut Database<K>) -> bool {
+        handle_user_input(
+            db,
+            &mut self.command_parser,
+            &mut self.input,
+            &mut self.settings,
+        )
+    }
 }
 
 fn main() {
-    let db = match create_db_from_args() {
+    let args = Args::parse();
+
+    let settings = ReplSettings {
+        replay_mode: if args.continue_on_error {
+            ReplayMode::ContinueOnError
+        } else {
+            ReplayMode::StopOnError
+        },
+        float_precision: args.float_precision,
+        output_format: if args.markdown {
+            OutputFormat::Markdown
+        } else {
+            OutputFormat::Plain
+        },
+        timer: false,
+    };
+
+    let db = match create_db_from_args(args) {
         Ok(db) => db,
         Err(e) => {
             println!("{e}");
@@ -56,57 +122,86 @@ fn main() {
         }
     };
 
-    let mut command_parser = CommandParser::new();
+    let mut repl = ReplHandler {
+        command_parser: CommandParser::new(),
+        input: stdin().lock(),
+        settings,
+    };
 
-    match db {
-        AnyDatabase::StringDatabase(mut database) => loop {
-            handle_user_input(&mut database, &mut command_parser);
-        },
-        AnyDatabase::I64Database(mut database) => loop {
-            handle_user_input(&mut database, &mut command_parser);
-        },
-    }
+    db.run_repl(&mut repl);
 }
 
-fn create_db_from_args() -> Result<AnyDatabase, ArgsError> {
-    let args = Args::parse();
-
+fn create_db_from_args(args: Args) -> Result<AnyDatabase, ArgsError> {
     match args.key_type {
         KeyType::String => Ok(AnyDatabase::StringDatabase(Database::<String>::new())),
         KeyType::I64 => Ok(AnyDatabase::I64Database(Database::<i64>::new())),
+        KeyType::U64 => Ok(AnyDatabase::U64Database(Database::<u64>::new())),
         KeyType::Unknown => Err(ArgsError::UnknownKeyTypeError),
     }
 }
 
-fn handle_user_input<K: DatabaseKey>(db: &mut Database<K>, command_parser: &mut CommandParser) {
+fn handle_user_input<K: DatabaseKey>(
+    db: &mut Database<K>,
+    command_parser: &mut CommandParser,
+    input: &mut impl BufRead,
+    settings: &mut ReplSettings,
+) -> bool {
     let mut line = String::new();
-    if let Err(e) = stdin().read_line(&mut line) {
-        println!("{e}");
-        return;
+    match input.read_line(&mut line) {
+        Ok(0) => return false,
+        Ok(_) => (),
+        Err(e) => {
+            println!("{e}");
+            return true;
+        }
     }
     println!("\n\n");
+    if let Some(timer) = parse_timer_meta_command(&line) {
+        settings.timer = timer;
+        println!("Timing is {}.", if timer { "on" } else { "off" });
+        return true;
+    }
     let parse_result = command_parser.parse_command(db, &line);
     let command = match parse_result {
         Err(e) => {
             println!("{e}");
-            return;
+            return true;
         }
         Ok(command) => command,
     };
-    let result = match command.execute() {
+    let (result, metrics) = TimedCommand::new(command).execute_timed();
+    let result = match result {
         Ok(res) => res,
         Err(e) => {
             println!("{e}");
             command_parser.remove_last_saved_line();
-            return;
+            return true;
         }
     };
     match result {
         CommandResult::Void => (),
         CommandResult::RecordValueList(columns, records) => {
-            print_record_value_list(&columns, records);
+            print_record_value_list(&columns, &records, *settings);
+        }
+        CommandResult::CommandList(items) => {
+            execute_command_list(db, command_parser, items, *settings);
         }
-        CommandResult::CommandList(items) => execute_command_list(db, command_parser, items),
+    }
+    if settings.timer {
+        println!(
+            "Time: {:.3} ms, {} records",
+            metrics.elapsed.as_secs_f64() * 1000.0,
+            metrics.records
+        );
+    }
+    true
+}
+
+fn parse_timer_meta_command(line: &str) -> Option<bool> {
+    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["\\timer", "on"] => Some(true),
+        ["\\timer", "off"] => Some(false),
+        _ => None,
     }
 }
 
@@ -114,78 +209,179 @@ fn execute_command_list<K: DatabaseKey>(
     db: &mut Database<K>,
     command_parser: &mut CommandParser,
     items: Vec<String>,
+    settings: ReplSettings,
 ) {
-    for item in items {
-        let parse_result = command_parser.parse_command(db, &item);
-
-        let command = match parse_result {
-            Err(e) => {
-                println!("{e}");
-                break;
-            }
-            Ok(command) => command,
-        };
-
-        let result = match command.execute() {
-            Ok(res) => res,
-            Err(e) => {
-                println!("{e}");
-                command_parser.remove_last_saved_line();
-                break;
-            }
-        };
-
+    let replay_mode = settings.replay_mode;
+    let report = replay_commands(db, command_parser, items, replay_mode, |result| {
         if let CommandResult::RecordValueList(columns, records) = result {
-            print_record_value_list(&columns, records);
+            print_record_value_list(&columns, &records, settings);
         }
+    });
+
+    for failure in &report.failures {
+        println!(
+            "Line {} ({}) failed: {}",
+            failure.line, failure.command, failure.error
+        );
+    }
+
+    if replay_mode == ReplayMode::ContinueOnError {
+        println!(
+            "Applied {} commands, {} failed",
+            report.applied,
+            report.failures.len()
+        );
     }
 }
 
-fn print_record_value_list(columns: &[String], records: Vec<Vec<record::Value>>) {
-    let widths = get_column_widths(columns, &records);
+fn print_record_value_list(
+    columns: &[String],
+    records: &[Vec<record::Value>],
+    settings: ReplSettings,
+) {
+    let rendered = match settings.output_format {
+        OutputFormat::Plain => render_record_value_list(columns, records, settings.float_precision),
+        OutputFormat::Markdown => render_markdown_table(columns, records, settings.float_precision),
+    };
+
+    print!("{rendered}");
+}
+
+fn render_record_value_list(
+    columns: &[String],
+    records: &[Vec<record::Value>],
+    float_precision: Option<usize>,
+) -> String {
+    let widths = get_column_widths(columns, records, float_precision);
+    let mut output = String::new();
 
     for (i, col) in columns.iter().enumerate() {
-        print!("{:width$}  ", col, width = widths[i]);
+        output += &format!("{:width$}  ", col, width = widths[i]);
     }
 
-    println!();
+    output.push('\n');
 
     for w in &widths {
-        print!("{}  ", "-".repeat(*w));
+        output += &format!("{}  ", "-".repeat(*w));
     }
 
-    println!();
+    output.push('\n');
 
     for record in records {
         for (i, val) in record.iter().enumerate() {
-            let val_str = match val {
-                record::Value::BOOL(b) => b.to_string(),
-                record::Value::STRING(s) => s.clone(),
-                record::Value::INT(i) => i.to_string(),
-                record::Value::FLOAT(f) => f.to_string(),
-            };
-
-            print!("{:width$}  ", val_str, width = widths[i]);
+            let val_str = format_value(val, float_precision);
+
+            output += &format!("{:width$}  ", val_str, width = widths[i]);
         }
 
-        println!();
+        output.push('\n');
     }
+
+    output
 }
 
-fn get_column_widths(columns: &[String], records: &Vec<Vec<record::Value>>) -> Vec<usize> {
+fn render_markdown_table(
+    columns: &[String],
+    records: &[Vec<record::Value>],
+    float_precision: Option<usize>,
+) -> String {
+    let widths: Vec<usize> = get_column_widths(columns, records, float_precision)
+        .into_iter()
+        .map(|w| w.max(3))
+        .collect();
+    let mut output = String::new();
+
+    let render_row = |cells: Vec<String>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    output += &render_row(columns.iter().map(|c| c.replace('|', "\\|")).collect());
+    output += &render_row(widths.iter().map(|w| "-".repeat(*w)).collect());
+
+    for record in records {
+        output += &render_row(
+            record
+                .iter()
+                .map(|val| format_value(val, float_precision).replace('|', "\\|"))
+                .collect(),
+        );
+    }
+
+    output
+}
+
+fn format_value(val: &record::Value, float_precision: Option<usize>) -> String {
+    match val {
+        record::Value::BOOL(b) => b.to_string(),
+        record::Value::STRING(s) => s.clone(),
+        record::Value::INT(i) => i.to_string(),
+        record::Value::FLOAT(f) => match float_precision {
+            Some(precision) => format!("{f:.precision$}"),
+            None => f.to_string(),
+        },
+    }
+}
+
+fn get_column_widths(
+    columns: &[String],
+    records: &[Vec<record::Value>],
+    float_precision: Option<usize>,
+) -> Vec<usize> {
     let mut widths = columns.iter().map(String::len).collect::<Vec<usize>>();
 
     for record in records {
         for (i, val) in record.iter().enumerate() {
-            let len = match val {
-                record::Value::BOOL(b) => b.to_string().len(),
-                record::Value::STRING(s) => s.len(),
-                record::Value::INT(i) => i.to_string().len(),
-                record::Value::FLOAT(f) => f.to_string().len(),
-            };
+            let len = format_value(val, float_precision).len();
             widths[i] = widths[i].max(len);
         }
     }
 
     widths
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_meta_command_is_recognized() {
+        assert_eq!(parse_timer_meta_command("\\timer on\n"), Some(true));
+        assert_eq!(parse_timer_meta_command("  \\timer   off "), Some(false));
+        assert_eq!(parse_timer_meta_command("\\timer"), None);
+        assert_eq!(parse_timer_meta_command("SELECT Name FROM Users"), None);
+    }
+
+    #[test]
+    fn float_column_uses_configured_precision() {
+        let columns = vec!["Val".to_string()];
+        let records = vec![vec![record::Value::FLOAT(1.23456)]];
+
+        assert_eq!(format_value(&records[0][0], Some(2)), "1.23");
+        assert_eq!(format_value(&records[0][0], None), "1.23456");
+        assert_eq!(get_column_widths(&columns, &records, Some(2)), vec![4]);
+        assert_eq!(get_column_widths(&columns, &records, None), vec![7]);
+
+        let rendered = render_record_value_list(&columns, &records, Some(2));
+        assert_eq!(rendered, "Val   \n----  \n1.23  \n");
+    }
+
+    #[test]
+    fn markdown_table_uses_pipes_and_header_separator() {
+        let columns = vec!["Name".to_string(), "Id".to_string()];
+        let records = vec![
+            vec![record::Value::STRING("Alice".into()), record::Value::INT(1)],
+            vec![record::Value::STRING("a|b".into()), record::Value::INT(20)],
+        ];
+
+        let rendered = render_markdown_table(&columns, &records, None);
+        assert_eq!(
+            rendered,
+            "| Name  | Id  |\n| ----- | --- |\n| Alice | 1   |\n| a\\|b  | 20  |\n"
+        );
+    }
+}
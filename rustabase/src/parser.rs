@@ -5,16 +5,25 @@ use thiserror::Error;
 
 use crate::{
     commands::{
-        command::AnyCommand,
+        alter_command::{AlterRekeyCommand, AlterRenameColumnCommand, AlterRenameKeyCommand},
+        command::{AnyCommand, CommandError},
+        count_command::CountCommand,
         create_command::CreateCommand,
-        delete_command::DeleteCommand,
+        dedup_command::DedupCommand,
+        delete_command::{DeleteCommand, DeleteWhereCommand},
+        dry_run_command::DryRunCommand,
+        dump_command::DumpCommand,
+        explain_command::ExplainCommand,
         insert_command::InsertCommand,
+        keys_command::KeysCommand,
         read_from_command::ReadFromCommand,
+        rename_command::RenameCommand,
         save_as_command::SaveAsCommand,
         select_command::{
-            And, AnyFilter, AnyWhereFilter, ColumnOperatorFilter, NoOpWhereFilter, Or,
-            SelectCommand, ValueOperatorFilter,
+            And, AnyFilter, AnyWhereFilter, ColumnOperatorFilter, KeyInFilter, NoOpWhereFilter,
+            Operator, Or, SelectCommand, SortDirection, ValueOperatorFilter,
         },
+        select_into_command::SelectIntoCommand,
     },
     database::{
         Database, DatabaseError,
@@ -34,6 +43,9 @@ pub enum ParserError {
     #[error("Missing token: {0}")]
     MissingTokenError(String),
 
+    #[error("Unknown table or alias: {0}")]
+    UnknownQualifierError(String),
+
     #[error("Database error occured: {0}")]
     DatabaseError(#[from] DatabaseError),
 }
@@ -42,6 +54,8 @@ pub enum ParserError {
 #[grammar = "./grammar.pest"]
 struct PestParser {}
 
+type ParsedFields = (Vec<String>, Vec<ColumnType>, Vec<(String, AnyWhereFilter)>);
+
 enum OperatorValue<'a> {
     Value(Value),
     Column(&'a str),
@@ -49,17 +63,27 @@ enum OperatorValue<'a> {
 
 pub struct CommandParser {
     commands_parsed: Vec<String>,
+    // whether the last parsed command added a line, so only that line can be taken back
+    last_line_saved: bool,
 }
 
 impl CommandParser {
     pub fn new() -> Self {
         Self {
             commands_parsed: Vec::new(),
+            last_line_saved: false,
         }
     }
 
     pub fn remove_last_saved_line(&mut self) {
-        self.commands_parsed.pop();
+        if std::mem::take(&mut self.last_line_saved) {
+            self.commands_parsed.pop();
+        }
+    }
+
+    fn save_line(&mut self, line: String) {
+        self.commands_parsed.push(line);
+        self.last_line_saved = true;
     }
 
     pub fn parse_command<'a, K: DatabaseKey>(
@@ -67,6 +91,7 @@ impl CommandParser {
         db: &'a mut Database<K>,
         command: &str,
     ) -> Result<AnyCommand<'a, K>, ParserError> {
+        self.last_line_saved = false;
         let result = PestParser::parse(Rule::command, command);
 
         print_ascii_tree(result.clone());
@@ -81,9 +106,23 @@ impl CommandParser {
                 Rule::create_command => return self.parse_create(&pair, db),
                 Rule::insert_command => return self.parse_insert(&pair, db),
                 Rule::select_query => return self.parse_select(&pair, db),
+                Rule::select_into_query => return self.parse_select_into(&pair, db),
                 Rule::delete_command => return self.parse_delete(&pair, db),
+                Rule::delete_where_command => return self.parse_delete_where(&pair, db),
                 Rule::save_as_command => return self.parse_save_as(&pair),
+                Rule::dump_command => return Self::parse_dump(&pair, db),
                 Rule::read_from_command => return CommandParser::parse_read_from(&pair),
+                Rule::rename_command => return self.parse_rename(&pair, db),
+                Rule::explain_command => return self.parse_explain(&pair, db),
+                Rule::keys_command => return self.parse_keys(&pair, db),
+                Rule::count_command => return self.parse_count(&pair, db),
+                Rule::alter_rename_column_command => {
+                    return self.parse_alter_rename_column(&pair, db);
+                }
+                Rule::alter_rename_key_command => return self.parse_alter_rename_key(&pair, db),
+                Rule::alter_rekey_command => return self.parse_alter_rekey(&pair, db),
+                Rule::dry_run_command => return self.parse_dry_run(&pair, db),
+                Rule::dedup_command => return self.parse_dedup(&pair, db),
                 _ => (),
             }
         }
@@ -100,6 +139,37 @@ impl CommandParser {
         Err(ParserError::MissingTokenError("table_name".into()))
     }
 
+    fn extract_new_table_name(pair: &Pair<'_, Rule>) -> Result<String, ParserError> {
+        for inner_pair in pair.clone().into_inner() {
+            if inner_pair.as_rule() == Rule::new_table_name {
+                return Ok(inner_pair.as_str().to_string());
+            }
+        }
+        Err(ParserError::MissingTokenError("new_table_name".into()))
+    }
+
+    fn extract_column_rename(pair: &Pair<'_, Rule>) -> Result<(String, String), ParserError> {
+        let mut column_name = None;
+        let mut new_column_name = None;
+
+        for inner_pair in pair.clone().into_inner() {
+            match inner_pair.as_rule() {
+                Rule::column_name => column_name = Some(inner_pair.as_str().to_string()),
+                Rule::new_column_name => new_column_name = Some(inner_pair.as_str().to_string()),
+                _ => (),
+            }
+        }
+
+        let Some(column_name) = column_name else {
+            return Err(ParserError::MissingTokenError("column_name".into()));
+        };
+        let Some(new_column_name) = new_column_name else {
+            return Err(ParserError::MissingTokenError("new_column_name".into()));
+        };
+
+        Ok((column_name, new_column_name))
+    }
+
     fn extract_file_name(pair: &Pair<'_, Rule>) -> Result<String, ParserError> {
         for inner_pair in pair.clone().into_inner() {
             if inner_pair.as_rule() == Rule::file_name {
@@ -125,7 +195,7 @@ impl CommandParser {
     ) -> Result<AnyCommand<'a, K>, ParserError> {
         let table_name = Self::extract_table_name(pair)?;
         let key_name = Self::extract_key_name(pair)?;
-        let (fields, types) = Self::parse_field_type_pairs(pair)?;
+        let (fields, types, checks) = Self::parse_field_type_pairs(pair)?;
 
         let command_str = pair.as_str().to_string();
 
@@ -135,36 +205,38 @@ impl CommandParser {
             key_name,
             fields,
             types,
+            checks,
         };
 
-        self.commands_parsed.push(command_str);
+        self.save_line(command_str);
 
         Ok(res.into())
     }
 
-    fn parse_field_type_pairs(
-        pair: &Pair<'_, Rule>,
-    ) -> Result<(Vec<String>, Vec<ColumnType>), ParserError> {
+    fn parse_field_type_pairs(pair: &Pair<'_, Rule>) -> Result<ParsedFields, ParserError> {
         let mut fields = Vec::<String>::new();
         let mut types = Vec::<ColumnType>::new();
+        let mut checks = Vec::<(String, AnyWhereFilter)>::new();
 
         for token in pair.clone().into_inner() {
             if token.as_rule() == Rule::field_type_pair {
-                Self::parse_field_type(&mut fields, &mut types, token)?;
+                Self::parse_field_type(&mut fields, &mut types, &mut checks, token)?;
             }
         }
 
-        Ok((fields, types))
+        Ok((fields, types, checks))
     }
 
     fn parse_field_type(
         fields: &mut Vec<String>,
         types: &mut Vec<ColumnType>,
+        checks: &mut Vec<(String, AnyWhereFilter)>,
         pair: Pair<'_, Rule>,
     ) -> Result<(), ParserError> {
         for field_type_pair in pair.into_inner() {
             match field_type_pair.as_rule() {
                 Rule::field_name => fields.push(field_type_pair.as_str().into()),
+                Rule::check_clause => checks.push(Self::parse_check_clause(field_type_pair)?),
                 Rule::r#type => {
                     for type_pair in field_type_pair.into_inner() {
                         match type_pair.as_rule() {
@@ -183,6 +255,27 @@ impl CommandParser {
         Ok(())
     }
 
+    fn parse_check_clause(pair: Pair<'_, Rule>) -> Result<(String, AnyWhereFilter), ParserError> {
+        let Some(check_token) = pair.into_inner().next() else {
+            return Err(ParserError::MissingTokenError("or_expr".into()));
+        };
+        let description = check_token.as_str().trim().to_string();
+
+        Ok((description, Self::construct_where_filter(check_token)?))
+    }
+
+    pub fn parse_check(source: &str) -> Result<AnyWhereFilter, ParserError> {
+        let mut pairs = match PestParser::parse(Rule::check_source, source) {
+            Ok(pairs) => pairs,
+            Err(err) => return Err(ParserError::Error(err.to_string())),
+        };
+        let Some(check_token) = pairs.next().and_then(|pair| pair.into_inner().next()) else {
+            return Err(ParserError::MissingTokenError("or_expr".into()));
+        };
+
+        Self::construct_where_filter(check_token)
+    }
+
     fn parse_insert<'a, K: DatabaseKey>(
         &mut self,
         pair: &Pair<'_, Rule>,
@@ -201,7 +294,7 @@ impl CommandParser {
             values,
         };
 
-        self.commands_parsed.push(command_str);
+        self.save_line(command_str);
 
         Ok(res.into())
     }
@@ -239,7 +332,7 @@ impl CommandParser {
                 };
                 Ok(Some(Value::FLOAT(value)))
             }
-            Rule::string_value => Ok(Some(Value::STRING(token.as_str().into()))),
+            Rule::string_value => Ok(Some(Value::STRING(unescape_string(token.as_str())))),
             Rule::bool_value => {
                 let value = match token.as_str().trim().parse::<bool>() {
                     Ok(v) => v,
@@ -274,26 +367,153 @@ impl CommandParser {
         &mut self,
         pair: &Pair<'_, Rule>,
         db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let command = Self::build_select(pair, db)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(command.into())
+    }
+
+    fn parse_select_into<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
     ) -> Result<AnyCommand<'a, K>, ParserError> {
         let table_name = Self::extract_table_name(pair)?;
+        let new_table_name = Self::extract_new_table_name(pair)?;
+        Self::validate_qualifiers(pair, &table_name)?;
         let selected_columns = Self::parse_column_names_from_pair(pair)?;
-        let where_filter = Self::parse_where_clause(pair)?;
 
-        let table = db.get_table(&table_name)?;
+        let key_name = db.get_table(&table_name)?.get_key_name().to_string();
+        let where_filter = Self::parse_where_clause(pair, &key_name)?;
 
-        let command_str = pair.as_str().to_string();
-
-        let command = SelectCommand {
-            table,
+        let command = SelectIntoCommand {
+            database: db,
+            table_name,
+            new_table_name,
             selected_columns,
             where_filter,
         };
 
-        self.commands_parsed.push(command_str);
+        self.save_line(pair.as_str().to_string());
 
         Ok(command.into())
     }
 
+    fn parse_explain<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let Some(select_pair) = pair
+            .clone()
+            .into_inner()
+            .find(|token| token.as_rule() == Rule::select_query)
+        else {
+            return Err(ParserError::MissingTokenError("select query".into()));
+        };
+
+        let select = Self::build_select(&select_pair, db)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(ExplainCommand { select }.into())
+    }
+
+    fn parse_dry_run<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let Some(inner_pair) = pair.clone().into_inner().next() else {
+            return Err(ParserError::MissingTokenError("command".into()));
+        };
+
+        let command = match inner_pair.as_rule() {
+            Rule::insert_command => self.parse_insert(&inner_pair, db)?,
+            Rule::delete_command => self.parse_delete(&inner_pair, db)?,
+            Rule::delete_where_command => self.parse_delete_where(&inner_pair, db)?,
+            _ => return Err(ParserError::Error("Unknown command".into())),
+        };
+
+        // a dry run has no side effects, so it stays out of the SAVE_AS history; this also
+        // clears last_line_saved so a failed dry run cannot take back an earlier line
+        self.remove_last_saved_line();
+
+        Ok(DryRunCommand {
+            command: Box::new(command),
+        }
+        .into())
+    }
+
+    fn build_select<'a, K: DatabaseKey>(
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<SelectCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        Self::validate_qualifiers(pair, &table_name)?;
+        let selected_columns = Self::parse_column_names_from_pair(pair)?;
+
+        let table = db.get_table(&table_name)?;
+
+        let where_filter = Self::parse_where_clause(pair, table.get_key_name())?;
+        let order_by = Self::parse_order_by_clause(pair);
+
+        Ok(SelectCommand::new(table, selected_columns, where_filter).with_order_by(order_by))
+    }
+
+    // `u.Name` resolves to `Name`; the qualifier must be the alias if one is
+    // given, the table name otherwise.
+    fn validate_qualifiers(pair: &Pair<'_, Rule>, table_name: &str) -> Result<(), ParserError> {
+        let expected = pair
+            .clone()
+            .into_inner()
+            .find(|token| token.as_rule() == Rule::table_alias)
+            .map_or(table_name, |alias| alias.as_str());
+
+        match pair
+            .clone()
+            .into_inner()
+            .flatten()
+            .find(|token| token.as_rule() == Rule::column_qualifier && token.as_str() != expected)
+        {
+            Some(token) => Err(ParserError::UnknownQualifierError(token.as_str().into())),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_order_by_clause(pair: &Pair<'_, Rule>) -> Vec<(String, SortDirection)> {
+        let Some(order_by_clause) = pair
+            .clone()
+            .into_inner()
+            .find(|token| token.as_rule() == Rule::order_by_clause)
+        else {
+            return Vec::new();
+        };
+
+        order_by_clause
+            .into_inner()
+            .filter(|token| token.as_rule() == Rule::sort_spec)
+            .filter_map(|sort_spec| {
+                let mut column = None;
+                let mut direction = SortDirection::Asc;
+
+                for token in sort_spec.into_inner() {
+                    match token.as_rule() {
+                        Rule::column_name => column = Some(token.as_str().to_string()),
+                        Rule::sort_direction if token.as_str() == "DESC" => {
+                            direction = SortDirection::Desc;
+                        }
+                        _ => (),
+                    }
+                }
+
+                column.map(|column| (column, direction))
+            })
+            .collect()
+    }
+
     fn parse_column_names_from_pair(pair: &Pair<'_, Rule>) -> Result<Vec<String>, ParserError> {
         let mut selected_columns = Vec::<String>::new();
 
@@ -306,10 +526,16 @@ impl CommandParser {
         Ok(selected_columns)
     }
 
-    fn parse_where_clause(pair: &Pair<'_, Rule>) -> Result<AnyWhereFilter, ParserError> {
+    fn parse_where_clause(
+        pair: &Pair<'_, Rule>,
+        key_name: &str,
+    ) -> Result<AnyWhereFilter, ParserError> {
         for token in pair.clone().into_inner() {
             if token.as_rule() == Rule::where_clause {
                 if let Some(where_token) = token.into_inner().next() {
+                    if where_token.as_rule() == Rule::key_in_clause {
+                        return Self::construct_key_in_filter(where_token, key_name);
+                    }
                     return Self::construct_where_filter(where_token);
                 }
                 return Err(ParserError::MissingTokenError("or_expr".into()));
@@ -326,6 +552,7 @@ impl CommandParser {
         for token in column_names_token.into_inner() {
             match token.as_rule() {
                 Rule::column_name => column_names.push(token.as_str().into()),
+                Rule::column_qualifier => (),
                 _ => return Err(ParserError::UnknownRuleError(token.as_str().into())),
             }
         }
@@ -351,14 +578,37 @@ impl CommandParser {
         Ok(result)
     }
 
+    fn construct_key_in_filter(
+        token: Pair<'_, Rule>,
+        key_name: &str,
+    ) -> Result<AnyWhereFilter, ParserError> {
+        let mut keys = Vec::new();
+
+        for key_token in token.into_inner() {
+            match CommandParser::parse_value(&key_token)? {
+                Some(value) => keys.push(value),
+                None => return Err(ParserError::UnknownRuleError(key_token.as_str().into())),
+            }
+        }
+
+        Ok(KeyInFilter {
+            key_column: key_name.into(),
+            keys,
+        }
+        .to_enum())
+    }
+
     fn construct_operator_filter(token: Pair<'_, Rule>) -> Result<AnyWhereFilter, ParserError> {
         let (column_name, op, value_or_column) = Self::parse_operator_components(token)?;
+        let op: Operator = op
+            .parse()
+            .map_err(|e: CommandError| ParserError::Error(e.to_string()))?;
 
         match value_or_column {
             OperatorValue::Value(val) => {
                 let result = ValueOperatorFilter {
                     column_name: column_name.into(),
-                    op: op.into(),
+                    op,
                     value: val,
                 };
                 Ok(result.to_enum())
@@ -366,7 +616,7 @@ impl CommandParser {
             OperatorValue::Column(col2) => {
                 let result = ColumnOperatorFilter {
                     column_name1: column_name.into(),
-                    op: op.into(),
+                    op,
                     column_name2: col2.into(),
                 };
                 Ok(result.to_enum())
@@ -414,6 +664,7 @@ impl CommandParser {
                         }
                     }
                     Rule::op => op = Some(operator_token.as_str()),
+                    Rule::column_qualifier => (),
                     _ => {
                         return Err(ParserError::UnknownRuleError(
                             operator_token.as_str().into(),
@@ -465,7 +716,7 @@ impl CommandParser {
 
         let command_str = pair.as_str().to_string();
 
-        self.commands_parsed.push(command_str);
+        self.save_line(command_str);
 
         Ok(DeleteCommand {
             table,
@@ -474,6 +725,26 @@ impl CommandParser {
         .into())
     }
 
+    fn parse_delete_where<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        Self::validate_qualifiers(pair, &table_name)?;
+
+        let table = db.get_table(&table_name)?;
+        let where_filter = Self::parse_where_clause(pair, table.get_key_name())?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(DeleteWhereCommand {
+            table,
+            where_filter,
+        }
+        .into())
+    }
+
     fn extract_key_as_value<K: DatabaseKey>(pair: &Pair<'_, Rule>) -> Result<K, ParserError> {
         for token in pair.clone().into_inner() {
             if let Some(value) = Self::parse_value(&token)?
@@ -486,6 +757,134 @@ impl CommandParser {
         Err(ParserError::MissingTokenError("key_value".into()))
     }
 
+    fn parse_rename<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        let new_table_name = Self::extract_new_table_name(pair)?;
+
+        let command_str = pair.as_str().to_string();
+
+        self.save_line(command_str);
+
+        Ok(RenameCommand {
+            database: db,
+            table_name,
+            new_table_name,
+        }
+        .into())
+    }
+
+    fn parse_alter_rename_column<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        let (column_name, new_column_name) = Self::extract_column_rename(pair)?;
+
+        let table = db.get_table(&table_name)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(AlterRenameColumnCommand {
+            table,
+            column_name,
+            new_column_name,
+        }
+        .into())
+    }
+
+    fn parse_alter_rename_key<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        let new_key_name = Self::extract_key_name(pair)?;
+
+        let table = db.get_table(&table_name)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(AlterRenameKeyCommand {
+            table,
+            new_key_name,
+        }
+        .into())
+    }
+
+    fn parse_alter_rekey<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        let new_key_name = Self::extract_key_name(pair)?;
+
+        let table = db.get_table(&table_name)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(AlterRekeyCommand {
+            table,
+            new_key_name,
+        }
+        .into())
+    }
+
+    fn parse_dedup<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        Self::validate_qualifiers(pair, &table_name)?;
+        let columns = Self::parse_column_names_from_pair(pair)?;
+
+        let table = db.get_table(&table_name)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(DedupCommand { table, columns }.into())
+    }
+
+    fn parse_keys<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+
+        let table = db.get_table(&table_name)?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(KeysCommand { table }.into())
+    }
+
+    fn parse_count<'a, K: DatabaseKey>(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let table_name = Self::extract_table_name(pair)?;
+        Self::validate_qualifiers(pair, &table_name)?;
+
+        let table = db.get_table(&table_name)?;
+        let where_filter = Self::parse_where_clause(pair, table.get_key_name())?;
+
+        self.save_line(pair.as_str().to_string());
+
+        Ok(CountCommand {
+            table,
+            where_filter,
+        }
+        .into())
+    }
+
     fn parse_save_as<'a, K: DatabaseKey>(
         &'a mut self,
         pair: &Pair<'_, Rule>,
@@ -500,6 +899,19 @@ impl CommandParser {
         Ok(result.into())
     }
 
+    fn parse_dump<'a, K: DatabaseKey>(
+        pair: &Pair<'_, Rule>,
+        db: &'a mut Database<K>,
+    ) -> Result<AnyCommand<'a, K>, ParserError> {
+        let file_name = Self::extract_file_name(pair)?;
+
+        Ok(DumpCommand {
+            database: db,
+            file_name,
+        }
+        .into())
+    }
+
     fn parse_read_from<'a, K: DatabaseKey>(
         pair: &Pair<'_, Rule>,
     ) -> Result<AnyCommand<'a, K>, ParserError> {
@@ -517,6 +929,25 @@ impl Default for CommandParser {
     }
 }
 
+// inverse of the escaping done by DUMP: `\"` and `\\` inside a string literal, any other
+// backslash is kept as is
+fn unescape_string(raw: &str) -> String {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\'
+            && let Some(escaped) = chars.next_if(|next| matches!(next, '"' | '\\'))
+        {
+            result.push(escaped);
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use crate::commands::command::{AnyCommand, Command};
@@ -576,6 +1007,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_create_command_with_check() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+
+        let command_str = "CREATE Users KEY UserId FIELDS Name:STRING, Age:INT CHECK(Age >= 0)";
+
+        let result = parser.parse_command(&mut db, command_str);
+
+        assert!(result.is_ok());
+        match result.unwrap() {
+            AnyCommand::CreateCommand(create_cmd) => {
+                assert_eq!(create_cmd.fields, vec!["Name", "Age"]);
+                assert_eq!(create_cmd.types, vec![ColumnType::STRING, ColumnType::INT]);
+                assert_eq!(create_cmd.checks.len(), 1);
+                assert_eq!(create_cmd.checks[0].0, "Age >= 0");
+            }
+            _ => panic!("Expected CreateCommand"),
+        }
+    }
+
+    #[test]
+    fn parse_check_source() {
+        let check = CommandParser::parse_check("Age >= 0 AND Age < 150").unwrap();
+        assert_eq!(check.describe(), "(Age >= INT 0 AND Age < INT 150)");
+
+        assert!(CommandParser::parse_check("Age >= 0)").is_err());
+        assert!(CommandParser::parse_check("").is_err());
+    }
+
     #[test]
     fn parse_insert_command_basic() {
         let mut parser = prepare_parser();
@@ -606,6 +1067,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_insert_command_escaped_string() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY UserId FIELDS Name: STRING")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let command_str = r#"INSERT UserId=1, Name="a \"b\" \\ c:\d" INTO Users"#;
+
+        match parser.parse_command(&mut db, command_str).unwrap() {
+            AnyCommand::InsertCommand(insert_cmd) => {
+                assert_eq!(
+                    insert_cmd.values[1],
+                    Value::STRING(r#"a "b" \ c:\d"#.into())
+                );
+            }
+            _ => panic!("Expected InsertCommand"),
+        }
+    }
+
     #[test]
     fn parse_select_command_basic() {
         let mut parser = prepare_parser();
@@ -633,6 +1117,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_explain_select_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+
+        let create_str = "CREATE Users KEY UserId FIELDS Name: STRING, Age: INT";
+        parser
+            .parse_command(&mut db, create_str)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let command_str = "EXPLAIN SELECT Name FROM Users WHERE Age > 18 AND Name = \"Bob\"";
+
+        let command = parser.parse_command(&mut db, command_str).unwrap();
+        match command {
+            AnyCommand::ExplainCommand(explain_cmd) => {
+                assert_eq!(explain_cmd.select.selected_columns, vec!["Name"]);
+                assert_eq!(explain_cmd.select.table.get_name(), "Users");
+                assert_eq!(
+                    explain_cmd.select.where_filter.describe(),
+                    "(Age > INT 18 AND Name = STRING Bob)"
+                );
+            }
+            _ => panic!("Expected ExplainCommand"),
+        }
+    }
+
     #[test]
     fn parse_select_command_with_where() {
         let mut parser = prepare_parser();
@@ -829,6 +1341,265 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_rename_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+
+        let command_str = "RENAME Users TO Accounts";
+
+        let result = parser.parse_command(&mut db, command_str);
+
+        assert!(result.is_ok());
+        let command = result.unwrap();
+        match command {
+            AnyCommand::RenameCommand(rename_cmd) => {
+                assert_eq!(rename_cmd.table_name, "Users");
+                assert_eq!(rename_cmd.new_table_name, "Accounts");
+            }
+            _ => panic!("Expected RenameCommand"),
+        }
+    }
+
+    #[test]
+    fn parse_alter_rename_key_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY UserId FIELDS Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        match parser
+            .parse_command(&mut db, "ALTER Users RENAME KEY TO Id")
+            .unwrap()
+        {
+            AnyCommand::AlterRenameKeyCommand(rename_key_cmd) => {
+                assert_eq!(rename_key_cmd.new_key_name, "Id");
+                assert_eq!(rename_key_cmd.table.get_name(), "Users");
+            }
+            _ => panic!("Expected AlterRenameKeyCommand"),
+        }
+    }
+
+    #[test]
+    fn parse_alter_rekey_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY Id FIELDS Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        match parser
+            .parse_command(&mut db, "ALTER Users SET KEY Age")
+            .unwrap()
+        {
+            AnyCommand::AlterRekeyCommand(rekey_cmd) => {
+                assert_eq!(rekey_cmd.new_key_name, "Age");
+                assert_eq!(rekey_cmd.table.get_name(), "Users");
+            }
+            _ => panic!("Expected AlterRekeyCommand"),
+        }
+    }
+
+    #[test]
+    fn parse_dump_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+
+        match parser.parse_command(&mut db, "DUMP TO backup.sql").unwrap() {
+            AnyCommand::DumpCommand(dump_cmd) => assert_eq!(dump_cmd.file_name, "backup.sql"),
+            _ => panic!("Expected DumpCommand"),
+        }
+        assert!(parser.commands_parsed.is_empty());
+    }
+
+    #[test]
+    fn parse_dedup_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY Id FIELDS Name: STRING, Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        match parser
+            .parse_command(&mut db, "DEDUP Users ON (Name, Users.Age)")
+            .unwrap()
+        {
+            AnyCommand::DedupCommand(dedup_cmd) => {
+                assert_eq!(dedup_cmd.columns, vec!["Name", "Age"]);
+                assert_eq!(dedup_cmd.table.get_name(), "Users");
+            }
+            _ => panic!("Expected DedupCommand"),
+        }
+
+        assert!(matches!(
+            parser.parse_command(&mut db, "DEDUP Users ON (Other.Name)"),
+            Err(ParserError::UnknownQualifierError(_))
+        ));
+    }
+
+    #[test]
+    fn parse_select_order_by_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY Id FIELDS Name: STRING, Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let command = parser
+            .parse_command(
+                &mut db,
+                "SELECT Name FROM Users WHERE Age > 1 ORDER BY Age DESC, Name ASC, Id",
+            )
+            .unwrap();
+
+        match command {
+            AnyCommand::SelectCommand(select_cmd) => {
+                assert_eq!(
+                    select_cmd.order_by,
+                    vec![
+                        ("Age".to_string(), SortDirection::Desc),
+                        ("Name".to_string(), SortDirection::Asc),
+                        ("Id".to_string(), SortDirection::Asc),
+                    ]
+                );
+            }
+            _ => panic!("Expected SelectCommand"),
+        }
+    }
+
+    #[test]
+    fn parse_dry_run_insert_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY Id FIELDS Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let command_str = "DRY_RUN INSERT Id=1, Age=20 INTO Users";
+
+        match parser.parse_command(&mut db, command_str).unwrap() {
+            AnyCommand::DryRunCommand(dry_run_cmd) => {
+                assert!(matches!(*dry_run_cmd.command, AnyCommand::InsertCommand(_)));
+            }
+            _ => panic!("Expected DryRunCommand"),
+        }
+        assert_eq!(
+            parser.commands_parsed,
+            vec!["CREATE Users KEY Id FIELDS Age: INT"]
+        );
+    }
+
+    #[test]
+    fn parse_select_with_alias_resolves_qualified_columns() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY Id FIELDS Name: STRING, Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        match parser
+            .parse_command(
+                &mut db,
+                "SELECT u.Name, Age FROM Users u WHERE u.Age > 18 ORDER BY u.Name DESC",
+            )
+            .unwrap()
+        {
+            AnyCommand::SelectCommand(select_cmd) => {
+                assert_eq!(select_cmd.selected_columns, vec!["Name", "Age"]);
+                assert_eq!(select_cmd.where_filter.describe(), "Age > INT 18");
+            }
+            _ => panic!("Expected SelectCommand"),
+        }
+
+        for command_str in [
+            "SELECT x.Name FROM Users u",
+            "SELECT Users.Name FROM Users u",
+            "SELECT Name FROM Users WHERE u.Age > 1",
+        ] {
+            assert!(matches!(
+                parser.parse_command(&mut db, command_str),
+                Err(ParserError::UnknownQualifierError(_))
+            ));
+        }
+        assert!(
+            parser
+                .parse_command(&mut db, "SELECT Users.Name FROM Users WHERE Users.Age > 1")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn parse_alter_rename_column_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+        parser
+            .parse_command(&mut db, "CREATE Users KEY Id FIELDS Age: INT")
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let result = parser.parse_command(&mut db, "ALTER Users RENAME COLUMN Age TO Years");
+
+        match result.unwrap() {
+            AnyCommand::AlterRenameColumnCommand(alter_cmd) => {
+                assert_eq!(alter_cmd.column_name, "Age");
+                assert_eq!(alter_cmd.new_column_name, "Years");
+            }
+            _ => panic!("Expected AlterRenameColumnCommand"),
+        }
+    }
+
+    #[test]
+    fn parse_select_without_table_reports_missing_table() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+
+        for command_str in ["SELECT Name", "SELECT Name, Age FROM"] {
+            let result = parser.parse_command(&mut db, command_str);
+
+            assert!(matches!(
+                result,
+                Err(ParserError::MissingTokenError(token)) if token == "table_name"
+            ));
+        }
+        assert!(parser.commands_parsed.is_empty());
+    }
+
+    #[test]
+    fn parse_keys_command() {
+        let mut parser = prepare_parser();
+        let mut db = prepare_db();
+
+        let create_str = "CREATE Users KEY UserId FIELDS Name: STRING";
+        parser
+            .parse_command(&mut db, create_str)
+            .unwrap()
+            .execute()
+            .unwrap();
+
+        let result = parser.parse_command(&mut db, "KEYS FROM Users");
+
+        match result {
+            Ok(AnyCommand::KeysCommand(keys_cmd)) => {
+                assert_eq!(keys_cmd.table.get_name(), "Users");
+            }
+            _ => panic!("Expected KeysCommand"),
+        }
+    }
+
     #[test]
     fn parse_save_as_command() {
         let mut parser = prepare_parser();